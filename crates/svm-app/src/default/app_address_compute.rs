@@ -2,14 +2,21 @@ use crate::{traits::AppAddressCompute, types::App};
 
 use svm_common::{Address, DefaultKeyHasher, KeyHasher};
 
+use byteorder::{BigEndian, WriteBytesExt};
+
 /// Default implementation for computing an `App` address deterministically.
+///
+/// Computing the app's account address as follows:
+/// Taking `Address::len()` bytes of `HASH(app.template || app.creator || app.nonce)`
+/// (`app.nonce` is laid out in Big-Endian order).
 pub struct DefaultAppAddressCompute;
 
 impl AppAddressCompute for DefaultAppAddressCompute {
     fn compute(app: &App) -> Address {
-        let mut buf = Vec::with_capacity(Address::len() * 2);
+        let mut buf = Vec::with_capacity(Address::len() * 2 + 4);
         buf.extend_from_slice(app.template.as_slice());
         buf.extend_from_slice(app.creator.as_slice());
+        buf.write_u32::<BigEndian>(app.nonce).unwrap();
 
         let hash = DefaultKeyHasher::hash(&buf);
 
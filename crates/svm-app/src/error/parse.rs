@@ -1,6 +1,6 @@
 use std::fmt;
 
-use crate::raw::Field;
+use crate::{raw::Field, types::WasmType};
 
 #[allow(missing_docs)]
 #[derive(PartialEq, Clone)]
@@ -13,6 +13,34 @@ pub enum ParseError {
     NotSupported(Field),
     InvalidUTF8String(Field),
     InvalidProtocolVersion(u32),
+    InvalidPageSize(u32),
+    InvalidMapPages {
+        page_count: u16,
+        map_pages: u16,
+    },
+    FuncNotInAbi(String),
+    InvalidArgsCount {
+        func_name: String,
+        expected: usize,
+        actual: usize,
+    },
+    InvalidArgValueType {
+        func_name: String,
+        arg_idx: usize,
+        expected: WasmType,
+        actual: WasmType,
+    },
+    InvalidBufSlicesCount {
+        func_name: String,
+        expected: usize,
+        actual: usize,
+    },
+    InvalidBufSliceLength {
+        func_name: String,
+        slice_idx: usize,
+        expected: usize,
+        actual: usize,
+    },
 }
 
 impl fmt::Display for ParseError {
@@ -23,6 +51,15 @@ impl fmt::Display for ParseError {
             ParseError::InvalidProtocolVersion(v) => {
                 write!(fmt, "Unsupported protocol version: `{}`", v)
             }
+            ParseError::InvalidPageSize(size) => write!(fmt, "Unsupported page size: `{}`", size),
+            ParseError::InvalidMapPages {
+                page_count,
+                map_pages,
+            } => write!(
+                fmt,
+                "Map pages count `{}` exceeds the pages count `{}`",
+                map_pages, page_count
+            ),
             ParseError::NotSupported(f) => write!(fmt, "Feature `{}` is not supported yet", f),
             ParseError::EmptyList(f) => {
                 write!(fmt, "`{}`-(s) list must contain at least one item", f)
@@ -32,6 +69,47 @@ impl fmt::Display for ParseError {
             }
             ParseError::InvalidWasm => write!(fmt, "Invalid wasm format"),
             ParseError::InvalidArgType(..) => write!(fmt, "Invalid arg type"),
+            ParseError::FuncNotInAbi(func_name) => {
+                write!(fmt, "Function `{}` isn't declared by the ABI", func_name)
+            }
+            ParseError::InvalidArgsCount {
+                func_name,
+                expected,
+                actual,
+            } => write!(
+                fmt,
+                "Function `{}` expects {} args (got {})",
+                func_name, expected, actual
+            ),
+            ParseError::InvalidArgValueType {
+                func_name,
+                arg_idx,
+                expected,
+                actual,
+            } => write!(
+                fmt,
+                "Function `{}` arg #{} should be of type `{:?}` (got `{:?}`)",
+                func_name, arg_idx, expected, actual
+            ),
+            ParseError::InvalidBufSlicesCount {
+                func_name,
+                expected,
+                actual,
+            } => write!(
+                fmt,
+                "Function `{}` expects {} buffer slices (got {})",
+                func_name, expected, actual
+            ),
+            ParseError::InvalidBufSliceLength {
+                func_name,
+                slice_idx,
+                expected,
+                actual,
+            } => write!(
+                fmt,
+                "Function `{}` buffer slice #{} should be of length {} (got {})",
+                func_name, slice_idx, expected, actual
+            ),
         }
     }
 }
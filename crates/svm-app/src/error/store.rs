@@ -1,10 +1,13 @@
 use std::fmt;
 
+use svm_kv::KVError;
+
 #[allow(missing_docs)]
 #[derive(Debug, PartialEq, Clone)]
 pub enum StoreError {
     OsFailure(String),
     DataCorruption(String),
+    AlreadyExists(String),
 }
 
 impl fmt::Display for StoreError {
@@ -12,3 +15,14 @@ impl fmt::Display for StoreError {
         <Self as fmt::Debug>::fmt(self, f)
     }
 }
+
+impl From<KVError> for StoreError {
+    fn from(e: KVError) -> Self {
+        match e {
+            KVError::DataCorruption(msg) => StoreError::DataCorruption(msg),
+            KVError::ReadFailed(..) | KVError::WriteFailed(..) => {
+                StoreError::OsFailure(format!("{}", e))
+            }
+        }
+    }
+}
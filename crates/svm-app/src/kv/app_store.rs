@@ -0,0 +1,73 @@
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+use crate::{
+    error::StoreError,
+    traits::{AppDeserializer, AppSerializer, AppStore},
+    types::App,
+};
+
+use svm_common::Address;
+use svm_kv::traits::KVStore;
+
+use log::{error, info};
+
+/// `AppStore` implementation backed by a (shared) `KVStore`
+pub struct KVAppStore<KV, S, D> {
+    kv: Rc<RefCell<KV>>,
+    _phantom: PhantomData<(S, D)>,
+}
+
+impl<KV, S, D> KVAppStore<KV, S, D>
+where
+    KV: KVStore,
+    S: AppSerializer,
+    D: AppDeserializer,
+{
+    /// New `KVAppStore` instance over `kv`
+    pub fn new(kv: &Rc<RefCell<KV>>) -> Self {
+        Self {
+            kv: Rc::clone(kv),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<KV, S, D> AppStore for KVAppStore<KV, S, D>
+where
+    KV: KVStore,
+    S: AppSerializer,
+    D: AppDeserializer,
+{
+    fn store(&mut self, app: &App, app_addr: &Address) -> Result<(), StoreError> {
+        info!("Storing `App`: \n{:?}", app);
+        info!("     `App` Account Address: {:?}", app_addr);
+
+        let bytes: Vec<u8> = S::serialize(app);
+
+        let entry = (app_addr.as_slice(), &bytes[..]);
+
+        self.kv
+            .borrow_mut()
+            .store(&[entry])
+            .or_else(|e| Err(StoreError::from(e)))
+    }
+
+    fn load(&self, app_addr: &Address) -> Result<Option<App>, StoreError> {
+        info!("loading `App` account {:?}", app_addr);
+
+        let bytes = self.kv.borrow().get(app_addr.as_slice()).or_else(|e| {
+            error!("loading `App` has failed: {}", e);
+            Err(StoreError::from(e))
+        })?;
+
+        match bytes {
+            None => Ok(None),
+            Some(bytes) => D::deserialize(bytes).map(Some).ok_or_else(|| {
+                let msg = format!("`App` data is corrupted (address = `{:?}`)", app_addr);
+                StoreError::DataCorruption(msg)
+            }),
+        }
+    }
+}
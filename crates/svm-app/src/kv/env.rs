@@ -2,20 +2,22 @@ use std::marker::PhantomData;
 
 use crate::{
     default::{DefaultAppAddressCompute, DefaultAppTemplateAddressCompute, DefaultTemplateHasher},
-    rocksdb::{RocksdbAppStore, RocksdbAppTemplateStore},
+    kv::{KVAppStore, KVAppTemplateStore},
     traits::{Env, EnvSerializerTypes, EnvTypes},
 };
 
-pub struct RocksdbEnvTypes<Ser>(PhantomData<Ser>);
+use svm_kv::traits::KVStore;
 
-impl<Ser> EnvTypes for RocksdbEnvTypes<Ser>
+pub struct KVEnvTypes<KV, Ser>(PhantomData<(KV, Ser)>);
+
+impl<KV, Ser> EnvTypes for KVEnvTypes<KV, Ser>
 where
+    KV: KVStore,
     Ser: EnvSerializerTypes,
 {
-    type TemplateStore =
-        RocksdbAppTemplateStore<Ser::TemplateSerializer, Ser::TemplateDeserializer>;
+    type TemplateStore = KVAppTemplateStore<KV, Ser::TemplateSerializer, Ser::TemplateDeserializer>;
 
-    type AppStore = RocksdbAppStore<Ser::AppSerializer, Ser::AppDeserializer>;
+    type AppStore = KVAppStore<KV, Ser::AppSerializer, Ser::AppDeserializer>;
 
     type AppTemplateAddressCompute = DefaultAppTemplateAddressCompute;
 
@@ -24,24 +26,27 @@ where
     type TemplateHasher = DefaultTemplateHasher;
 }
 
-/// AppTemplate environment backed-by `rocksdb`
-pub struct RocksdbEnv<Ser>
+/// AppTemplate environment backed by `KVStore`(s).
+/// Useful for buffering the environment writes (using an `OverlayKV`) or for injecting faults into them (using a `FaultyKV`).
+pub struct KVEnv<KV, Ser>
 where
+    KV: KVStore,
     Ser: EnvSerializerTypes,
 {
-    app_store: <RocksdbEnvTypes<Ser> as EnvTypes>::AppStore,
+    app_store: <KVEnvTypes<KV, Ser> as EnvTypes>::AppStore,
 
-    template_store: <RocksdbEnvTypes<Ser> as EnvTypes>::TemplateStore,
+    template_store: <KVEnvTypes<KV, Ser> as EnvTypes>::TemplateStore,
 }
 
-impl<Ser> RocksdbEnv<Ser>
+impl<KV, Ser> KVEnv<KV, Ser>
 where
+    KV: KVStore,
     Ser: EnvSerializerTypes,
 {
-    /// Creates a new `RocksdbEnv`. Injects externally the `AppTemplateStore`
+    /// Creates a new `KVEnv`. Injects externally the `AppStore` and `AppTemplateStore`
     pub fn new(
-        app_store: <RocksdbEnvTypes<Ser> as EnvTypes>::AppStore,
-        template_store: <RocksdbEnvTypes<Ser> as EnvTypes>::TemplateStore,
+        app_store: <KVEnvTypes<KV, Ser> as EnvTypes>::AppStore,
+        template_store: <KVEnvTypes<KV, Ser> as EnvTypes>::TemplateStore,
     ) -> Self {
         Self {
             app_store,
@@ -50,11 +55,12 @@ where
     }
 }
 
-impl<Ser> Env for RocksdbEnv<Ser>
+impl<KV, Ser> Env for KVEnv<KV, Ser>
 where
+    KV: KVStore,
     Ser: EnvSerializerTypes,
 {
-    type Types = RocksdbEnvTypes<Ser>;
+    type Types = KVEnvTypes<KV, Ser>;
 
     fn get_template_store(&self) -> &<Self::Types as EnvTypes>::TemplateStore {
         &self.template_store
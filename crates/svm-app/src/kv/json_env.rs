@@ -0,0 +1,22 @@
+use crate::{
+    default::DefaultJsonSerializerTypes as Ser,
+    kv::{KVAppStore, KVAppTemplateStore, KVEnv},
+    traits::EnvSerializerTypes,
+};
+
+/// `KVAppStore` with json serialization.
+pub type JsonKVAppStore<KV> = KVAppStore<
+    KV,
+    <Ser as EnvSerializerTypes>::AppSerializer,
+    <Ser as EnvSerializerTypes>::AppDeserializer,
+>;
+
+/// `KVAppTemplateStore` with json serialization.
+pub type JsonKVAppTemplateStore<KV> = KVAppTemplateStore<
+    KV,
+    <Ser as EnvSerializerTypes>::TemplateSerializer,
+    <Ser as EnvSerializerTypes>::TemplateDeserializer,
+>;
+
+/// `KVEnv` with json serialization.
+pub type JsonKVEnv<KV> = KVEnv<KV, Ser>;
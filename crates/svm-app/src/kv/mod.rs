@@ -0,0 +1,9 @@
+mod app_store;
+mod env;
+mod json_env;
+mod template_store;
+
+pub use app_store::KVAppStore;
+pub use env::KVEnv;
+pub use json_env::{JsonKVAppStore, JsonKVAppTemplateStore, JsonKVEnv};
+pub use template_store::KVAppTemplateStore;
@@ -0,0 +1,88 @@
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+use crate::{
+    error::StoreError,
+    traits::{AppTemplateDeserializer, AppTemplateSerializer, AppTemplateStore},
+    types::{AppTemplate, AppTemplateHash},
+};
+
+use svm_common::Address;
+use svm_kv::traits::KVStore;
+
+use log::{error, info};
+
+/// `AppTemplate` store backed by a (shared) `KVStore`
+pub struct KVAppTemplateStore<KV, S, D> {
+    kv: Rc<RefCell<KV>>,
+    _phantom: PhantomData<(S, D)>,
+}
+
+impl<KV, S, D> KVAppTemplateStore<KV, S, D>
+where
+    KV: KVStore,
+    S: AppTemplateSerializer,
+    D: AppTemplateDeserializer,
+{
+    /// New `KVAppTemplateStore` instance over `kv`
+    pub fn new(kv: &Rc<RefCell<KV>>) -> Self {
+        Self {
+            kv: Rc::clone(kv),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<KV, S, D> AppTemplateStore for KVAppTemplateStore<KV, S, D>
+where
+    KV: KVStore,
+    S: AppTemplateSerializer,
+    D: AppTemplateDeserializer,
+{
+    /// Stores both the `Address -> Hash` and `Hash -> AppTemplate` entries as a single batch.
+    fn store(
+        &mut self,
+        template: &AppTemplate,
+        addr: &Address,
+        hash: &AppTemplateHash,
+    ) -> Result<(), StoreError> {
+        info!("Storing `AppTemplate`: \n{:?}", template);
+        info!("     `AppTemplate` Account Address: {:?}", addr);
+        info!("     `AppTemplate` Hash: {:?}", hash);
+
+        let bytes: Vec<u8> = S::serialize(template);
+
+        let addr_hash = (addr.as_slice(), &hash.0[..]);
+        let hash_wasm = (&hash.0[..], &bytes[..]);
+
+        self.kv
+            .borrow_mut()
+            .store(&[addr_hash, hash_wasm])
+            .or_else(|e| Err(StoreError::from(e)))
+    }
+
+    fn load(&self, addr: &Address) -> Result<Option<AppTemplate>, StoreError> {
+        info!("loading `AppTemplate` account {:?}", addr);
+
+        let kv = self.kv.borrow();
+
+        let res = kv.get(addr.as_slice()).and_then(|hash| match hash {
+            Some(hash) => kv.get(&hash),
+            None => Ok(None),
+        });
+
+        let bytes = res.or_else(|e| {
+            error!("loading `AppTemplate` has failed: {}", e);
+            Err(StoreError::from(e))
+        })?;
+
+        match bytes {
+            None => Ok(None),
+            Some(bytes) => D::deserialize(bytes).map(Some).ok_or_else(|| {
+                let msg = format!("`AppTemplate` data is corrupted (address = `{:?}`)", addr);
+                StoreError::DataCorruption(msg)
+            }),
+        }
+    }
+}
@@ -15,6 +15,9 @@ pub mod traits;
 /// Common crate types such as `App`, `AppTemplate` and other related primitives
 pub mod types;
 
+/// `KVStore` backed implementation for `AppTemplateStore`, `AppStore` and `Env`
+pub mod kv;
+
 /// `rocksdb` backed implementation for `AppTemplateStore` and `AppTemplateEnv`
 #[cfg(feature = "default-rocksdb")]
 pub mod rocksdb;
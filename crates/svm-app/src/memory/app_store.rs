@@ -43,9 +43,13 @@ where
         Ok(())
     }
 
-    fn load(&self, app_addr: &Address) -> Option<App> {
-        let bytes = self.app_bytes.get(app_addr);
-
-        bytes.and_then(|bytes| D::deserialize(bytes.to_vec()))
+    fn load(&self, app_addr: &Address) -> Result<Option<App>, StoreError> {
+        match self.app_bytes.get(app_addr) {
+            None => Ok(None),
+            Some(bytes) => D::deserialize(bytes.to_vec()).map(Some).ok_or_else(|| {
+                let msg = format!("`App` data is corrupted (address = `{:?}`)", app_addr);
+                StoreError::DataCorruption(msg)
+            }),
+        }
     }
 }
@@ -51,13 +51,18 @@ where
         Ok(())
     }
 
-    fn load(&self, addr: &Address) -> Option<AppTemplate> {
-        let hash = self.template_hash.get(addr);
-
-        hash.and_then(|h| {
-            self.template_bytes
-                .get(&h)
-                .and_then(|bytes| D::deserialize(bytes.to_vec()))
-        })
+    fn load(&self, addr: &Address) -> Result<Option<AppTemplate>, StoreError> {
+        let bytes = self
+            .template_hash
+            .get(addr)
+            .and_then(|h| self.template_bytes.get(&h));
+
+        match bytes {
+            None => Ok(None),
+            Some(bytes) => D::deserialize(bytes.to_vec()).map(Some).ok_or_else(|| {
+                let msg = format!("`AppTemplate` data is corrupted (address = `{:?}`)", addr);
+                StoreError::DataCorruption(msg)
+            }),
+        }
     }
 }
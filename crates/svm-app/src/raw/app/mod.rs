@@ -4,6 +4,10 @@
 //!  |    version    |        `AppTemplate` `Address`         |
 //!  |    (4 bytes)  |            (20 bytes)                  |
 //!  |_______________|________________________________________|
+//!  |               |                                        |
+//!  |     nonce     |                                        |
+//!  |   (4 bytes)   |                                        |
+//!  |_______________|________________________________________|
 //!  |  ctor-buf  |  ctor-buf  |  ctor-buf  |                 |
 //!  |   #slices  |  slice #1  |  slice #1  |                 |
 //!  |            |   len      |            |     . . . .     |
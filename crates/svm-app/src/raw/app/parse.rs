@@ -18,12 +18,14 @@ pub fn parse_app(bytes: &[u8], creator: &Address) -> Result<SpawnApp, ParseError
     helpers::parse_version(&mut cursor)?;
 
     let template = helpers::parse_address(&mut cursor, Field::AppTemplate)?;
+    let nonce = helpers::read_u32(&mut cursor, Field::Nonce)?;
     let ctor_buf = helpers::parse_func_buf(&mut cursor)?;
     let ctor_args = helpers::parse_func_args(&mut cursor)?;
 
     let app = App {
         template,
         creator: creator.clone(),
+        nonce,
     };
 
     let spawn_app = SpawnApp {
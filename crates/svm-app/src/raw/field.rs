@@ -12,15 +12,27 @@ pub enum Field {
     Dependencies,
     DependenciesCount,
     PageCount,
+    PageSize,
+    MapPages,
+    AbiFuncsCount,
+    AbiFuncNameLength,
+    AbiFuncName,
+    AbiParamsCount,
+    AbiReturnsCount,
+    AbiBufSlicesCount,
+    AbiBufSliceLength,
     Code,
     AppTemplate,
     App,
+    Nonce,
     FuncNameLength,
     FuncName,
     FuncBufSlicesCount,
     FuncBufSliceLength,
     FuncBufSlice,
     FuncArgsCount,
+    AccessListCount,
+    AccessListPage,
     WasmType,
     WasmValue,
 }
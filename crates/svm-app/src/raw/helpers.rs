@@ -24,13 +24,22 @@ pub fn ensure_enough_bytes<T>(res: &std::io::Result<T>, field: Field) -> Result<
 
 #[must_use]
 pub fn parse_version(cursor: &mut Cursor<&[u8]>) -> Result<u32, ParseError> {
+    parse_version_up_to(cursor, 0)
+}
+
+/// Parses the format version. Versions `0..=max_version` are supported.
+#[must_use]
+pub fn parse_version_up_to(
+    cursor: &mut Cursor<&[u8]>,
+    max_version: u32,
+) -> Result<u32, ParseError> {
     let res = cursor.read_u32::<BigEndian>();
 
     ensure_enough_bytes(&res, Field::Version)?;
 
     let version = res.unwrap();
 
-    if version != 0 {
+    if version > max_version {
         return Err(ParseError::InvalidProtocolVersion(version as u32));
     }
 
@@ -87,7 +96,7 @@ pub fn parse_func_args(cursor: &mut Cursor<&[u8]>) -> Result<Vec<WasmValue>, Par
 
 #[must_use]
 fn parse_func_arg(cursor: &mut Cursor<&[u8]>) -> Result<WasmValue, ParseError> {
-    let arg_type = parse_func_arg_type(cursor)?;
+    let arg_type = parse_wasm_type(cursor)?;
 
     let arg = match arg_type {
         WasmType::I32 => {
@@ -104,7 +113,7 @@ fn parse_func_arg(cursor: &mut Cursor<&[u8]>) -> Result<WasmValue, ParseError> {
 }
 
 #[must_use]
-fn parse_func_arg_type(cursor: &mut Cursor<&[u8]>) -> Result<WasmType, ParseError> {
+pub fn parse_wasm_type(cursor: &mut Cursor<&[u8]>) -> Result<WasmType, ParseError> {
     let byte = helpers::read_u8(cursor, Field::WasmType)?;
 
     WasmType::try_from(byte).or_else(|_e| Err(ParseError::InvalidArgType(byte)))
@@ -187,6 +196,19 @@ pub fn write_func_args(args: &Option<Vec<WasmValue>>, buf: &mut Vec<u8>) {
     }
 }
 
+pub fn write_wasm_types(types: &[WasmType], buf: &mut Vec<u8>) {
+    assert!(
+        types.len() <= u8::max_value() as usize,
+        "a function signature can't have more than 255 params (or returns)"
+    );
+
+    buf.write_u8(types.len() as u8).unwrap();
+
+    for ty in types {
+        buf.write_u8((*ty).into()).unwrap();
+    }
+}
+
 pub fn write_func_buf(slices: &Option<Vec<Vec<u8>>>, buf: &mut Vec<u8>) {
     if slices.is_none() {
         buf.write_u8(0).unwrap();
@@ -204,3 +226,18 @@ pub fn write_func_buf(slices: &Option<Vec<Vec<u8>>>, buf: &mut Vec<u8>) {
         buf.extend_from_slice(&slice);
     }
 }
+
+pub fn write_access_list(pages: &Option<Vec<u16>>, buf: &mut Vec<u8>) {
+    // the access-list section is optional
+    if pages.is_none() {
+        return;
+    }
+
+    let pages = pages.as_ref().unwrap();
+
+    buf.write_u16::<BigEndian>(pages.len() as u16).unwrap();
+
+    for page_idx in pages {
+        buf.write_u16::<BigEndian>(*page_idx).unwrap();
+    }
+}
@@ -4,8 +4,8 @@ mod template;
 mod transaction;
 
 pub use app::parse_app;
-pub use template::parse_template;
-pub use transaction::parse_app_tx;
+pub use template::{encode_template, parse_template, TEMPLATE_VERSION};
+pub use transaction::{parse_app_tx, validate_app_tx};
 
 pub use app::{AppJsonDeserializer, AppJsonSerializer};
 pub use template::{AppTemplateJsonDeserializer, AppTemplateJsonSerializer};
@@ -0,0 +1,123 @@
+use byteorder::{BigEndian, WriteBytesExt};
+
+use crate::{
+    error::ParseError,
+    raw::{helpers, Field},
+    types::{AppTemplate, FuncAbi, TemplateAbi},
+};
+
+use super::TEMPLATE_VERSION;
+
+/// Encodes `template` into the most recent raw `deploy-template` format (see `TEMPLATE_VERSION`).
+/// The template `author` isn't part of the raw format (it's the transaction sender).
+///
+/// Returns an error when `template` can't be represented by the raw format,
+/// or when it'd be rejected by `parse_template` (for example, having an invalid page size).
+#[must_use]
+pub fn encode_template(template: &AppTemplate) -> Result<Vec<u8>, ParseError> {
+    validate(template)?;
+
+    let mut buf = Vec::new();
+
+    buf.write_u32::<BigEndian>(TEMPLATE_VERSION).unwrap();
+
+    write_name(&template.name, &mut buf);
+
+    // no admins and no dependencies
+    buf.write_u16::<BigEndian>(0).unwrap();
+    buf.write_u16::<BigEndian>(0).unwrap();
+
+    buf.write_u16::<BigEndian>(template.page_count).unwrap();
+    buf.write_u8(template.page_size.trailing_zeros() as u8)
+        .unwrap();
+    buf.write_u16::<BigEndian>(template.map_pages).unwrap();
+
+    write_abi(&template.abi, &mut buf);
+
+    buf.write_u64::<BigEndian>(template.code.len() as u64)
+        .unwrap();
+    buf.extend_from_slice(&template.code);
+
+    Ok(buf)
+}
+
+#[must_use]
+fn validate(template: &AppTemplate) -> Result<(), ParseError> {
+    validate_len(&template.name, Field::Name, Field::NameLength)?;
+
+    if !svm_common::is_valid_page_size(template.page_size) {
+        return Err(ParseError::InvalidPageSize(template.page_size));
+    }
+
+    if template.map_pages > template.page_count {
+        return Err(ParseError::InvalidMapPages {
+            page_count: template.page_count,
+            map_pages: template.map_pages,
+        });
+    }
+
+    if template.abi.funcs.len() > u16::max_value() as usize {
+        return Err(ParseError::NotSupported(Field::AbiFuncsCount));
+    }
+
+    for func in template.abi.funcs.iter() {
+        validate_func(func)?;
+    }
+
+    Ok(())
+}
+
+#[must_use]
+fn validate_func(func: &FuncAbi) -> Result<(), ParseError> {
+    validate_len(&func.name, Field::AbiFuncName, Field::AbiFuncNameLength)?;
+
+    let limits = [
+        (func.params.len(), Field::AbiParamsCount),
+        (func.returns.len(), Field::AbiReturnsCount),
+        (func.buf_layout.len(), Field::AbiBufSlicesCount),
+    ];
+
+    for (len, field) in limits.iter() {
+        if *len > u8::max_value() as usize {
+            return Err(ParseError::NotSupported(field.clone()));
+        }
+    }
+
+    Ok(())
+}
+
+/// A string field is prefixed by a single byte length, and must not be empty.
+#[must_use]
+fn validate_len(s: &str, field: Field, len_field: Field) -> Result<(), ParseError> {
+    if s.is_empty() {
+        return Err(ParseError::EmptyField(field));
+    }
+
+    if s.len() > u8::max_value() as usize {
+        return Err(ParseError::NotSupported(len_field));
+    }
+
+    Ok(())
+}
+
+fn write_name(name: &str, buf: &mut Vec<u8>) {
+    buf.write_u8(name.len() as u8).unwrap();
+    buf.extend_from_slice(name.as_bytes());
+}
+
+fn write_abi(abi: &TemplateAbi, buf: &mut Vec<u8>) {
+    buf.write_u16::<BigEndian>(abi.funcs.len() as u16).unwrap();
+
+    for func in abi.funcs.iter() {
+        write_name(&func.name, buf);
+
+        helpers::write_wasm_types(&func.params, buf);
+        helpers::write_wasm_types(&func.returns, buf);
+
+        buf.write_u8(func.buf_layout.len() as u8).unwrap();
+
+        for slice_len in func.buf_layout.iter() {
+            buf.write_u16::<BigEndian>(*slice_len).unwrap();
+        }
+    }
+}
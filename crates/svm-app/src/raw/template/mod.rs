@@ -1,4 +1,4 @@
-//!       `AppTemplate` Raw Format Version 0.0.0.0
+//!       `AppTemplate` Raw Format Version 0.0.0.2
 //!  -------------------------------------------------------
 //!  |   format   |                |                       |
 //!  |  version   |  name length   |     name (UTF-8)      |
@@ -13,18 +13,65 @@
 //!  |  (2 bytes)  |              (TBD)                    |
 //!  |_____________|_______________________________________|
 //!  |             |                                       |
-//!  |  #app-pages |            code length                |
-//!  |  (2 bytes)  |             (8 bytes)                 |
-//!  |_____________|_______________________________________|
+//!  |  #app-pages |  page size  |  #map-pages |           |
+//!  |             |   (log2)    |             |    ABI    |
+//!  |  (2 bytes)  |  (1 byte)   |  (2 bytes)  |           |
+//!  |_____________|_____________|_____________|___________|
+//!  |                                                     |
+//!  |                  code length                        |
+//!  |                   (8 bytes)                         |
+//!  |_____________________________________________________|
 //!  |                                                     |
 //!  |           `AppTemplate` code (wasm)                 |
 //!  |_____________________________________________________|
 //!
+//!
+//!                        ABI
+//!  -------------------------------------------------------
+//!  |             |                                       |
+//!  |   #funcs    |       func #1, func #2, ...           |
+//!  |  (2 bytes)  |                                       |
+//!  |_____________|_______________________________________|
+//!
+//!                     ABI func
+//!  -------------------------------------------------------
+//!  |  func name  |                                       |
+//!  |   length    |          func name (UTF-8)            |
+//!  |  (1 byte)   |                                       |
+//!  |_____________|_______________________________________|
+//!  |  #params  |  param #1  |                            |
+//!  |           |    type    |          . . . .           |
+//!  | (1 byte)  |  (1 byte)  |                            |
+//!  |___________|____________|____________________________|
+//!  |  #returns |  return #1 |                            |
+//!  |           |    type    |          . . . .           |
+//!  | (1 byte)  |  (1 byte)  |                            |
+//!  |___________|____________|____________________________|
+//!  |  func-buf  |  func-buf  |                           |
+//!  |   #slices  |  slice #1  |         . . . .           |
+//!  |            |    len     |                           |
+//!  | (1 byte)   | (2 bytes)  |                           |
+//!  |____________|____________|___________________________|
+//!
+//!  The page size (in bytes) is `2 ^ page-size-log2`, and must be allowed by `svm_common::is_valid_page_size`.
+//!  Version `0.0.0.0` templates have no page size field (their pages are of `types::DEFAULT_PAGE_SIZE` bytes).
+//!
+//!  The last `#map-pages` app-pages are dedicated to the app key-value map (zero means the app has no map).
+//!  Templates of versions prior to `0.0.0.2` have no `#map-pages` field (their apps have no map).
+//!
+//!  An ABI with zero functions means the template didn't declare its ABI,
+//!  and `AppTransaction`(s) executed against its apps won't be validated.
+//!
+
+/// The most recent `AppTemplate` raw format version (older versions are parsed as well).
+pub const TEMPLATE_VERSION: u32 = 2;
 
+mod encode;
 mod parse;
 mod serialize;
 mod validate;
 
+pub use encode::encode_template;
 pub use parse::parse_template;
 pub use serialize::{AppTemplateJsonDeserializer, AppTemplateJsonSerializer};
 pub use validate::validate_template;
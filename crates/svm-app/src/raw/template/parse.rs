@@ -3,11 +3,13 @@ use std::io::{Cursor, Read};
 use crate::{
     error::ParseError,
     raw::{helpers, Field},
-    types::AppTemplate,
+    types::{AppTemplate, FuncAbi, TemplateAbi, WasmType, DEFAULT_PAGE_SIZE},
 };
 
 use svm_common::Address;
 
+use super::TEMPLATE_VERSION;
+
 use byteorder::{BigEndian, ReadBytesExt};
 
 /// Returns the parsed raw app-template as `AppTemplate` struct.
@@ -15,18 +17,35 @@ use byteorder::{BigEndian, ReadBytesExt};
 pub fn parse_template(bytes: &[u8], author: &Address) -> Result<AppTemplate, ParseError> {
     let mut cursor = Cursor::new(bytes);
 
-    helpers::parse_version(&mut cursor)?;
+    let version = helpers::parse_version_up_to(&mut cursor, TEMPLATE_VERSION)?;
 
     let name = parse_name(&mut cursor)?;
     let _admins = parse_admins(&mut cursor)?;
     parse_deps(&mut cursor)?;
     let page_count = parse_page_count(&mut cursor)?;
+
+    let page_size = if version >= 1 {
+        parse_page_size(&mut cursor)?
+    } else {
+        DEFAULT_PAGE_SIZE
+    };
+
+    let map_pages = if version >= 2 {
+        parse_map_pages(&mut cursor, page_count)?
+    } else {
+        0
+    };
+
+    let abi = parse_abi(&mut cursor)?;
     let code = parse_code(&mut cursor)?;
 
     let template = AppTemplate {
         name,
         author: author.clone(),
         page_count,
+        page_size,
+        map_pages,
+        abi,
         code,
     };
 
@@ -89,6 +108,105 @@ fn parse_page_count(cursor: &mut Cursor<&[u8]>) -> Result<u16, ParseError> {
     helpers::read_u16(cursor, Field::PageCount)
 }
 
+#[must_use]
+fn parse_page_size(cursor: &mut Cursor<&[u8]>) -> Result<u32, ParseError> {
+    let log2 = helpers::read_u8(cursor, Field::PageSize)? as u32;
+
+    let page_size = 1u32.checked_shl(log2).unwrap_or(0);
+
+    if !svm_common::is_valid_page_size(page_size) {
+        return Err(ParseError::InvalidPageSize(page_size));
+    }
+
+    Ok(page_size)
+}
+
+#[must_use]
+fn parse_map_pages(cursor: &mut Cursor<&[u8]>, page_count: u16) -> Result<u16, ParseError> {
+    let map_pages = helpers::read_u16(cursor, Field::MapPages)?;
+
+    if map_pages > page_count {
+        return Err(ParseError::InvalidMapPages {
+            page_count,
+            map_pages,
+        });
+    }
+
+    Ok(map_pages)
+}
+
+#[must_use]
+fn parse_abi(cursor: &mut Cursor<&[u8]>) -> Result<TemplateAbi, ParseError> {
+    let funcs_count = helpers::read_u16(cursor, Field::AbiFuncsCount)?;
+
+    let mut funcs = Vec::with_capacity(funcs_count as usize);
+
+    for _ in 0..funcs_count {
+        let func = parse_abi_func(cursor)?;
+        funcs.push(func);
+    }
+
+    Ok(TemplateAbi { funcs })
+}
+
+#[must_use]
+fn parse_abi_func(cursor: &mut Cursor<&[u8]>) -> Result<FuncAbi, ParseError> {
+    let name = parse_abi_func_name(cursor)?;
+    let params = parse_abi_types(cursor, Field::AbiParamsCount)?;
+    let returns = parse_abi_types(cursor, Field::AbiReturnsCount)?;
+    let buf_layout = parse_abi_buf_layout(cursor)?;
+
+    let func = FuncAbi {
+        name,
+        params,
+        returns,
+        buf_layout,
+    };
+
+    Ok(func)
+}
+
+#[must_use]
+fn parse_abi_func_name(cursor: &mut Cursor<&[u8]>) -> Result<String, ParseError> {
+    let name_len = helpers::read_u8(cursor, Field::AbiFuncNameLength)? as usize;
+
+    if name_len == 0 {
+        return Err(ParseError::EmptyField(Field::AbiFuncName));
+    }
+
+    let buf = helpers::read_buffer(cursor, name_len, Field::AbiFuncName)?;
+
+    String::from_utf8(buf).or_else(|_e| Err(ParseError::InvalidUTF8String(Field::AbiFuncName)))
+}
+
+#[must_use]
+fn parse_abi_types(cursor: &mut Cursor<&[u8]>, field: Field) -> Result<Vec<WasmType>, ParseError> {
+    let count = helpers::read_u8(cursor, field)?;
+
+    let mut types = Vec::with_capacity(count as usize);
+
+    for _ in 0..count {
+        let ty = helpers::parse_wasm_type(cursor)?;
+        types.push(ty);
+    }
+
+    Ok(types)
+}
+
+#[must_use]
+fn parse_abi_buf_layout(cursor: &mut Cursor<&[u8]>) -> Result<Vec<u16>, ParseError> {
+    let slices_count = helpers::read_u8(cursor, Field::AbiBufSlicesCount)?;
+
+    let mut layout = Vec::with_capacity(slices_count as usize);
+
+    for _ in 0..slices_count {
+        let slice_len = helpers::read_u16(cursor, Field::AbiBufSliceLength)?;
+        layout.push(slice_len);
+    }
+
+    Ok(layout)
+}
+
 #[must_use]
 fn parse_code(cursor: &mut Cursor<&[u8]>) -> Result<Vec<u8>, ParseError> {
     let res = cursor.read_u64::<BigEndian>();
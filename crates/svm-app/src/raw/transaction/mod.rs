@@ -19,8 +19,17 @@
 //!  |           |    type   |   value   |     . . . .     |
 //!  | (1 byte)  | (1 byte)  | (u32/u64) |                 |
 //!  |___________|___________|___________|_________________|
+//!  |  access-list  |   page #1   |                       |
+//!  |    #pages     |    index    |        . . . .        |
+//!  |  (2 bytes)    |  (2 bytes)  |                       |
+//!  |_______________|_____________|_______________________|
+//!
+//!  The access-list section is optional. A transaction ending right after its func-args
+//!  declares no pages (see `AppTransaction::access_list`).
 //!
 
 mod parse;
+mod validate;
 
 pub use parse::parse_app_tx;
+pub use validate::validate_app_tx;
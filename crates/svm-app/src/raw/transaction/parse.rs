@@ -23,6 +23,7 @@ pub fn parse_app_tx(bytes: &[u8], sender: &Address) -> Result<AppTransaction, Pa
     let func_name = parse_func_name(&mut cursor)?;
     let func_buf = helpers::parse_func_buf(&mut cursor)?;
     let func_args = helpers::parse_func_args(&mut cursor)?;
+    let access_list = parse_access_list(&mut cursor)?;
 
     let tx = AppTransaction {
         app,
@@ -30,6 +31,7 @@ pub fn parse_app_tx(bytes: &[u8], sender: &Address) -> Result<AppTransaction, Pa
         func_name,
         func_args,
         func_buf,
+        access_list,
     };
 
     Ok(tx)
@@ -55,3 +57,22 @@ fn parse_func_name(cursor: &mut Cursor<&[u8]>) -> Result<String, ParseError> {
 
     String::from_utf8(buf).or_else(|_e| Err(ParseError::InvalidUTF8String(Field::Name)))
 }
+
+#[must_use]
+fn parse_access_list(cursor: &mut Cursor<&[u8]>) -> Result<Vec<u16>, ParseError> {
+    // the access-list section is optional
+    if cursor.position() as usize == cursor.get_ref().len() {
+        return Ok(Vec::new());
+    }
+
+    let page_count = helpers::read_u16(cursor, Field::AccessListCount)?;
+
+    let mut pages = Vec::with_capacity(page_count as usize);
+
+    for _ in 0..page_count {
+        let page_idx = helpers::read_u16(cursor, Field::AccessListPage)?;
+        pages.push(page_idx);
+    }
+
+    Ok(pages)
+}
@@ -0,0 +1,70 @@
+use crate::{
+    error::ParseError,
+    types::{AppTransaction, TemplateAbi, WasmType, WasmValue},
+};
+
+/// Validates an `AppTransaction` against the ABI of the `AppTemplate` its app has been spawned from.
+/// Should be called right after parsing the raw bytes into `AppTransaction`.
+///
+/// An empty ABI (i.e the template didn't declare any function) validates any transaction.
+#[must_use]
+pub fn validate_app_tx(tx: &AppTransaction, abi: &TemplateAbi) -> Result<(), ParseError> {
+    if abi.is_empty() {
+        return Ok(());
+    }
+
+    let func = abi
+        .get_func(&tx.func_name)
+        .ok_or_else(|| ParseError::FuncNotInAbi(tx.func_name.clone()))?;
+
+    if func.params.len() != tx.func_args.len() {
+        return Err(ParseError::InvalidArgsCount {
+            func_name: tx.func_name.clone(),
+            expected: func.params.len(),
+            actual: tx.func_args.len(),
+        });
+    }
+
+    for (arg_idx, (expected, arg)) in func.params.iter().zip(tx.func_args.iter()).enumerate() {
+        let actual = wasm_value_type(arg);
+
+        if *expected != actual {
+            return Err(ParseError::InvalidArgValueType {
+                func_name: tx.func_name.clone(),
+                arg_idx,
+                expected: *expected,
+                actual,
+            });
+        }
+    }
+
+    if func.buf_layout.len() != tx.func_buf.len() {
+        return Err(ParseError::InvalidBufSlicesCount {
+            func_name: tx.func_name.clone(),
+            expected: func.buf_layout.len(),
+            actual: tx.func_buf.len(),
+        });
+    }
+
+    for (slice_idx, (expected, slice)) in func.buf_layout.iter().zip(tx.func_buf.iter()).enumerate()
+    {
+        if *expected as usize != slice.len() {
+            return Err(ParseError::InvalidBufSliceLength {
+                func_name: tx.func_name.clone(),
+                slice_idx,
+                expected: *expected as usize,
+                actual: slice.len(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[inline]
+fn wasm_value_type(value: &WasmValue) -> WasmType {
+    match value {
+        WasmValue::I32(..) => WasmType::I32,
+        WasmValue::I64(..) => WasmType::I64,
+    }
+}
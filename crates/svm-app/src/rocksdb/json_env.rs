@@ -0,0 +1,20 @@
+use crate::{
+    default::DefaultJsonSerializerTypes as Ser,
+    rocksdb::{RocksdbAppStore, RocksdbAppTemplateStore, RocksdbEnv},
+    traits::EnvSerializerTypes,
+};
+
+/// `RocksdbAppStore` with json serialization.
+pub type JsonRocksdbAppStore = RocksdbAppStore<
+    <Ser as EnvSerializerTypes>::AppSerializer,
+    <Ser as EnvSerializerTypes>::AppDeserializer,
+>;
+
+/// `RocksdbAppTemplateStore` with json serialization.
+pub type JsonRocksdbAppTemplateStore = RocksdbAppTemplateStore<
+    <Ser as EnvSerializerTypes>::TemplateSerializer,
+    <Ser as EnvSerializerTypes>::TemplateDeserializer,
+>;
+
+/// `RocksdbEnv` with json serialization.
+pub type JsonRocksdbEnv = RocksdbEnv<Ser>;
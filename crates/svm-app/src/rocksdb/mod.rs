@@ -1,7 +1,16 @@
-mod app_store;
-mod env;
-mod template_store;
+mod json_env;
 
-pub use app_store::RocksdbAppStore;
-pub use env::RocksdbEnv;
-pub use template_store::RocksdbAppTemplateStore;
+pub use json_env::{JsonRocksdbAppStore, JsonRocksdbAppTemplateStore, JsonRocksdbEnv};
+
+use crate::kv::{KVAppStore, KVAppTemplateStore, KVEnv};
+
+use svm_kv::rocksdb::Rocksdb;
+
+/// `AppStore` implementation backed-by `rocksdb`
+pub type RocksdbAppStore<S, D> = KVAppStore<Rocksdb, S, D>;
+
+/// `AppTemplate` store backed by `rocksdb`
+pub type RocksdbAppTemplateStore<S, D> = KVAppTemplateStore<Rocksdb, S, D>;
+
+/// AppTemplate environment backed-by `rocksdb`
+pub type RocksdbEnv<Ser> = KVEnv<Rocksdb, Ser>;
@@ -11,6 +11,7 @@ use svm_common::Address;
 pub struct AppBuilder {
     version: Option<u32>,
     template: Option<Address>,
+    nonce: Option<u32>,
     ctor_buf: Option<Vec<Vec<u8>>>,
     ctor_args: Option<Vec<WasmValue>>,
 }
@@ -22,6 +23,7 @@ impl AppBuilder {
         Self {
             version: None,
             template: None,
+            nonce: None,
             ctor_buf: None,
             ctor_args: None,
         }
@@ -37,6 +39,11 @@ impl AppBuilder {
         self
     }
 
+    pub fn with_nonce(mut self, nonce: u32) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
     pub fn with_ctor_buf(mut self, ctor_buf: &Vec<Vec<u8>>) -> Self {
         self.ctor_buf = Some(ctor_buf.clone());
         self
@@ -52,6 +59,7 @@ impl AppBuilder {
 
         self.write_version(&mut buf);
         self.write_template(&mut buf);
+        self.write_nonce(&mut buf);
         self.write_ctor_buf(&mut buf);
         self.write_ctor_args(&mut buf);
 
@@ -67,6 +75,12 @@ impl AppBuilder {
         self.write_address(&self.template.as_ref().unwrap(), buf)
     }
 
+    fn write_nonce(&self, buf: &mut Vec<u8>) {
+        // `nonce` defaults to zero when not given
+        let nonce = self.nonce.unwrap_or(0);
+        buf.write_u32::<BigEndian>(nonce).unwrap();
+    }
+
     fn write_address(&self, address: &Address, buf: &mut Vec<u8>) {
         let bytes = address.bytes();
         buf.extend_from_slice(&bytes);
@@ -15,6 +15,7 @@ pub struct AppTxBuilder {
     func_name: Option<String>,
     func_buf: Option<Vec<Vec<u8>>>,
     func_args: Option<Vec<WasmValue>>,
+    access_list: Option<Vec<u16>>,
 }
 
 #[allow(missing_docs)]
@@ -27,6 +28,7 @@ impl AppTxBuilder {
             func_name: None,
             func_buf: None,
             func_args: None,
+            access_list: None,
         }
     }
 
@@ -55,6 +57,11 @@ impl AppTxBuilder {
         self
     }
 
+    pub fn with_access_list(mut self, pages: &[u16]) -> Self {
+        self.access_list = Some(pages.to_vec());
+        self
+    }
+
     pub fn build(&mut self) -> Vec<u8> {
         let mut buf = Vec::new();
 
@@ -64,6 +71,7 @@ impl AppTxBuilder {
 
         helpers::write_func_buf(&self.func_buf, &mut buf);
         helpers::write_func_args(&self.func_args, &mut buf);
+        helpers::write_access_list(&self.access_list, &mut buf);
 
         buf
     }
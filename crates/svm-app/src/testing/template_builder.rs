@@ -1,6 +1,9 @@
 use byteorder::{BigEndian, WriteBytesExt};
 
-use svm_common::Address;
+use crate::{
+    raw::helpers,
+    types::{TemplateAbi, DEFAULT_PAGE_SIZE},
+};
 
 /// Builds a raw representation for `deploy-template`
 /// Should be used for testing only.
@@ -8,6 +11,9 @@ pub struct AppTemplateBuilder {
     version: Option<u32>,
     name: Option<String>,
     page_count: Option<u16>,
+    page_size: Option<u32>,
+    map_pages: Option<u16>,
+    abi: Option<TemplateAbi>,
     code: Option<Vec<u8>>,
 }
 
@@ -19,6 +25,9 @@ impl AppTemplateBuilder {
             version: None,
             name: None,
             page_count: None,
+            page_size: None,
+            map_pages: None,
+            abi: None,
             code: None,
         }
     }
@@ -38,6 +47,21 @@ impl AppTemplateBuilder {
         self
     }
 
+    pub fn with_page_size(mut self, page_size: u32) -> Self {
+        self.page_size = Some(page_size);
+        self
+    }
+
+    pub fn with_map_pages(mut self, map_pages: u16) -> Self {
+        self.map_pages = Some(map_pages);
+        self
+    }
+
+    pub fn with_abi(mut self, abi: &TemplateAbi) -> Self {
+        self.abi = Some(abi.clone());
+        self
+    }
+
     pub fn with_code(mut self, code: &[u8]) -> Self {
         self.code = Some(code.to_vec());
         self
@@ -51,6 +75,9 @@ impl AppTemplateBuilder {
         self.write_admins(&mut buf);
         self.write_deps(&mut buf);
         self.write_page_count(&mut buf);
+        self.write_page_size(&mut buf);
+        self.write_map_pages(&mut buf);
+        self.write_abi(&mut buf);
         self.write_code(&mut buf);
 
         buf
@@ -84,6 +111,59 @@ impl AppTemplateBuilder {
         buf.write_u16::<BigEndian>(pages).unwrap();
     }
 
+    fn write_page_size(&self, buf: &mut Vec<u8>) {
+        let page_size = self.page_size.unwrap_or(DEFAULT_PAGE_SIZE);
+
+        if self.version.unwrap() < 1 {
+            // version `0` templates have no page size field
+            assert_eq!(DEFAULT_PAGE_SIZE, page_size);
+            return;
+        }
+
+        assert!(page_size.is_power_of_two());
+        buf.write_u8(page_size.trailing_zeros() as u8).unwrap();
+    }
+
+    fn write_map_pages(&self, buf: &mut Vec<u8>) {
+        let map_pages = self.map_pages.unwrap_or(0);
+
+        if self.version.unwrap() < 2 {
+            // templates prior to version `2` have no map pages field
+            assert_eq!(0, map_pages);
+            return;
+        }
+
+        buf.write_u16::<BigEndian>(map_pages).unwrap();
+    }
+
+    fn write_abi(&self, buf: &mut Vec<u8>) {
+        if self.abi.is_none() {
+            buf.write_u16::<BigEndian>(0).unwrap();
+            return;
+        }
+
+        let abi = self.abi.as_ref().unwrap();
+
+        buf.write_u16::<BigEndian>(abi.funcs.len() as u16).unwrap();
+
+        for func in abi.funcs.iter() {
+            let name = func.name.as_bytes();
+
+            assert!(name.len() <= 255);
+            buf.write_u8(name.len() as u8).unwrap();
+            buf.extend_from_slice(name);
+
+            helpers::write_wasm_types(&func.params, buf);
+            helpers::write_wasm_types(&func.returns, buf);
+
+            buf.write_u8(func.buf_layout.len() as u8).unwrap();
+
+            for slice_len in func.buf_layout.iter() {
+                buf.write_u16::<BigEndian>(*slice_len).unwrap();
+            }
+        }
+    }
+
     fn write_code(&self, buf: &mut Vec<u8>) {
         let code = self.code.as_ref().unwrap();
 
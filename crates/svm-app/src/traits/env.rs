@@ -4,7 +4,9 @@ use crate::{
         AppAddressCompute, AppDeserializer, AppSerializer, AppStore, AppTemplateAddressCompute,
         AppTemplateDeserializer, AppTemplateHasher, AppTemplateSerializer, AppTemplateStore,
     },
-    types::{App, AppTemplate, AppTemplateHash, AppTransaction, BufferSlice, SpawnApp},
+    types::{
+        App, AppTemplate, AppTemplateHash, AppTransaction, BufferSlice, SpawnApp, TemplateAbi,
+    },
 };
 
 use svm_common::Address;
@@ -104,9 +106,10 @@ pub trait Env {
     }
 
     /// Stores `app address` -> `app-template address` relation.
+    /// Returns `StoreError::AlreadyExists` in case an `App` has already been stored under the derived address.
     #[must_use]
     fn store_app(&mut self, app: &App) -> Result<Address, StoreError> {
-        match self.template_exists(&app.template) {
+        match self.template_exists(&app.template)? {
             false => {
                 // important:
                 // Normally code shuld never execute these piece.
@@ -121,6 +124,14 @@ pub trait Env {
             }
             true => {
                 let addr = self.derive_app_address(&app);
+
+                if self.app_exists(&addr)? {
+                    // the `(template, creator, nonce)` triple has already been used for spawning an app.
+                    // we must not override the existing `App`.
+                    let msg = format!("`App` already exists (address = `{:?}`)", addr);
+                    return Err(StoreError::AlreadyExists(msg));
+                }
+
                 let store = self.get_app_store_mut();
                 store.store(app, &addr)?;
 
@@ -130,24 +141,44 @@ pub trait Env {
     }
 
     /// Given an `App` address, loads the `AppTemplate` the app is associated with.
-    fn load_template_by_app(&self, app_addr: &Address) -> Option<(AppTemplate, Address)> {
-        if let Some(app) = self.load_app(app_addr) {
-            if let Some(template) = self.load_template(&app.template) {
-                return Some((template, app.template));
+    fn load_template_by_app(
+        &self,
+        app_addr: &Address,
+    ) -> Result<Option<(AppTemplate, Address)>, StoreError> {
+        if let Some(app) = self.load_app(app_addr)? {
+            if let Some(template) = self.load_template(&app.template)? {
+                return Ok(Some((template, app.template)));
             }
         }
 
-        None
+        Ok(None)
     }
 
     /// Loads an `AppTemplate` given its `Address`
-    fn load_template(&self, template_addr: &Address) -> Option<AppTemplate> {
+    fn load_template(&self, template_addr: &Address) -> Result<Option<AppTemplate>, StoreError> {
         let store = self.get_template_store();
         store.load(&template_addr)
     }
 
+    /// Loads the ABI of an `AppTemplate` given its `Address`
+    fn load_template_abi(
+        &self,
+        template_addr: &Address,
+    ) -> Result<Option<TemplateAbi>, StoreError> {
+        let template = self.load_template(template_addr)?;
+
+        Ok(template.map(|template| template.abi))
+    }
+
+    /// Given an `App` address, loads the ABI of the `AppTemplate` the app is associated with.
+    fn load_abi_by_app(&self, app_addr: &Address) -> Result<Option<TemplateAbi>, StoreError> {
+        let template = self.load_template_by_app(app_addr)?;
+
+        Ok(template.map(|(template, _addr)| template.abi))
+    }
+
     /// Loads an `App` given its `Address`
-    fn load_app(&self, app_addr: &Address) -> Option<App> {
+    fn load_app(&self, app_addr: &Address) -> Result<Option<App>, StoreError> {
         let store = self.get_app_store();
         store.load(&app_addr)
     }
@@ -167,23 +198,41 @@ pub trait Env {
         let app = self.load_app(&tx.app);
 
         match app {
-            Some(..) => Ok(()),
-            None => {
+            Ok(Some(..)) => Ok(()),
+            Ok(None) => {
                 let err = format!("App `{:?}` doesn't exist", tx.app);
                 Err(err)
             }
+            Err(e) => {
+                let err = format!("Loading App `{:?}` has failed: {}", tx.app, e);
+                Err(err)
+            }
+        }
+    }
+
+    /// Validates an `AppTransaction` func name, args and buffer against its `AppTemplate` ABI.
+    /// In case the app doesn't exist (or can't be loaded) there is nothing to validate against, so we return `Ok`.
+    /// (the `Runtime` will fail the transaction later as part of its execution).
+    fn validate_app_tx_abi(&self, tx: &AppTransaction) -> Result<(), ParseError> {
+        match self.load_abi_by_app(&tx.app) {
+            Ok(Some(abi)) => crate::raw::validate_app_tx(tx, &abi),
+            Ok(None) | Err(..) => Ok(()),
         }
     }
 
     /// Given an `Address`, returns whether it's associated with some `AppTemplate`
     #[inline]
-    fn template_exists(&self, template_addr: &Address) -> bool {
-        self.load_template(template_addr).is_some()
+    fn template_exists(&self, template_addr: &Address) -> Result<bool, StoreError> {
+        let template = self.load_template(template_addr)?;
+
+        Ok(template.is_some())
     }
 
     /// Given an `Address`, returns whether it's associated with some `App`
     #[inline]
-    fn app_exists(&self, app_addr: &Address) -> bool {
-        self.load_app(app_addr).is_some()
+    fn app_exists(&self, app_addr: &Address) -> Result<bool, StoreError> {
+        let app = self.load_app(app_addr)?;
+
+        Ok(app.is_some())
     }
 }
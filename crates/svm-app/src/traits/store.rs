@@ -19,9 +19,10 @@ pub trait AppTemplateStore {
     ) -> Result<(), StoreError>;
 
     /// Given a `AppTemplate` account address, fetches its raw data
-    /// and deserializes it into `AppTemplate`. Returns `None` if `AppTemplatee` doesn't exist.
+    /// and deserializes it into `AppTemplate`. Returns `Ok(None)` if `AppTemplate` doesn't exist.
+    /// Returns an error when reading (or deserializing) the `AppTemplate` data has failed.
     #[must_use]
-    fn load(&self, template_addr: &Address) -> Option<AppTemplate>;
+    fn load(&self, template_addr: &Address) -> Result<Option<AppTemplate>, StoreError>;
 }
 
 /// A persistent store for `App`(s)
@@ -31,7 +32,8 @@ pub trait AppStore {
     fn store(&mut self, app: &App, app_addr: &Address) -> Result<(), StoreError>;
 
     /// Given a `App` account address, fetches its raw data
-    /// and deserializes it into `App`. Returns `None` if `AppTemplatee` doesn't exist.
+    /// and deserializes it into `App`. Returns `Ok(None)` if `App` doesn't exist.
+    /// Returns an error when reading (or deserializing) the `App` data has failed.
     #[must_use]
-    fn load(&self, app_addr: &Address) -> Option<App>;
+    fn load(&self, app_addr: &Address) -> Result<Option<App>, StoreError>;
 }
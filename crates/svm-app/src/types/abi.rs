@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::WasmType;
+
+/// An `AppTemplate` ABI. Lists the functions exported by the template that are callable
+/// by an `AppTransaction`.
+///
+/// An empty ABI means that the template didn't declare its functions.
+/// In that case no `AppTransaction` validation against the ABI takes place.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct TemplateAbi {
+    /// The exported functions
+    pub funcs: Vec<FuncAbi>,
+}
+
+/// An exported function signature as declared by the `AppTemplate` ABI.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FuncAbi {
+    /// Function name
+    pub name: String,
+
+    /// Function params types
+    pub params: Vec<WasmType>,
+
+    /// Function returns types
+    pub returns: Vec<WasmType>,
+
+    /// The expected `func_buf` layout. The `ith` item is the length (in bytes) of the `ith` buffer slice.
+    pub buf_layout: Vec<u16>,
+}
+
+impl TemplateAbi {
+    /// Returns whether the ABI declares no functions.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.funcs.is_empty()
+    }
+
+    /// Looks up function `func_name`.
+    pub fn get_func(&self, func_name: &str) -> Option<&FuncAbi> {
+        self.funcs.iter().find(|f| f.name == func_name)
+    }
+}
@@ -11,14 +11,20 @@ pub struct App {
 
     /// `Address` of app creator
     pub creator: Address,
+
+    /// Creator-chosen nonce. Lets the same creator spawn many apps out of the same `AppTemplate`.
+    /// (apps stored prior to the nonce introduction have a zero nonce).
+    #[serde(default)]
+    pub nonce: u32,
 }
 
 impl fmt::Debug for App {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let template = self.fmt_template(&self.template);
         let creator = self.fmt_creator(&self.creator);
+        let nonce = self.fmt_nonce(self.nonce);
 
-        let msg = [template, creator].join("\n");
+        let msg = [template, creator, nonce].join("\n");
         write!(f, "{}", msg)
     }
 }
@@ -34,6 +40,11 @@ impl App {
         format!("Creator: {}", self.fmt_address(addr))
     }
 
+    #[inline]
+    fn fmt_nonce(&self, nonce: u32) -> String {
+        format!("Nonce: {}", nonce)
+    }
+
     #[inline]
     fn fmt_address(&self, addr: &Address) -> String {
         addr.fmt(4, 4, " ")
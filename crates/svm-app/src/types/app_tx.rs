@@ -21,6 +21,10 @@ pub struct AppTransaction {
 
     /// `App` function args
     pub func_args: Vec<WasmValue>,
+
+    /// The app pages indexes the transaction declares it's going to touch (may be empty).
+    /// The runtime prefetches them before execution. Undeclared pages are still read on demand.
+    pub access_list: Vec<u16>,
 }
 
 impl fmt::Debug for AppTransaction {
@@ -30,8 +34,9 @@ impl fmt::Debug for AppTransaction {
         let func_name = self.fmt_func_name();
         let func_args = self.fmt_func_args();
         let func_buf = self.fmt_func_buf();
+        let access_list = self.fmt_access_list();
 
-        let msg = [app, sender, func_name, func_args, func_buf];
+        let msg = [app, sender, func_name, func_args, func_buf, access_list];
 
         write!(f, "{}", msg.join("\n"))
     }
@@ -73,4 +78,8 @@ impl AppTransaction {
 
         format!("FuncArgs: {}", args_str.join(", "))
     }
+
+    fn fmt_access_list(&self) -> String {
+        format!("AccessList: {:?}", self.access_list)
+    }
 }
@@ -1,3 +1,4 @@
+mod abi;
 mod app;
 mod app_tx;
 mod buffer_slice;
@@ -8,12 +9,13 @@ mod template;
 mod wasm_type;
 mod wasm_value;
 
+pub use abi::{FuncAbi, TemplateAbi};
 pub use app::App;
 pub use app_tx::AppTransaction;
 pub use buffer_slice::BufferSlice;
 pub use hash::AppTemplateHash;
 pub use host_ctx::HostCtx;
 pub use spawn_app::SpawnApp;
-pub use template::AppTemplate;
+pub use template::{AppTemplate, DEFAULT_PAGE_SIZE};
 pub use wasm_type::{WasmConvertTypeError, WasmType};
 pub use wasm_value::WasmValue;
@@ -2,8 +2,13 @@ use std::fmt;
 
 use serde::{Deserialize, Serialize};
 
+use crate::types::TemplateAbi;
+
 use svm_common::Address;
 
+/// The page size (in bytes) of an app-template not declaring its page size.
+pub use svm_common::DEFAULT_PAGE_SIZE;
+
 /// An in-memory representation of an app-template.
 #[allow(missing_docs)]
 #[derive(Serialize, Deserialize, PartialEq)]
@@ -11,6 +16,12 @@ pub struct AppTemplate {
     pub name: String,
     pub author: Address,
     pub page_count: u16,
+    #[serde(default = "default_page_size")]
+    pub page_size: u32,
+    #[serde(default)]
+    pub map_pages: u16,
+    #[serde(default)]
+    pub abi: TemplateAbi,
     pub code: Vec<u8>,
 }
 
@@ -19,9 +30,12 @@ impl fmt::Debug for AppTemplate {
         let name = self.fmt_name(&self.name);
         let author = self.fmt_author(&self.author);
         let pages = self.fmt_page_count(self.page_count);
+        let page_size = self.fmt_page_size(self.page_size);
+        let map_pages = self.fmt_map_pages(self.map_pages);
+        let abi = self.fmt_abi(&self.abi);
         let code = self.fmt_code(&self.code);
 
-        let msg = [author, name, code, pages].join("\n");
+        let msg = [author, name, code, pages, page_size, map_pages, abi].join("\n");
         write!(f, "{}", msg)
     }
 }
@@ -39,7 +53,25 @@ impl AppTemplate {
         format!("#Pages: {:?}", page_count)
     }
 
+    fn fmt_page_size(&self, page_size: u32) -> String {
+        format!("Page Size: {:?}", page_size)
+    }
+
+    fn fmt_map_pages(&self, map_pages: u16) -> String {
+        format!("#Map Pages: {:?}", map_pages)
+    }
+
+    fn fmt_abi(&self, abi: &TemplateAbi) -> String {
+        let funcs: Vec<&str> = abi.funcs.iter().map(|f| f.name.as_str()).collect();
+
+        format!("ABI: {:?}", funcs)
+    }
+
     fn fmt_code(&self, code: &[u8]) -> String {
         format!("Code: {:?}", &code[0..4])
     }
 }
+
+fn default_page_size() -> u32 {
+    DEFAULT_PAGE_SIZE
+}
@@ -1,7 +1,9 @@
 use std::convert::TryFrom;
 
+use serde::{Deserialize, Serialize};
+
 /// `WasmType` - Wasm primitive type.
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
 pub enum WasmType {
     /// Represents a 4-byte integer argument.
     I32,
@@ -1,8 +1,11 @@
 use svm_app::{
+    error::ParseError,
     memory::{JsonMemAppStore, JsonMemAppTemplateStore, JsonMemoryEnv},
     testing::AppTxBuilder,
     traits::Env,
-    types::{App, AppTemplate, AppTransaction, BufferSlice, WasmValue},
+    types::{
+        App, AppTemplate, AppTransaction, BufferSlice, FuncAbi, TemplateAbi, WasmType, WasmValue,
+    },
 };
 use svm_common::Address;
 
@@ -16,6 +19,9 @@ fn parse_app_tx() {
         name: "Template #1".to_string(),
         author: Address::from(0x00_11_22_33),
         page_count: 5,
+        page_size: 4096,
+        map_pages: 0,
+        abi: TemplateAbi::default(),
         code: vec![0x00, 0x00, 0x00],
     };
 
@@ -28,6 +34,7 @@ fn parse_app_tx() {
     let app = App {
         template: template_addr,
         creator: creator_addr,
+        nonce: 0,
     };
 
     assert!(env.store_app(&app).is_ok());
@@ -58,7 +65,239 @@ fn parse_app_tx() {
                 data: vec![0xBB, 0xBB],
             },
         ],
+        access_list: Vec::new(),
     };
 
     assert_eq!(expected, actual);
 }
+
+#[test]
+fn parse_app_tx_with_access_list() {
+    let (env, app_addr) = env_with_app(TemplateAbi::default());
+    let sender = Address::from(0x00_AA_BB_CC);
+
+    let bytes = AppTxBuilder::new()
+        .with_version(0)
+        .with_app(&app_addr)
+        .with_func_name("run")
+        .with_func_args(&[WasmValue::I32(10)])
+        .with_access_list(&[3, 0, 1])
+        .build();
+
+    let tx = env.parse_app_tx(&bytes, &sender).unwrap();
+
+    assert_eq!(vec![3, 0, 1], tx.access_list);
+    assert_eq!(vec![WasmValue::I32(10)], tx.func_args);
+}
+
+#[test]
+fn parse_app_tx_with_empty_access_list() {
+    let (env, app_addr) = env_with_app(TemplateAbi::default());
+    let sender = Address::from(0x00_AA_BB_CC);
+
+    let bytes = AppTxBuilder::new()
+        .with_version(0)
+        .with_app(&app_addr)
+        .with_func_name("run")
+        .with_access_list(&[])
+        .build();
+
+    let tx = env.parse_app_tx(&bytes, &sender).unwrap();
+
+    assert!(tx.access_list.is_empty());
+}
+
+#[test]
+fn parse_app_tx_truncated_access_list() {
+    let (env, app_addr) = env_with_app(TemplateAbi::default());
+    let sender = Address::from(0x00_AA_BB_CC);
+
+    let mut bytes = AppTxBuilder::new()
+        .with_version(0)
+        .with_app(&app_addr)
+        .with_func_name("run")
+        .with_access_list(&[3, 4])
+        .build();
+
+    // dropping the last page index
+    bytes.truncate(bytes.len() - 2);
+
+    let err = env.parse_app_tx(&bytes, &sender).unwrap_err();
+    assert_eq!(
+        "Not enough bytes for field `AccessListPage`",
+        err.to_string()
+    );
+
+    // dropping the first page index and the second byte of the pages count
+    bytes.truncate(bytes.len() - 3);
+
+    let err = env.parse_app_tx(&bytes, &sender).unwrap_err();
+    assert_eq!(
+        "Not enough bytes for field `AccessListCount`",
+        err.to_string()
+    );
+}
+
+fn env_with_app(abi: TemplateAbi) -> (JsonMemoryEnv, Address) {
+    let app_store = JsonMemAppStore::new();
+    let template_store = JsonMemAppTemplateStore::new();
+    let mut env = JsonMemoryEnv::new(app_store, template_store);
+
+    let template = AppTemplate {
+        name: "Template #1".to_string(),
+        author: Address::from(0x00_11_22_33),
+        page_count: 5,
+        page_size: 4096,
+        map_pages: 0,
+        abi,
+        code: vec![0x00, 0x00, 0x00],
+    };
+
+    let template_addr = env.store_template(&template).unwrap();
+
+    let app = App {
+        template: template_addr,
+        creator: Address::from(0x50_60_70_80),
+        nonce: 0,
+    };
+
+    let app_addr = env.store_app(&app).unwrap();
+
+    (env, app_addr)
+}
+
+fn transfer_abi() -> TemplateAbi {
+    TemplateAbi {
+        funcs: vec![FuncAbi {
+            name: "transfer".to_string(),
+            params: vec![WasmType::I64, WasmType::I32],
+            returns: vec![],
+            buf_layout: vec![3],
+        }],
+    }
+}
+
+fn parse_and_validate(
+    env: &JsonMemoryEnv,
+    app_addr: &Address,
+    func_name: &str,
+    func_buf: Vec<Vec<u8>>,
+    func_args: Vec<WasmValue>,
+) -> Result<AppTransaction, ParseError> {
+    let sender = Address::from(0x00_AA_BB_CC);
+
+    let bytes = AppTxBuilder::new()
+        .with_version(0)
+        .with_app(app_addr)
+        .with_func_name(func_name)
+        .with_func_buf(&func_buf)
+        .with_func_args(&func_args)
+        .build();
+
+    let tx = env.parse_app_tx(&bytes, &sender)?;
+    env.validate_app_tx_abi(&tx)?;
+
+    Ok(tx)
+}
+
+#[test]
+fn validate_app_tx_matching_abi() {
+    let (env, app_addr) = env_with_app(transfer_abi());
+
+    let res = parse_and_validate(
+        &env,
+        &app_addr,
+        "transfer",
+        vec![vec![0xAA, 0xBB, 0xCC]],
+        vec![WasmValue::I64(10), WasmValue::I32(20)],
+    );
+
+    assert!(res.is_ok());
+}
+
+#[test]
+fn validate_app_tx_with_empty_abi() {
+    let (env, app_addr) = env_with_app(TemplateAbi::default());
+
+    let res = parse_and_validate(&env, &app_addr, "anything", vec![], vec![WasmValue::I32(1)]);
+
+    assert!(res.is_ok());
+}
+
+#[test]
+fn validate_app_tx_func_not_in_abi() {
+    let (env, app_addr) = env_with_app(transfer_abi());
+
+    let res = parse_and_validate(&env, &app_addr, "mint", vec![], vec![]);
+
+    assert_eq!(Err(ParseError::FuncNotInAbi("mint".to_string())), res);
+}
+
+#[test]
+fn validate_app_tx_invalid_args_count() {
+    let (env, app_addr) = env_with_app(transfer_abi());
+
+    let res = parse_and_validate(
+        &env,
+        &app_addr,
+        "transfer",
+        vec![vec![0xAA, 0xBB, 0xCC]],
+        vec![WasmValue::I64(10)],
+    );
+
+    let expected = ParseError::InvalidArgsCount {
+        func_name: "transfer".to_string(),
+        expected: 2,
+        actual: 1,
+    };
+
+    assert_eq!(Err(expected), res);
+}
+
+#[test]
+fn validate_app_tx_invalid_arg_type() {
+    let (env, app_addr) = env_with_app(transfer_abi());
+
+    let res = parse_and_validate(
+        &env,
+        &app_addr,
+        "transfer",
+        vec![vec![0xAA, 0xBB, 0xCC]],
+        vec![WasmValue::I64(10), WasmValue::I64(20)],
+    );
+
+    let expected = ParseError::InvalidArgValueType {
+        func_name: "transfer".to_string(),
+        arg_idx: 1,
+        expected: WasmType::I32,
+        actual: WasmType::I64,
+    };
+
+    assert_eq!(Err(expected), res);
+}
+
+#[test]
+fn validate_app_tx_invalid_func_buf() {
+    let (env, app_addr) = env_with_app(transfer_abi());
+
+    let args = vec![WasmValue::I64(10), WasmValue::I32(20)];
+
+    let res = parse_and_validate(&env, &app_addr, "transfer", vec![], args.clone());
+
+    let expected = ParseError::InvalidBufSlicesCount {
+        func_name: "transfer".to_string(),
+        expected: 1,
+        actual: 0,
+    };
+    assert_eq!(Err(expected), res);
+
+    let res = parse_and_validate(&env, &app_addr, "transfer", vec![vec![0xAA]], args);
+
+    let expected = ParseError::InvalidBufSliceLength {
+        func_name: "transfer".to_string(),
+        slice_idx: 0,
+        expected: 3,
+        actual: 1,
+    };
+    assert_eq!(Err(expected), res);
+}
@@ -0,0 +1,186 @@
+#![cfg(feature = "memory")]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use svm_app::{
+    error::StoreError,
+    kv::{JsonKVAppStore, JsonKVAppTemplateStore, JsonKVEnv},
+    traits::Env,
+    types::{App, AppTemplate, TemplateAbi},
+};
+use svm_common::Address;
+use svm_kv::{
+    faulty::{Fault, FaultyKV},
+    memory::MemKVStore,
+    traits::KVStore,
+};
+
+type KV = FaultyKV<MemKVStore>;
+
+fn open_env(kv: &Rc<RefCell<KV>>) -> JsonKVEnv<KV> {
+    let app_store = JsonKVAppStore::new(kv);
+    let template_store = JsonKVAppTemplateStore::new(kv);
+
+    JsonKVEnv::new(app_store, template_store)
+}
+
+fn faulty_kv() -> Rc<RefCell<KV>> {
+    Rc::new(RefCell::new(FaultyKV::new(MemKVStore::new())))
+}
+
+fn template() -> AppTemplate {
+    AppTemplate {
+        name: "Template #1".to_string(),
+        author: Address::from(0x00_11_22_33),
+        page_count: 10,
+        page_size: 4096,
+        map_pages: 0,
+        abi: TemplateAbi::default(),
+        code: vec![0x00, 0x00, 0x00],
+    }
+}
+
+#[test]
+fn kv_env_store_template_and_app() {
+    let kv = faulty_kv();
+    let mut env = open_env(&kv);
+
+    let template = template();
+    let template_addr = env.store_template(&template).unwrap();
+
+    let app = App {
+        template: template_addr.clone(),
+        creator: Address::from(0x50_60_70_80),
+        nonce: 0,
+    };
+    let app_addr = env.store_app(&app).unwrap();
+
+    // a fresh env over the same store sees everything
+    let env = open_env(&kv);
+
+    assert_eq!(Ok(Some(app)), env.load_app(&app_addr));
+    assert_eq!(
+        Ok(Some((template, template_addr))),
+        env.load_template_by_app(&app_addr)
+    );
+}
+
+#[test]
+fn kv_env_failed_template_store_leaves_nothing_behind() {
+    let kv = faulty_kv();
+    let mut env = open_env(&kv);
+
+    kv.borrow_mut().inject(0, Fault::Fail);
+
+    let template = template();
+    let addr = env.derive_template_address(&template);
+
+    match env.store_template(&template) {
+        Err(StoreError::OsFailure(..)) => (),
+        res => panic!("expected an `OsFailure` (got `{:?}`)", res),
+    }
+
+    assert_eq!(Ok(None), env.load_template(&addr));
+    assert_eq!(None, kv.borrow().get(addr.as_slice()).unwrap());
+}
+
+#[test]
+fn kv_env_torn_template_store_isnt_loadable() {
+    let kv = faulty_kv();
+    let mut env = open_env(&kv);
+
+    // only the `Address -> Hash` entry makes it
+    kv.borrow_mut().inject(0, Fault::PartialApply(1));
+
+    let template = template();
+    let addr = env.derive_template_address(&template);
+
+    assert!(env.store_template(&template).is_err());
+
+    assert!(kv.borrow().get(addr.as_slice()).unwrap().is_some());
+    assert_eq!(Ok(None), env.load_template(&addr));
+}
+
+#[test]
+fn kv_env_crash_drops_unflushed_app() {
+    let kv = Rc::new(RefCell::new(
+        FaultyKV::new(MemKVStore::new()).with_write_back(),
+    ));
+    let mut env = open_env(&kv);
+
+    let template_addr = env.store_template(&template()).unwrap();
+    kv.borrow_mut().flush().unwrap();
+
+    let app = App {
+        template: template_addr.clone(),
+        creator: Address::from(0x50_60_70_80),
+        nonce: 0,
+    };
+    let app_addr = env.store_app(&app).unwrap();
+    assert_eq!(Ok(Some(app)), env.load_app(&app_addr));
+
+    kv.borrow_mut().crash();
+
+    assert_eq!(Ok(None), env.load_app(&app_addr));
+    assert!(env.load_template(&template_addr).unwrap().is_some());
+}
+
+#[test]
+fn kv_env_failing_reads_are_errors() {
+    let kv = faulty_kv();
+    let mut env = open_env(&kv);
+
+    let template_addr = env.store_template(&template()).unwrap();
+
+    let app = App {
+        template: template_addr.clone(),
+        creator: Address::from(0x50_60_70_80),
+        nonce: 0,
+    };
+    let app_addr = env.store_app(&app).unwrap();
+
+    kv.borrow_mut().fail_reads(true);
+
+    // a read failure isn't reported as a missing `App` / `AppTemplate`
+    match env.load_app(&app_addr) {
+        Err(StoreError::OsFailure(..)) => (),
+        res => panic!("expected an `OsFailure` (got `{:?}`)", res),
+    }
+
+    match env.load_template_by_app(&app_addr) {
+        Err(StoreError::OsFailure(..)) => (),
+        res => panic!("expected an `OsFailure` (got `{:?}`)", res),
+    }
+
+    // and the existing `App` isn't overridden
+    let writes = kv.borrow().writes_count();
+
+    match env.store_app(&app) {
+        Err(StoreError::OsFailure(..)) => (),
+        res => panic!("expected an `OsFailure` (got `{:?}`)", res),
+    }
+
+    assert_eq!(writes, kv.borrow().writes_count());
+
+    kv.borrow_mut().fail_reads(false);
+
+    assert_eq!(Ok(Some(app)), env.load_app(&app_addr));
+}
+
+#[test]
+fn kv_env_corrupted_app_is_an_error() {
+    let kv = faulty_kv();
+    let env = open_env(&kv);
+
+    let app_addr = Address::from(0x10_20_30_40);
+
+    kv.borrow_mut()
+        .store(&[(app_addr.as_slice(), b"not an app")])
+        .unwrap();
+
+    match env.load_app(&app_addr) {
+        Err(StoreError::DataCorruption(..)) => (),
+        res => panic!("expected a `DataCorruption` (got `{:?}`)", res),
+    }
+}
@@ -0,0 +1,154 @@
+#![cfg(feature = "default-rocksdb")]
+
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use svm_app::{
+    error::StoreError,
+    rocksdb::{JsonRocksdbAppStore, JsonRocksdbAppTemplateStore, JsonRocksdbEnv},
+    testing::AppTemplateBuilder,
+    traits::Env,
+    types::{App, AppTemplate, TemplateAbi},
+};
+use svm_common::Address;
+use svm_kv::rocksdb::Rocksdb;
+
+fn db_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("svm-app-{}", name));
+    let _ = std::fs::remove_dir_all(&path);
+
+    path
+}
+
+fn open_env(path: &PathBuf) -> JsonRocksdbEnv {
+    let apps = Rc::new(RefCell::new(Rocksdb::new(path.join("apps"))));
+    let templates = Rc::new(RefCell::new(Rocksdb::new(path.join("templates"))));
+
+    let app_store = JsonRocksdbAppStore::new(&apps);
+    let template_store = JsonRocksdbAppTemplateStore::new(&templates);
+
+    JsonRocksdbEnv::new(app_store, template_store)
+}
+
+fn template() -> AppTemplate {
+    AppTemplate {
+        name: "Template #1".to_string(),
+        author: Address::from(0x00_11_22_33),
+        page_count: 10,
+        page_size: 4096,
+        map_pages: 0,
+        abi: TemplateAbi::default(),
+        code: vec![0x00, 0x00, 0x00],
+    }
+}
+
+#[test]
+fn rocksdb_store_template() {
+    let path = db_path("store-template");
+    let mut env = open_env(&path);
+
+    let code = vec![0xAA, 0xBB, 0xCC, 0xDD];
+    let author = Address::from(0x10_20_30_40);
+
+    let bytes = AppTemplateBuilder::new()
+        .with_version(0)
+        .with_name("Template #1")
+        .with_page_count(10)
+        .with_code(&code)
+        .build();
+
+    let template = env.parse_template(&bytes, &author).unwrap();
+    let expected_addr = env.derive_template_address(&template);
+
+    let actual_addr = env.store_template(&template).unwrap();
+    assert_eq!(expected_addr, actual_addr);
+
+    let actual = env.load_template(&actual_addr).unwrap().unwrap();
+    assert_eq!(template, actual);
+}
+
+#[test]
+fn rocksdb_template_persists_across_reopen() {
+    let path = db_path("template-reopen");
+    let template = template();
+
+    let addr = {
+        let mut env = open_env(&path);
+        env.store_template(&template).unwrap()
+    };
+
+    let env = open_env(&path);
+    assert_eq!(Ok(Some(template)), env.load_template(&addr));
+}
+
+#[test]
+fn rocksdb_app_persists_across_reopen() {
+    let path = db_path("app-reopen");
+    let template = template();
+
+    let (template_addr, app_addr, app) = {
+        let mut env = open_env(&path);
+
+        let template_addr = env.store_template(&template).unwrap();
+
+        let app = App {
+            template: template_addr.clone(),
+            creator: Address::from(0x50_60_70_80),
+            nonce: 0,
+        };
+
+        let app_addr = env.store_app(&app).unwrap();
+
+        (template_addr, app_addr, app)
+    };
+
+    let env = open_env(&path);
+
+    assert_eq!(Ok(Some(app)), env.load_app(&app_addr));
+    assert_eq!(
+        Ok(Some((template, template_addr))),
+        env.load_template_by_app(&app_addr)
+    );
+}
+
+#[test]
+fn rocksdb_app_already_exists_after_reopen() {
+    let path = db_path("app-exists");
+    let template = template();
+
+    let app = {
+        let mut env = open_env(&path);
+
+        let template_addr = env.store_template(&template).unwrap();
+
+        let app = App {
+            template: template_addr,
+            creator: Address::from(0x50_60_70_80),
+            nonce: 1,
+        };
+
+        env.store_app(&app).unwrap();
+
+        app
+    };
+
+    let mut env = open_env(&path);
+    let addr = env.derive_app_address(&app);
+
+    let msg = format!("`App` already exists (address = `{:?}`)", addr);
+    let expected = Err(StoreError::AlreadyExists(msg));
+
+    assert_eq!(expected, env.store_app(&app));
+}
+
+#[test]
+fn rocksdb_load_missing_app_and_template() {
+    let path = db_path("missing");
+    let env = open_env(&path);
+
+    let addr = Address::from(0x10_20_30_40);
+
+    assert_eq!(Ok(None), env.load_app(&addr));
+    assert_eq!(Ok(None), env.load_template(&addr));
+}
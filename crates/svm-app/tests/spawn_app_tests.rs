@@ -1,9 +1,10 @@
 use svm_app::{
     error::StoreError,
     memory::{JsonMemAppStore, JsonMemAppTemplateStore, JsonMemoryEnv},
+    raw::{AppJsonDeserializer, AppJsonSerializer},
     testing::AppBuilder,
-    traits::Env,
-    types::{App, AppTemplate, BufferSlice, SpawnApp, WasmValue},
+    traits::{AppDeserializer, AppSerializer, Env},
+    types::{App, AppTemplate, BufferSlice, SpawnApp, TemplateAbi, WasmValue},
 };
 use svm_common::Address;
 
@@ -19,6 +20,7 @@ fn parse_spawn_app() {
     let bytes = AppBuilder::new()
         .with_version(0)
         .with_template(&template)
+        .with_nonce(7)
         .with_ctor_buf(&vec![vec![0xAA, 0xAA, 0xAA], vec![0xBB, 0xBB]])
         .with_ctor_args(&vec![WasmValue::I32(10), WasmValue::I64(200)])
         .build();
@@ -26,7 +28,11 @@ fn parse_spawn_app() {
     let actual = env.parse_app(&bytes, &creator).unwrap();
 
     let expected = SpawnApp {
-        app: App { template, creator },
+        app: App {
+            template,
+            creator,
+            nonce: 7,
+        },
         ctor_buf: vec![
             BufferSlice {
                 data: vec![0xAA, 0xAA, 0xAA],
@@ -51,6 +57,9 @@ fn valid_app_creation() {
         name: "Template #1".to_string(),
         author: Address::from(0x00_11_22_33),
         page_count: 10,
+        page_size: 4096,
+        map_pages: 0,
+        abi: TemplateAbi::default(),
         code: vec![0x00, 0x00, 0x00],
     };
     assert!(env.store_template(&template).is_ok());
@@ -74,9 +83,85 @@ fn valid_app_creation() {
     let expected = App {
         template: template_addr,
         creator: creator_addr,
+        nonce: 0,
     };
 
-    let actual = env.load_app(&actual_addr).unwrap();
+    let actual = env.load_app(&actual_addr).unwrap().unwrap();
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn same_creator_spawns_many_apps_using_different_nonces() {
+    let app_store = JsonMemAppStore::new();
+    let template_store = JsonMemAppTemplateStore::new();
+    let mut env = JsonMemoryEnv::new(app_store, template_store);
+
+    let template = AppTemplate {
+        name: "Template #1".to_string(),
+        author: Address::from(0x00_11_22_33),
+        page_count: 10,
+        page_size: 4096,
+        map_pages: 0,
+        abi: TemplateAbi::default(),
+        code: vec![0x00, 0x00, 0x00],
+    };
+    assert!(env.store_template(&template).is_ok());
+
+    let template_addr = env.derive_template_address(&template);
+    let creator_addr = Address::from(0x50_60_70_80);
+
+    let app1 = App {
+        template: template_addr.clone(),
+        creator: creator_addr.clone(),
+        nonce: 1,
+    };
+
+    let app2 = App {
+        template: template_addr,
+        creator: creator_addr,
+        nonce: 2,
+    };
+
+    let addr1 = env.store_app(&app1).unwrap();
+    let addr2 = env.store_app(&app2).unwrap();
+    assert_ne!(addr1, addr2);
+
+    assert_eq!(app1, env.load_app(&addr1).unwrap().unwrap());
+    assert_eq!(app2, env.load_app(&addr2).unwrap().unwrap());
+}
+
+#[test]
+fn app_already_exists() {
+    let app_store = JsonMemAppStore::new();
+    let template_store = JsonMemAppTemplateStore::new();
+    let mut env = JsonMemoryEnv::new(app_store, template_store);
+
+    let template = AppTemplate {
+        name: "Template #1".to_string(),
+        author: Address::from(0x00_11_22_33),
+        page_count: 10,
+        page_size: 4096,
+        map_pages: 0,
+        abi: TemplateAbi::default(),
+        code: vec![0x00, 0x00, 0x00],
+    };
+    assert!(env.store_template(&template).is_ok());
+
+    let template_addr = env.derive_template_address(&template);
+    let creator_addr = Address::from(0x50_60_70_80);
+
+    let app = App {
+        template: template_addr,
+        creator: creator_addr,
+        nonce: 1,
+    };
+
+    let addr = env.store_app(&app).unwrap();
+    let actual = env.store_app(&app);
+
+    let msg = format!("`App` already exists (address = `{:?}`)", addr);
+    let expected = Err(StoreError::AlreadyExists(msg));
+
     assert_eq!(expected, actual);
 }
 
@@ -102,3 +187,20 @@ fn app_template_does_not_exist() {
 
     assert_eq!(expected, actual);
 }
+
+#[test]
+fn app_stored_without_nonce_has_zero_nonce() {
+    let app = App {
+        template: Address::from(0x10_20_30_40),
+        creator: Address::from(0x50_60_70_80),
+        nonce: 0,
+    };
+
+    let json = String::from_utf8(AppJsonSerializer::serialize(&app)).unwrap();
+    let json = json.replace(",\"nonce\":0", "");
+    assert!(!json.contains("nonce"));
+
+    let actual = AppJsonDeserializer::deserialize(json.into_bytes()).unwrap();
+
+    assert_eq!(app, actual);
+}
@@ -1,8 +1,13 @@
 use svm_app::{
+    error::ParseError,
     memory::{JsonMemAppStore, JsonMemAppTemplateStore, JsonMemoryEnv},
+    raw::{
+        encode_template, parse_template, AppTemplateJsonDeserializer, AppTemplateJsonSerializer,
+        TEMPLATE_VERSION,
+    },
     testing::AppTemplateBuilder,
-    traits::Env,
-    types::AppTemplate,
+    traits::{AppTemplateDeserializer, AppTemplateSerializer, Env},
+    types::{AppTemplate, FuncAbi, TemplateAbi, WasmType, DEFAULT_PAGE_SIZE},
 };
 use svm_common::Address;
 
@@ -34,11 +39,335 @@ fn store_template() {
         name: name.to_string(),
         author,
         page_count,
+        page_size: 4096,
+        map_pages: 0,
+        abi: TemplateAbi::default(),
         code,
     };
 
     let addr = env.derive_template_address(&template);
-    let actual = env.load_template(&addr).unwrap();
+    let actual = env.load_template(&addr).unwrap().unwrap();
 
     assert_eq!(expected, actual);
 }
+
+#[test]
+fn store_template_with_abi() {
+    let app_store = JsonMemAppStore::new();
+    let template_store = JsonMemAppTemplateStore::new();
+    let mut env = JsonMemoryEnv::new(app_store, template_store);
+
+    let author = Address::from(0x10_20_30_40);
+
+    let abi = TemplateAbi {
+        funcs: vec![
+            FuncAbi {
+                name: "ctor".to_string(),
+                params: vec![],
+                returns: vec![],
+                buf_layout: vec![],
+            },
+            FuncAbi {
+                name: "transfer".to_string(),
+                params: vec![WasmType::I64, WasmType::I32],
+                returns: vec![WasmType::I32],
+                buf_layout: vec![20],
+            },
+        ],
+    };
+
+    let bytes = AppTemplateBuilder::new()
+        .with_version(0)
+        .with_name("Template #1")
+        .with_page_count(10)
+        .with_abi(&abi)
+        .with_code(&[0xAA, 0xBB, 0xCC, 0xDD])
+        .build();
+
+    let template = env.parse_template(&bytes, &author).unwrap();
+    assert_eq!(abi, template.abi);
+
+    let addr = env.store_template(&template).unwrap();
+    assert_eq!(Ok(Some(abi)), env.load_template_abi(&addr));
+}
+
+#[test]
+fn template_declaring_page_size() {
+    let app_store = JsonMemAppStore::new();
+    let template_store = JsonMemAppTemplateStore::new();
+    let mut env = JsonMemoryEnv::new(app_store, template_store);
+
+    let author = Address::from(0x10_20_30_40);
+
+    let bytes = AppTemplateBuilder::new()
+        .with_version(1)
+        .with_name("Template #1")
+        .with_page_count(10)
+        .with_page_size(512)
+        .with_code(&[0xAA, 0xBB, 0xCC, 0xDD])
+        .build();
+
+    let template = env.parse_template(&bytes, &author).unwrap();
+    assert_eq!(512, template.page_size);
+
+    let addr = env.store_template(&template).unwrap();
+    assert_eq!(512, env.load_template(&addr).unwrap().unwrap().page_size);
+}
+
+#[test]
+fn template_declaring_unsupported_page_size() {
+    let env = JsonMemoryEnv::new(JsonMemAppStore::new(), JsonMemAppTemplateStore::new());
+
+    let bytes = AppTemplateBuilder::new()
+        .with_version(1)
+        .with_name("Template #1")
+        .with_page_count(10)
+        .with_page_size(128)
+        .with_code(&[0xAA, 0xBB, 0xCC, 0xDD])
+        .build();
+
+    let author = Address::from(0x10_20_30_40);
+
+    assert_eq!(
+        Err(ParseError::InvalidPageSize(128)),
+        env.parse_template(&bytes, &author)
+    );
+}
+
+#[test]
+fn template_version_0_has_default_page_size() {
+    let env = JsonMemoryEnv::new(JsonMemAppStore::new(), JsonMemAppTemplateStore::new());
+
+    let bytes = AppTemplateBuilder::new()
+        .with_version(0)
+        .with_name("Template #1")
+        .with_page_count(10)
+        .with_code(&[0xAA, 0xBB, 0xCC, 0xDD])
+        .build();
+
+    let author = Address::from(0x10_20_30_40);
+    let template = env.parse_template(&bytes, &author).unwrap();
+
+    assert_eq!(DEFAULT_PAGE_SIZE, template.page_size);
+}
+
+#[test]
+fn template_of_unsupported_version() {
+    let env = JsonMemoryEnv::new(JsonMemAppStore::new(), JsonMemAppTemplateStore::new());
+
+    let bytes = AppTemplateBuilder::new()
+        .with_version(TEMPLATE_VERSION + 1)
+        .with_name("Template #1")
+        .with_page_count(10)
+        .with_code(&[0xAA, 0xBB, 0xCC, 0xDD])
+        .build();
+
+    let author = Address::from(0x10_20_30_40);
+
+    assert_eq!(
+        Err(ParseError::InvalidProtocolVersion(TEMPLATE_VERSION + 1)),
+        env.parse_template(&bytes, &author)
+    );
+}
+
+#[test]
+fn template_stored_without_page_size_has_default_page_size_and_no_map() {
+    let template = AppTemplate {
+        name: "Template #1".to_string(),
+        author: Address::from(0x10_20_30_40),
+        page_count: 10,
+        page_size: 4096,
+        map_pages: 0,
+        abi: TemplateAbi::default(),
+        code: vec![0xAA, 0xBB, 0xCC, 0xDD],
+    };
+
+    let json = String::from_utf8(AppTemplateJsonSerializer::serialize(&template)).unwrap();
+    let json = json
+        .replace(",\"page_size\":4096", "")
+        .replace(",\"map_pages\":0", "");
+    assert!(!json.contains("page_size"));
+    assert!(!json.contains("map_pages"));
+
+    let actual = AppTemplateJsonDeserializer::deserialize(json.into_bytes()).unwrap();
+
+    assert_eq!(template, actual);
+}
+
+#[test]
+fn template_declaring_map_pages() {
+    let env = JsonMemoryEnv::new(JsonMemAppStore::new(), JsonMemAppTemplateStore::new());
+    let author = Address::from(0x10_20_30_40);
+
+    let bytes = AppTemplateBuilder::new()
+        .with_version(2)
+        .with_name("Template #1")
+        .with_page_count(10)
+        .with_map_pages(4)
+        .with_code(&[0xAA, 0xBB, 0xCC, 0xDD])
+        .build();
+
+    let template = env.parse_template(&bytes, &author).unwrap();
+    assert_eq!(4, template.map_pages);
+
+    let bytes = AppTemplateBuilder::new()
+        .with_version(2)
+        .with_name("Template #1")
+        .with_page_count(10)
+        .with_map_pages(11)
+        .with_code(&[0xAA, 0xBB, 0xCC, 0xDD])
+        .build();
+
+    let expected = ParseError::InvalidMapPages {
+        page_count: 10,
+        map_pages: 11,
+    };
+    assert_eq!(Err(expected), env.parse_template(&bytes, &author));
+}
+
+#[test]
+fn template_version_1_has_no_map() {
+    let env = JsonMemoryEnv::new(JsonMemAppStore::new(), JsonMemAppTemplateStore::new());
+
+    let bytes = AppTemplateBuilder::new()
+        .with_version(1)
+        .with_name("Template #1")
+        .with_page_count(10)
+        .with_page_size(1024)
+        .with_code(&[0xAA, 0xBB, 0xCC, 0xDD])
+        .build();
+
+    let author = Address::from(0x10_20_30_40);
+    let template = env.parse_template(&bytes, &author).unwrap();
+
+    assert_eq!(1024, template.page_size);
+    assert_eq!(0, template.map_pages);
+}
+
+#[test]
+fn template_stored_without_abi_has_no_abi() {
+    let template = AppTemplate {
+        name: "Template #1".to_string(),
+        author: Address::from(0x10_20_30_40),
+        page_count: 10,
+        page_size: 4096,
+        map_pages: 0,
+        abi: TemplateAbi::default(),
+        code: vec![0xAA, 0xBB, 0xCC, 0xDD],
+    };
+
+    let json = String::from_utf8(AppTemplateJsonSerializer::serialize(&template)).unwrap();
+    let json = json.replace(",\"abi\":{\"funcs\":[]}", "");
+    assert!(!json.contains("abi"));
+
+    let actual = AppTemplateJsonDeserializer::deserialize(json.into_bytes()).unwrap();
+
+    assert_eq!(template, actual);
+}
+
+#[test]
+#[should_panic(expected = "a function signature can't have more than 255 params (or returns)")]
+fn template_abi_func_of_too_many_params() {
+    let abi = TemplateAbi {
+        funcs: vec![FuncAbi {
+            name: "run".to_string(),
+            params: vec![WasmType::I32; 256],
+            returns: vec![],
+            buf_layout: vec![],
+        }],
+    };
+
+    AppTemplateBuilder::new()
+        .with_version(0)
+        .with_name("Template #1")
+        .with_page_count(10)
+        .with_abi(&abi)
+        .with_code(&[0xAA, 0xBB, 0xCC, 0xDD])
+        .build();
+}
+
+#[test]
+fn encode_template_round_trip() {
+    let template = AppTemplate {
+        name: "Template #1".to_string(),
+        author: Address::from(0x10_20_30_40),
+        page_count: 10,
+        page_size: 512,
+        map_pages: 2,
+        abi: TemplateAbi {
+            funcs: vec![FuncAbi {
+                name: "run".to_string(),
+                params: vec![WasmType::I32, WasmType::I64],
+                returns: vec![WasmType::I32],
+                buf_layout: vec![3],
+            }],
+        },
+        code: vec![0xAA, 0xBB, 0xCC, 0xDD],
+    };
+
+    let bytes = encode_template(&template).unwrap();
+    let actual = parse_template(&bytes, &template.author).unwrap();
+
+    assert_eq!(template, actual);
+}
+
+fn encodable_template() -> AppTemplate {
+    AppTemplate {
+        name: "Template #1".to_string(),
+        author: Address::from(0x10_20_30_40),
+        page_count: 10,
+        page_size: DEFAULT_PAGE_SIZE,
+        map_pages: 0,
+        abi: TemplateAbi::default(),
+        code: vec![0xAA, 0xBB, 0xCC, 0xDD],
+    }
+}
+
+#[test]
+fn encode_template_rejects_what_parsing_would() {
+    let invalid = AppTemplate {
+        page_size: 1000,
+        ..encodable_template()
+    };
+    assert_eq!(
+        Err(ParseError::InvalidPageSize(1000)),
+        encode_template(&invalid)
+    );
+
+    let invalid = AppTemplate {
+        map_pages: 11,
+        ..encodable_template()
+    };
+    assert_eq!(
+        Err(ParseError::InvalidMapPages {
+            page_count: 10,
+            map_pages: 11
+        }),
+        encode_template(&invalid)
+    );
+
+    let invalid = AppTemplate {
+        name: "".to_string(),
+        ..encodable_template()
+    };
+    match encode_template(&invalid) {
+        Err(ParseError::EmptyField(..)) => (),
+        res => panic!("expected an `EmptyField` (got `{:?}`)", res),
+    }
+
+    let invalid = AppTemplate {
+        abi: TemplateAbi {
+            funcs: vec![FuncAbi {
+                name: "run".to_string(),
+                params: vec![WasmType::I32; 256],
+                returns: vec![],
+                buf_layout: vec![],
+            }],
+        },
+        ..encodable_template()
+    };
+    match encode_template(&invalid) {
+        Err(ParseError::NotSupported(..)) => (),
+        res => panic!("expected a `NotSupported` (got `{:?}`)", res),
+    }
+}
@@ -0,0 +1,147 @@
+use clap::{App, AppSettings, Arg, SubCommand};
+
+/// Builds the `svm-cli` command-line arguments definition.
+///
+/// ```text
+/// svm-cli [--db <path>] deploy <template.wasm|template.wast> [--name] [--page-count] [--page-size] [--map-pages] [--abi] [--author]
+/// svm-cli [--db <path>] spawn <json> [--creator]
+/// svm-cli [--db <path>] exec <json> --state <state> [--sender]
+/// svm-cli [--db <path>] storage dump <app> <state>
+/// svm-cli [--db <path>] storage fsck <app> <state>
+/// svm-cli receipt decode <receipt>
+/// ```
+///
+/// Each invocation runs a single command, so the state is kept between invocations by the `rocksdb` database.
+///
+/// A `<json>` argument is either an inline JSON or a path to a JSON file.
+/// (see `svm_runtime_c_api::json` for the expected JSON formats).
+pub fn build_app() -> App<'static, 'static> {
+    App::new("svm-cli")
+        .about("Crafts, runs and inspects SVM transactions")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .arg(
+            Arg::with_name("db")
+                .long("db")
+                .takes_value(true)
+                .default_value("svm-db")
+                .global(true)
+                .help("Path of the `rocksdb` database"),
+        )
+        .subcommand(deploy_cmd())
+        .subcommand(spawn_cmd())
+        .subcommand(exec_cmd())
+        .subcommand(storage_cmd())
+        .subcommand(receipt_cmd())
+}
+
+fn deploy_cmd() -> App<'static, 'static> {
+    SubCommand::with_name("deploy")
+        .about("Deploys a template (`.wasm` or `.wast`)")
+        .arg(
+            Arg::with_name("file")
+                .required(true)
+                .help("The template code file"),
+        )
+        .arg(
+            Arg::with_name("name")
+                .long("name")
+                .takes_value(true)
+                .help("The template name (defaults to the file name)"),
+        )
+        .arg(
+            Arg::with_name("page-count")
+                .long("page-count")
+                .takes_value(true)
+                .default_value("10")
+                .help("The number of storage pages of each spawned app"),
+        )
+        .arg(
+            Arg::with_name("page-size")
+                .long("page-size")
+                .takes_value(true)
+                .default_value("4096")
+                .help("The size (in bytes) of each storage page (a power of two)"),
+        )
+        .arg(
+            Arg::with_name("map-pages")
+                .long("map-pages")
+                .takes_value(true)
+                .default_value("0")
+                .help("The number of (last) storage pages dedicated to the app key-value map"),
+        )
+        .arg(
+            Arg::with_name("abi")
+                .long("abi")
+                .takes_value(true)
+                .help("The template ABI (JSON)"),
+        )
+        .arg(address_arg("author"))
+}
+
+fn spawn_cmd() -> App<'static, 'static> {
+    SubCommand::with_name("spawn")
+        .about("Spawns an app out of a deployed template")
+        .arg(json_arg())
+        .arg(address_arg("creator"))
+}
+
+fn exec_cmd() -> App<'static, 'static> {
+    SubCommand::with_name("exec")
+        .about("Executes an app transaction and prints its receipt")
+        .arg(json_arg())
+        .arg(
+            Arg::with_name("state")
+                .long("state")
+                .takes_value(true)
+                .required(true)
+                .help("The app current state"),
+        )
+        .arg(address_arg("sender"))
+}
+
+fn storage_cmd() -> App<'static, 'static> {
+    SubCommand::with_name("storage")
+        .about("Inspects an app storage")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(
+            SubCommand::with_name("dump")
+                .about("Prints the non-empty pages of an app storage at a given state")
+                .arg(Arg::with_name("app").required(true).help("The app address"))
+                .arg(Arg::with_name("state").required(true).help("The app state")),
+        )
+        .subcommand(
+            SubCommand::with_name("fsck")
+                .about("Verifies an app storage at a given state against its hashes")
+                .arg(Arg::with_name("app").required(true).help("The app address"))
+                .arg(Arg::with_name("state").required(true).help("The app state")),
+        )
+}
+
+fn receipt_cmd() -> App<'static, 'static> {
+    SubCommand::with_name("receipt")
+        .about("Inspects receipts")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(
+            SubCommand::with_name("decode")
+                .about("Decodes a hex-encoded binary receipt into JSON")
+                .arg(
+                    Arg::with_name("receipt")
+                        .required(true)
+                        .help("The hex-encoded receipt"),
+                ),
+        )
+}
+
+fn json_arg() -> Arg<'static, 'static> {
+    Arg::with_name("json")
+        .required(true)
+        .help("An inline JSON or a path to a JSON file")
+}
+
+fn address_arg(name: &'static str) -> Arg<'static, 'static> {
+    Arg::with_name(name)
+        .long(name)
+        .takes_value(true)
+        .default_value("0x0000000000000000000000000000000000000000")
+        .help("An address (hex-encoded)")
+}
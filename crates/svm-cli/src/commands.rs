@@ -0,0 +1,213 @@
+use std::fs;
+use std::path::Path;
+
+use svm_app::{
+    raw::encode_template,
+    traits::Env,
+    types::{AppTemplate, HostCtx, TemplateAbi},
+};
+use svm_common::{Address, State};
+use svm_runtime::{
+    error::DeployTemplateError, settings::AppSettings, traits::Runtime, DefaultRuntime,
+};
+use svm_runtime_c_api::{encode_receipt, json};
+use svm_storage::{fsck::FsckReport, page::PageIndex};
+
+use crate::CliError;
+
+/// Deploys `template` (authored by `template.author`) and returns the template `Address`.
+pub fn deploy<ENV: Env>(
+    runtime: &mut DefaultRuntime<ENV>,
+    template: &AppTemplate,
+) -> Result<Address, CliError> {
+    let bytes = encode_template(template)
+        .or_else(|e| Err(CliError::DeployFailed(DeployTemplateError::ParseFailed(e))))?;
+
+    runtime
+        .deploy_template(&template.author, HostCtx::new(), &bytes)
+        .or_else(|e| Err(CliError::DeployFailed(e)))
+}
+
+/// Spawns an app described by a JSON (see `json::encode_spawn_app`).
+/// Returns the app `Address` and its initial `State`.
+pub fn spawn<ENV: Env>(
+    runtime: &mut DefaultRuntime<ENV>,
+    creator: &Address,
+    json: &str,
+) -> Result<(Address, State), CliError> {
+    let bytes = json::encode_spawn_app(json).or_else(|e| Err(CliError::Json(e)))?;
+
+    runtime
+        .spawn_app(creator, HostCtx::new(), &bytes)
+        .or_else(|e| Err(CliError::SpawnFailed(e)))
+}
+
+/// Executes an app transaction described by a JSON (see `json::encode_app_tx`)
+/// on top of app state `state`. Returns the receipt as JSON.
+pub fn exec<ENV: Env>(
+    runtime: &mut DefaultRuntime<ENV>,
+    sender: &Address,
+    state: &State,
+    json: &str,
+) -> Result<serde_json::Value, CliError> {
+    let bytes = json::encode_app_tx(json).or_else(|e| Err(CliError::Json(e)))?;
+
+    let tx = runtime
+        .parse_exec_app(sender, &bytes)
+        .or_else(|e| Err(CliError::ExecFailed(e)))?;
+
+    let receipt = runtime
+        .exec_app(tx, state.clone(), HostCtx::new())
+        .or_else(|e| Err(CliError::ExecFailed(e)))?;
+
+    let bytes = encode_receipt(&receipt);
+
+    receipt_decode(&bytes)
+}
+
+/// Returns the non-empty pages of app `app_addr` storage at state `state`.
+pub fn storage_dump<ENV: Env>(
+    runtime: &DefaultRuntime<ENV>,
+    app_addr: &Address,
+    state: &State,
+) -> Result<Vec<(PageIndex, Vec<u8>)>, CliError> {
+    let template = load_app_template(runtime, app_addr)?;
+
+    let settings = AppSettings {
+        page_count: template.page_count,
+        page_size: template.page_size,
+        map_pages: template.map_pages,
+    };
+
+    let mut storage = runtime.open_app_storage(app_addr, state, &settings);
+
+    let mut pages = Vec::new();
+
+    for page_idx in 0..template.page_count {
+        let page_idx = PageIndex(page_idx);
+
+        if let Some(page) = storage.read_page(page_idx) {
+            if page.iter().any(|byte| *byte != 0) {
+                pages.push((page_idx, page));
+            }
+        }
+    }
+
+    Ok(pages)
+}
+
+/// Checks the integrity of app `app_addr` storage at state `state` (see `svm_storage::fsck`).
+pub fn storage_fsck<ENV: Env>(
+    runtime: &DefaultRuntime<ENV>,
+    app_addr: &Address,
+    state: &State,
+) -> Result<FsckReport, CliError> {
+    let template = load_app_template(runtime, app_addr)?;
+
+    let settings = AppSettings {
+        page_count: template.page_count,
+        page_size: template.page_size,
+        map_pages: template.map_pages,
+    };
+
+    let storage = runtime.open_app_storage(app_addr, state, &settings);
+
+    storage
+        .check_integrity()
+        .or_else(|e| Err(CliError::StorageFailed(format!("{}", e))))
+}
+
+/// Decodes a binary receipt into JSON.
+pub fn receipt_decode(bytes: &[u8]) -> Result<serde_json::Value, CliError> {
+    json::decode_receipt(bytes).or_else(|e| Err(CliError::Json(e)))
+}
+
+/// Reads a template code file. A `.wast` (or `.wat`) file is compiled into wasm first.
+pub fn read_code(path: &Path) -> Result<Vec<u8>, CliError> {
+    let bytes = fs::read(path).or_else(|e| Err(CliError::Io(format!("{}", e))))?;
+
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("wast") | Some("wat") => wabt::wat2wasm(&bytes).or_else(|e| {
+            Err(CliError::InvalidArg {
+                name: "file",
+                reason: format!("{:?}", e),
+            })
+        }),
+        _ => Ok(bytes),
+    }
+}
+
+/// Parses a template ABI given as JSON.
+pub fn parse_abi(json: &str) -> Result<TemplateAbi, CliError> {
+    serde_json::from_str(json).or_else(|e| {
+        Err(CliError::InvalidArg {
+            name: "abi",
+            reason: format!("{}", e),
+        })
+    })
+}
+
+/// Returns `arg` when it's an inline JSON. Otherwise treats `arg` as a path and returns the file content.
+pub fn read_json(arg: &str) -> Result<String, CliError> {
+    if arg.trim_start().starts_with('{') {
+        Ok(arg.to_string())
+    } else {
+        fs::read_to_string(arg).or_else(|e| Err(CliError::Io(format!("{}", e))))
+    }
+}
+
+/// Parses a hex-encoded `Address`.
+pub fn parse_address(s: &str, name: &'static str) -> Result<Address, CliError> {
+    let bytes = parse_hex(s, name)?;
+    ensure_len(&bytes, name, Address::len())?;
+
+    Ok(Address::from(&bytes[..]))
+}
+
+/// Parses a hex-encoded `State`.
+pub fn parse_state(s: &str, name: &'static str) -> Result<State, CliError> {
+    let bytes = parse_hex(s, name)?;
+    ensure_len(&bytes, name, State::len())?;
+
+    Ok(State::from(&bytes[..]))
+}
+
+/// Decodes a hex string (an optional `0x` prefix is allowed).
+pub fn parse_hex(s: &str, name: &'static str) -> Result<Vec<u8>, CliError> {
+    hex::decode(s.trim_start_matches("0x")).or_else(|e| {
+        Err(CliError::InvalidArg {
+            name,
+            reason: format!("{}", e),
+        })
+    })
+}
+
+fn ensure_len(bytes: &[u8], name: &'static str, expected: usize) -> Result<(), CliError> {
+    if bytes.len() != expected {
+        return Err(CliError::InvalidArg {
+            name,
+            reason: format!("should be {} bytes (got {} bytes)", expected, bytes.len()),
+        });
+    }
+
+    Ok(())
+}
+
+/// Encodes `bytes` as a `0x`-prefixed hex string.
+pub fn fmt_hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+fn load_app_template<ENV: Env>(
+    runtime: &DefaultRuntime<ENV>,
+    app_addr: &Address,
+) -> Result<AppTemplate, CliError> {
+    let template = runtime
+        .env
+        .load_template_by_app(app_addr)
+        .or_else(|e| Err(CliError::StorageFailed(e.to_string())))?;
+
+    template
+        .map(|(template, _template_addr)| template)
+        .ok_or_else(|| CliError::AppNotFound(fmt_hex(app_addr.as_slice())))
+}
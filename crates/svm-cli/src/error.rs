@@ -0,0 +1,39 @@
+use std::fmt;
+
+use svm_runtime::error::{DeployTemplateError, ExecAppError, SpawnAppError};
+use svm_runtime_c_api::json::JsonError;
+
+/// `svm-cli` errors
+#[allow(missing_docs)]
+#[derive(Debug, PartialEq, Clone)]
+pub enum CliError {
+    Io(String),
+    InvalidArg { name: &'static str, reason: String },
+    Json(JsonError),
+    DeployFailed(DeployTemplateError),
+    SpawnFailed(SpawnAppError),
+    ExecFailed(ExecAppError),
+    AppNotFound(String),
+    StorageFailed(String),
+    StorageCorrupt(usize),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::Io(reason) => write!(fmt, "I/O error: {}", reason),
+            CliError::InvalidArg { name, reason } => {
+                write!(fmt, "Invalid argument `{}`: {}", name, reason)
+            }
+            CliError::Json(e) => write!(fmt, "{}", e),
+            CliError::DeployFailed(e) => write!(fmt, "Deploy template failed: {:?}", e),
+            CliError::SpawnFailed(e) => write!(fmt, "Spawn app failed: {:?}", e),
+            CliError::ExecFailed(e) => write!(fmt, "Exec app failed: {}", e),
+            CliError::AppNotFound(addr) => write!(fmt, "App `{}` not found", addr),
+            CliError::StorageFailed(reason) => write!(fmt, "Storage failed: {}", reason),
+            CliError::StorageCorrupt(count) => {
+                write!(fmt, "App storage is corrupt ({} issues found)", count)
+            }
+        }
+    }
+}
@@ -0,0 +1,16 @@
+#![deny(missing_docs)]
+#![deny(unused)]
+
+//! `svm-cli` crate implements the `svm-cli` command-line tool.
+//! It drives a `DefaultRuntime` against a local `rocksdb` database,
+//! so that apps can be deployed, spawned, executed and inspected without writing Rust harnesses.
+
+/// The command-line arguments definition
+pub mod cli;
+
+/// The subcommands implementation
+pub mod commands;
+
+mod error;
+
+pub use error::CliError;
@@ -0,0 +1,194 @@
+use std::path::Path;
+
+use clap::ArgMatches;
+
+use svm_app::{
+    default::DefaultJsonSerializerTypes,
+    traits::Env,
+    types::{AppTemplate, TemplateAbi},
+};
+use svm_runtime::{settings::RuntimeConfig, DefaultRuntime};
+
+use svm_cli::{cli, commands, commands::fmt_hex, CliError};
+
+fn main() {
+    env_logger::init();
+
+    let matches = cli::build_app().get_matches();
+
+    if let Err(e) = run(&matches) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run(matches: &ArgMatches) -> Result<(), CliError> {
+    if let ("receipt", Some(receipt)) = matches.subcommand() {
+        // decoding a receipt doesn't require a runtime
+        return receipt_cmd(receipt);
+    }
+
+    let host = std::ptr::null_mut();
+    let imports = Vec::new();
+
+    let path = Path::new(matches.value_of("db").unwrap());
+    let config = RuntimeConfig::default();
+
+    let mut runtime = svm_runtime::open_rocksdb_runtime::<_, DefaultJsonSerializerTypes>(
+        host, &path, &config, imports,
+    )
+    .or_else(|e| Err(CliError::StorageFailed(e)))?;
+
+    run_with_runtime(&mut runtime, matches)
+}
+
+fn run_with_runtime<ENV: Env>(
+    runtime: &mut DefaultRuntime<ENV>,
+    matches: &ArgMatches,
+) -> Result<(), CliError> {
+    match matches.subcommand() {
+        ("deploy", Some(deploy)) => deploy_cmd(runtime, deploy),
+        ("spawn", Some(spawn)) => spawn_cmd(runtime, spawn),
+        ("exec", Some(exec)) => exec_cmd(runtime, exec),
+        ("storage", Some(storage)) => storage_cmd(runtime, storage),
+        _ => unreachable!(),
+    }
+}
+
+fn deploy_cmd<ENV: Env>(
+    runtime: &mut DefaultRuntime<ENV>,
+    matches: &ArgMatches,
+) -> Result<(), CliError> {
+    let path = Path::new(matches.value_of("file").unwrap());
+    let code = commands::read_code(path)?;
+
+    let name = match matches.value_of("name") {
+        Some(name) => name.to_string(),
+        None => path.file_stem().unwrap().to_string_lossy().to_string(),
+    };
+
+    let page_count = parse_number_arg::<u16>(matches, "page-count")?;
+    let page_size = parse_number_arg::<u32>(matches, "page-size")?;
+    let map_pages = parse_number_arg::<u16>(matches, "map-pages")?;
+
+    let abi = match matches.value_of("abi") {
+        Some(abi) => commands::parse_abi(&commands::read_json(abi)?)?,
+        None => TemplateAbi::default(),
+    };
+
+    let author = commands::parse_address(matches.value_of("author").unwrap(), "author")?;
+
+    let template = AppTemplate {
+        name,
+        author,
+        page_count,
+        page_size,
+        map_pages,
+        abi,
+        code,
+    };
+
+    let template_addr = commands::deploy(runtime, &template)?;
+
+    println!("template: {}", fmt_hex(template_addr.as_slice()));
+
+    Ok(())
+}
+
+fn parse_number_arg<T>(matches: &ArgMatches, name: &'static str) -> Result<T, CliError>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    matches.value_of(name).unwrap().parse::<T>().or_else(|e| {
+        Err(CliError::InvalidArg {
+            name,
+            reason: format!("{}", e),
+        })
+    })
+}
+
+fn spawn_cmd<ENV: Env>(
+    runtime: &mut DefaultRuntime<ENV>,
+    matches: &ArgMatches,
+) -> Result<(), CliError> {
+    let json = commands::read_json(matches.value_of("json").unwrap())?;
+    let creator = commands::parse_address(matches.value_of("creator").unwrap(), "creator")?;
+
+    let (app_addr, state) = commands::spawn(runtime, &creator, &json)?;
+
+    println!("app: {}", fmt_hex(app_addr.as_slice()));
+    println!("state: {}", fmt_hex(state.as_slice()));
+
+    Ok(())
+}
+
+fn exec_cmd<ENV: Env>(
+    runtime: &mut DefaultRuntime<ENV>,
+    matches: &ArgMatches,
+) -> Result<(), CliError> {
+    let json = commands::read_json(matches.value_of("json").unwrap())?;
+    let sender = commands::parse_address(matches.value_of("sender").unwrap(), "sender")?;
+    let state = commands::parse_state(matches.value_of("state").unwrap(), "state")?;
+
+    let receipt = commands::exec(runtime, &sender, &state, &json)?;
+
+    println!("{}", serde_json::to_string_pretty(&receipt).unwrap());
+
+    Ok(())
+}
+
+fn storage_cmd<ENV: Env>(
+    runtime: &mut DefaultRuntime<ENV>,
+    matches: &ArgMatches,
+) -> Result<(), CliError> {
+    match matches.subcommand() {
+        ("dump", Some(dump)) => {
+            let app_addr = commands::parse_address(dump.value_of("app").unwrap(), "app")?;
+            let state = commands::parse_state(dump.value_of("state").unwrap(), "state")?;
+
+            let pages = commands::storage_dump(runtime, &app_addr, &state)?;
+
+            for (page_idx, page) in pages {
+                println!("page #{}: {}", page_idx.0, fmt_hex(&page));
+            }
+
+            Ok(())
+        }
+        ("fsck", Some(fsck)) => {
+            let app_addr = commands::parse_address(fsck.value_of("app").unwrap(), "app")?;
+            let state = commands::parse_state(fsck.value_of("state").unwrap(), "state")?;
+
+            let report = commands::storage_fsck(runtime, &app_addr, &state)?;
+
+            println!("pages checked: {}", report.pages_checked);
+
+            for issue in report.issues.iter() {
+                println!("{}", issue);
+            }
+
+            if report.is_ok() {
+                println!("ok");
+
+                Ok(())
+            } else {
+                Err(CliError::StorageCorrupt(report.issues.len()))
+            }
+        }
+        _ => unreachable!(),
+    }
+}
+
+fn receipt_cmd(matches: &ArgMatches) -> Result<(), CliError> {
+    match matches.subcommand() {
+        ("decode", Some(decode)) => {
+            let bytes = commands::parse_hex(decode.value_of("receipt").unwrap(), "receipt")?;
+            let receipt = commands::receipt_decode(&bytes)?;
+
+            println!("{}", serde_json::to_string_pretty(&receipt).unwrap());
+
+            Ok(())
+        }
+        _ => unreachable!(),
+    }
+}
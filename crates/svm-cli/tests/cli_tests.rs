@@ -0,0 +1,109 @@
+use std::path::Path;
+
+use serde_json::json;
+
+use svm_app::types::{AppTemplate, TemplateAbi, DEFAULT_PAGE_SIZE};
+use svm_common::{Address, State};
+use svm_runtime::testing;
+use svm_storage::page::PageIndex;
+
+use svm_cli::{commands, commands::fmt_hex, CliError};
+
+#[test]
+fn cli_deploy_spawn_exec_and_dump_storage() {
+    let kv = testing::memory_kv_store_init();
+    let mut runtime = testing::create_memory_runtime(std::ptr::null_mut(), &kv, Vec::new());
+
+    let author = Address::of("author");
+    let creator = Address::of("creator");
+    let sender = Address::of("sender");
+
+    // 1) deploy
+    let path = Path::new(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/wasm/store.wast"
+    ));
+    let code = commands::read_code(path).unwrap();
+
+    let template = AppTemplate {
+        name: "My Template".to_string(),
+        author,
+        page_count: 2,
+        page_size: DEFAULT_PAGE_SIZE,
+        map_pages: 0,
+        abi: TemplateAbi::default(),
+        code,
+    };
+
+    let template_addr = commands::deploy(&mut runtime, &template).unwrap();
+
+    // 2) spawn
+    let json = format!(r#"{{"template": "{}"}}"#, fmt_hex(template_addr.as_slice()));
+    let (app_addr, init_state) = commands::spawn(&mut runtime, &creator, &json).unwrap();
+    assert_eq!(State::empty(), init_state);
+
+    // 3) exec
+    // func `run` params: buf_id, buf_offset, reg_bits, reg_idx, count, page_idx, page_offset
+    let json = format!(
+        r#"{{
+          "app": "{}",
+          "func": "run",
+          "buf": ["0x10203040"],
+          "args": [
+            {{"type": "i32", "value": 0}},
+            {{"type": "i32", "value": 0}},
+            {{"type": "i32", "value": 128}},
+            {{"type": "i32", "value": 3}},
+            {{"type": "i32", "value": 4}},
+            {{"type": "i32", "value": 1}},
+            {{"type": "i32", "value": 2}}
+          ]
+        }}"#,
+        fmt_hex(app_addr.as_slice())
+    );
+
+    let receipt = commands::exec(&mut runtime, &sender, &init_state, &json).unwrap();
+    assert_eq!(json!(true), receipt["success"]);
+    assert_eq!(json!([]), receipt["returns"]);
+
+    let new_state = commands::parse_state(receipt["new_state"].as_str().unwrap(), "state").unwrap();
+
+    // 4) storage dump
+    let pages = commands::storage_dump(&runtime, &app_addr, &new_state).unwrap();
+    assert_eq!(1, pages.len());
+
+    let (page_idx, page) = &pages[0];
+    assert_eq!(PageIndex(1), *page_idx);
+    assert_eq!(&[0x00, 0x00, 0x10, 0x20, 0x30, 0x40, 0x00], &page[0..7]);
+
+    // 5) storage fsck
+    let report = commands::storage_fsck(&runtime, &app_addr, &new_state).unwrap();
+    assert!(report.is_ok());
+    assert_eq!(1, report.pages_checked);
+}
+
+#[test]
+fn cli_storage_dump_app_not_found() {
+    let kv = testing::memory_kv_store_init();
+    let runtime = testing::create_memory_runtime(std::ptr::null_mut(), &kv, Vec::new());
+
+    let app_addr = Address::of("my-app");
+    let res = commands::storage_dump(&runtime, &app_addr, &State::empty());
+
+    assert_eq!(
+        Err(CliError::AppNotFound(fmt_hex(app_addr.as_slice()))),
+        res
+    );
+}
+
+#[test]
+fn cli_parse_address_invalid_length() {
+    let res = commands::parse_address("0x1020", "sender");
+
+    let expected = CliError::InvalidArg {
+        name: "sender",
+        reason: "should be 20 bytes (got 2 bytes)".to_string(),
+    };
+
+    assert_eq!(Err(expected), res);
+}
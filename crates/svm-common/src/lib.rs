@@ -11,6 +11,7 @@ mod ffi;
 mod helpers;
 mod key_hasher;
 mod macros;
+mod page_size;
 mod state;
 
 /// Common Formatters
@@ -21,4 +22,5 @@ pub use balance::Balance;
 pub use default_key_hasher::DefaultKeyHasher;
 pub use ffi::{from_raw, from_raw_mut, into_raw, into_raw_mut};
 pub use key_hasher::KeyHasher;
+pub use page_size::{is_valid_page_size, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE, MIN_PAGE_SIZE};
 pub use state::State;
@@ -0,0 +1,14 @@
+/// The default page size (in bytes)
+pub const DEFAULT_PAGE_SIZE: u32 = 4_096;
+
+/// The smallest allowed page size
+pub const MIN_PAGE_SIZE: u32 = 256;
+
+/// The biggest allowed page size
+pub const MAX_PAGE_SIZE: u32 = 16_384;
+
+/// Returns whether `page_size` is an allowed page size (a power of two between `MIN_PAGE_SIZE` and `MAX_PAGE_SIZE`)
+#[inline]
+pub fn is_valid_page_size(page_size: u32) -> bool {
+    page_size.is_power_of_two() && (MIN_PAGE_SIZE..=MAX_PAGE_SIZE).contains(&page_size)
+}
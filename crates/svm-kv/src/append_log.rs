@@ -0,0 +1,685 @@
+use crate::{
+    error::KVError,
+    traits::{KVChange, KVEntry, KVStore},
+};
+
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use log::{info, warn};
+
+/// The magic bytes opening an append-log file (followed by the format version).
+pub const LOG_MAGIC: &[u8; 4] = b"SVML";
+
+/// The append-log file format version.
+pub const LOG_VERSION: u8 = 1;
+
+/// The size (in bytes) of the append-log file header (magic || version).
+pub const LOG_HEADER_SIZE: u64 = 5;
+
+/// Compaction is triggered automatically once the stale bytes exceed both this threshold and the live bytes.
+pub const COMPACTION_MIN_STALE_BYTES: u64 = 1 << 20;
+
+const OP_PUT: u8 = 1;
+const OP_DELETE: u8 = 2;
+
+// the number of live entries per batch, written by a compaction
+const COMPACTION_BATCH_ENTRIES: usize = 1024;
+
+/// The position of a value under the append-log file
+#[derive(Debug, Clone, Copy)]
+struct ValuePos {
+    offset: u64,
+    len: u32,
+
+    // the size of the `put` operation holding the value
+    op_size: u64,
+}
+
+enum Op<'a> {
+    Put(&'a [u8], &'a [u8]),
+    Delete(&'a [u8]),
+}
+
+/// An implementation of `KVStore` backed by a single append-only log file (pure-Rust, no native dependencies).
+///
+/// The file is a sequence of batches. Each `store` / `delete` call appends one batch:
+/// `payload length (4 bytes) || payload crc32 (4 bytes) || payload`, where the payload is a sequence of operations:
+/// * `put`    - `1 (1 byte) || key length (4 bytes) || value length (4 bytes) || key || value`
+/// * `delete` - `2 (1 byte) || key length (4 bytes) || key`
+///
+/// The keys are indexed in-memory (each pointing to its value position under the file),
+/// and the index is rebuilt by replaying the log when opening it.
+///
+/// A batch is written and synced as a whole, so a crash may only leave a torn batch at the end of the log.
+/// Opening the log discards such a batch (i.e its changes are never partially applied).
+///
+/// Overridden and deleted entries are reclaimed by a compaction (see `compact`), rewriting the live entries into a new log.
+/// A compaction is also triggered automatically by writes (once enough stale bytes accumulate), in which case its
+/// failure is only logged (the written batch is durable anyway, and the compaction is retried by the next writes).
+pub struct AppendLogKV {
+    path: PathBuf,
+    file: File,
+    len: u64,
+    index: BTreeMap<Vec<u8>, ValuePos>,
+    live_bytes: u64,
+    stale_bytes: u64,
+}
+
+impl AppendLogKV {
+    /// Opens (or creates) the append-log under `path` and rebuilds its index.
+    ///
+    /// A torn batch at the end of the log (due to a crash while writing it) is truncated.
+    /// A corrupted batch elsewhere fails the opening with `KVError::DataCorruption`.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, KVError> {
+        let path = path.as_ref().to_path_buf();
+
+        info!("opening append-log kv. (path = \"{}\")", path.display());
+
+        // a leftover of an interrupted compaction (the log itself is intact)
+        let compaction_path = compaction_path(&path);
+
+        if compaction_path.exists() {
+            warn!(
+                "removing leftover of an interrupted compaction: \"{}\"",
+                compaction_path.display()
+            );
+
+            fs::remove_file(&compaction_path).or_else(|e| Err(write_failed("remove", e)))?;
+        }
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .or_else(|e| Err(read_failed("open", e)))?;
+
+        let mut bytes = Vec::new();
+
+        file.read_to_end(&mut bytes)
+            .or_else(|e| Err(read_failed("read", e)))?;
+
+        let mut kv = Self {
+            path,
+            file,
+            len: 0,
+            index: BTreeMap::new(),
+            live_bytes: 0,
+            stale_bytes: 0,
+        };
+
+        kv.recover(&bytes)?;
+
+        Ok(kv)
+    }
+
+    /// The size (in bytes) of the log
+    #[inline]
+    pub fn size(&self) -> u64 {
+        self.len
+    }
+
+    /// The size (in bytes) of the operations of overridden and deleted entries (reclaimed by `compact`)
+    #[inline]
+    pub fn stale_bytes(&self) -> u64 {
+        self.stale_bytes
+    }
+
+    /// Rewrites the live entries into a new log, which then atomically replaces the current one.
+    /// On failure, the current log is left intact (and still in use).
+    pub fn compact(&mut self) -> Result<(), KVError> {
+        info!(
+            "compacting append-log kv. (path = \"{}\", stale bytes = {})",
+            self.path.display(),
+            self.stale_bytes
+        );
+
+        let entries = self.read_entries(self.index.iter())?;
+
+        let mut bytes = log_header();
+
+        for chunk in entries.chunks(COMPACTION_BATCH_ENTRIES) {
+            let ops: Vec<Op> = chunk.iter().map(|(k, v)| Op::Put(k, v)).collect();
+
+            bytes.extend_from_slice(&encode_batch(&ops)?);
+        }
+
+        let compaction_path = compaction_path(&self.path);
+
+        let compacted = self
+            .write_compaction(&compaction_path, &bytes)
+            .and_then(|compacted| {
+                fs::rename(&compaction_path, &self.path)
+                    .or_else(|e| Err(write_failed("rename", e)))?;
+
+                Ok(compacted)
+            });
+
+        match compacted {
+            Ok(compacted) => {
+                sync_parent_dir(&self.path);
+
+                // the compacted log handle keeps referring to the compacted file after its renaming
+                *self = compacted;
+
+                Ok(())
+            }
+            Err(e) => {
+                let _ = fs::remove_file(&compaction_path);
+
+                Err(e)
+            }
+        }
+    }
+
+    /// Writes the compacted log `bytes` into `compaction_path`.
+    /// Returns the compacted log (whose index is rebuilt from `bytes`), to be used once it replaces the current log.
+    fn write_compaction(&self, compaction_path: &Path, bytes: &[u8]) -> Result<Self, KVError> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(compaction_path)
+            .or_else(|e| Err(write_failed("create", e)))?;
+
+        file.write_all(bytes)
+            .and_then(|_| file.sync_all())
+            .or_else(|e| Err(write_failed("write", e)))?;
+
+        let mut compacted = Self {
+            path: self.path.clone(),
+            file,
+            len: 0,
+            index: BTreeMap::new(),
+            live_bytes: 0,
+            stale_bytes: 0,
+        };
+
+        compacted.recover(bytes)?;
+
+        Ok(compacted)
+    }
+
+    fn recover(&mut self, bytes: &[u8]) -> Result<(), KVError> {
+        if (bytes.len() as u64) < LOG_HEADER_SIZE {
+            // a new log (or a crash while creating it)
+            let header = log_header();
+
+            self.truncate(0)?;
+            self.append(&header)?;
+
+            return Ok(());
+        }
+
+        if &bytes[0..4] != LOG_MAGIC || bytes[4] != LOG_VERSION {
+            return Err(KVError::DataCorruption(format!(
+                "\"{}\" isn't an append-log (version {})",
+                self.path.display(),
+                LOG_VERSION
+            )));
+        }
+
+        let mut offset = LOG_HEADER_SIZE as usize;
+
+        while offset < bytes.len() {
+            let batch = &bytes[offset..];
+
+            if batch.len() < 8 {
+                break;
+            }
+
+            let payload_len = read_u32(&batch[0..4]) as usize;
+            let crc = read_u32(&batch[4..8]);
+
+            if batch.len() < 8 + payload_len {
+                // an incomplete batch is a torn batch only when it's the last one (otherwise, its length is corrupted)
+                if contains_batch(&batch[8..]) {
+                    return Err(KVError::DataCorruption(format!(
+                        "invalid length of the batch at offset {} of \"{}\"",
+                        offset,
+                        self.path.display()
+                    )));
+                }
+
+                break;
+            }
+
+            let payload = &batch[8..8 + payload_len];
+            let end = offset + 8 + payload_len;
+
+            if crc32(payload) != crc {
+                if end == bytes.len() {
+                    break;
+                }
+
+                return Err(KVError::DataCorruption(format!(
+                    "checksum mismatch of the batch at offset {} of \"{}\"",
+                    offset,
+                    self.path.display()
+                )));
+            }
+
+            self.apply(payload, (offset + 8) as u64)?;
+
+            offset = end;
+        }
+
+        if offset < bytes.len() {
+            warn!(
+                "truncating a torn batch at offset {} of \"{}\"",
+                offset,
+                self.path.display()
+            );
+
+            self.truncate(offset as u64)?;
+        } else {
+            self.len = offset as u64;
+        }
+
+        Ok(())
+    }
+
+    fn write_batch(&mut self, ops: &[Op]) -> Result<(), KVError> {
+        if ops.is_empty() {
+            return Ok(());
+        }
+
+        let batch = encode_batch(ops)?;
+
+        self.append(&batch)?;
+
+        let payload_offset = self.len - batch.len() as u64 + 8;
+        self.apply(&batch[8..], payload_offset)?;
+
+        if self.stale_bytes >= COMPACTION_MIN_STALE_BYTES && self.stale_bytes > self.live_bytes {
+            if let Err(e) = self.compact() {
+                warn!("compaction of \"{}\" failed: {}", self.path.display(), e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Appends `bytes` to the log and syncs it.
+    /// On failure, the log is truncated back, so no partial write is left behind.
+    fn append(&mut self, bytes: &[u8]) -> Result<(), KVError> {
+        let len = self.len;
+
+        let res = self
+            .file
+            .seek(SeekFrom::Start(len))
+            .and_then(|_| self.file.write_all(bytes))
+            .and_then(|_| self.file.sync_data());
+
+        match res {
+            Ok(()) => {
+                self.len += bytes.len() as u64;
+
+                Ok(())
+            }
+            Err(e) => {
+                let _ = self.file.set_len(len);
+
+                Err(write_failed("append", e))
+            }
+        }
+    }
+
+    fn truncate(&mut self, len: u64) -> Result<(), KVError> {
+        self.file
+            .set_len(len)
+            .and_then(|_| self.file.sync_data())
+            .or_else(|e| Err(write_failed("truncate", e)))?;
+
+        self.len = len;
+
+        Ok(())
+    }
+
+    /// Applies the operations of the batch `payload` (residing at offset `payload_offset` of the log) to the index
+    fn apply(&mut self, payload: &[u8], payload_offset: u64) -> Result<(), KVError> {
+        let mut pos = 0;
+
+        while pos < payload.len() {
+            let op_start = pos;
+            let kind = payload[pos];
+            pos += 1;
+
+            let key_len = self.decode_u32(payload, &mut pos)? as usize;
+
+            match kind {
+                OP_PUT => {
+                    let value_len = self.decode_u32(payload, &mut pos)?;
+                    let key = self.decode_bytes(payload, &mut pos, key_len)?.to_vec();
+
+                    let value_offset = payload_offset + pos as u64;
+                    self.decode_bytes(payload, &mut pos, value_len as usize)?;
+
+                    let op_size = (pos - op_start) as u64;
+
+                    let value_pos = ValuePos {
+                        offset: value_offset,
+                        len: value_len,
+                        op_size,
+                    };
+
+                    if let Some(prev) = self.index.insert(key, value_pos) {
+                        self.live_bytes -= prev.op_size;
+                        self.stale_bytes += prev.op_size;
+                    }
+
+                    self.live_bytes += op_size;
+                }
+                OP_DELETE => {
+                    let key = self.decode_bytes(payload, &mut pos, key_len)?;
+
+                    if let Some(prev) = self.index.remove(key) {
+                        self.live_bytes -= prev.op_size;
+                        self.stale_bytes += prev.op_size;
+                    }
+
+                    // the `delete` operation itself is no longer needed after a compaction
+                    self.stale_bytes += (pos - op_start) as u64;
+                }
+                _ => {
+                    return Err(self.malformed(payload_offset));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn decode_u32(&self, payload: &[u8], pos: &mut usize) -> Result<u32, KVError> {
+        let bytes = self.decode_bytes(payload, pos, 4)?;
+
+        Ok(read_u32(bytes))
+    }
+
+    fn decode_bytes<'a>(
+        &self,
+        payload: &'a [u8],
+        pos: &mut usize,
+        len: usize,
+    ) -> Result<&'a [u8], KVError> {
+        if payload.len() - *pos < len {
+            return Err(self.malformed(0));
+        }
+
+        let bytes = &payload[*pos..*pos + len];
+        *pos += len;
+
+        Ok(bytes)
+    }
+
+    fn malformed(&self, payload_offset: u64) -> KVError {
+        KVError::DataCorruption(format!(
+            "malformed batch (payload offset {}) of \"{}\"",
+            payload_offset,
+            self.path.display()
+        ))
+    }
+
+    fn read_value(&self, pos: &ValuePos) -> Result<Vec<u8>, KVError> {
+        let mut file = &self.file;
+        let mut value = vec![0; pos.len as usize];
+
+        file.seek(SeekFrom::Start(pos.offset))
+            .and_then(|_| file.read_exact(&mut value))
+            .or_else(|e| Err(read_failed("read", e)))?;
+
+        Ok(value)
+    }
+
+    fn read_entries<'a, I>(&self, iter: I) -> Result<Vec<KVEntry>, KVError>
+    where
+        I: Iterator<Item = (&'a Vec<u8>, &'a ValuePos)>,
+    {
+        iter.map(|(k, pos)| Ok((k.clone(), self.read_value(pos)?)))
+            .collect()
+    }
+}
+
+impl KVStore for AppendLogKV {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, KVError> {
+        match self.index.get(key) {
+            Some(pos) => self.read_value(pos).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Stores a batch of changes atomically (as a single log batch).
+    fn store(&mut self, changes: &[(&[u8], &[u8])]) -> Result<(), KVError> {
+        info!("storing append-log kv changeset");
+
+        let ops: Vec<Op> = changes.iter().map(|(k, v)| Op::Put(k, v)).collect();
+
+        self.write_batch(&ops)
+    }
+
+    /// Deletes `keys` atomically (as a single log batch).
+    fn delete(&mut self, keys: &[&[u8]]) -> Result<(), KVError> {
+        info!("deleting append-log kv keys");
+
+        let ops: Vec<Op> = keys.iter().map(|k| Op::Delete(k)).collect();
+
+        self.write_batch(&ops)
+    }
+
+    /// Writes `changes` atomically (as a single log batch).
+    fn write(&mut self, changes: &[KVChange]) -> Result<(), KVError> {
+        info!("writing append-log kv changeset");
+
+        let ops: Vec<Op> = changes
+            .iter()
+            .map(|(k, v)| match v {
+                Some(v) => Op::Put(k, v),
+                None => Op::Delete(k),
+            })
+            .collect();
+
+        self.write_batch(&ops)
+    }
+
+    fn iter_prefix(&self, prefix: &[u8]) -> Result<Vec<KVEntry>, KVError> {
+        let iter = self
+            .index
+            .range(prefix.to_vec()..)
+            .take_while(|(k, _pos)| k.starts_with(prefix));
+
+        self.read_entries(iter)
+    }
+
+    fn iter_range(&self, start: &[u8], end: &[u8]) -> Result<Vec<KVEntry>, KVError> {
+        if start >= end {
+            return Ok(Vec::new());
+        }
+
+        let iter = self.index.range(start.to_vec()..end.to_vec());
+
+        self.read_entries(iter)
+    }
+}
+
+impl Drop for AppendLogKV {
+    fn drop(&mut self) {
+        info!("dropping `AppendLogKV`");
+    }
+}
+
+fn log_header() -> Vec<u8> {
+    let mut header = LOG_MAGIC.to_vec();
+    header.push(LOG_VERSION);
+
+    header
+}
+
+fn encode_batch(ops: &[Op]) -> Result<Vec<u8>, KVError> {
+    let mut payload = Vec::new();
+
+    for op in ops {
+        match op {
+            Op::Put(key, value) => {
+                payload.push(OP_PUT);
+                payload.extend_from_slice(&encode_len(key.len())?);
+                payload.extend_from_slice(&encode_len(value.len())?);
+                payload.extend_from_slice(key);
+                payload.extend_from_slice(value);
+            }
+            Op::Delete(key) => {
+                payload.push(OP_DELETE);
+                payload.extend_from_slice(&encode_len(key.len())?);
+                payload.extend_from_slice(key);
+            }
+        }
+    }
+
+    let mut batch = Vec::with_capacity(8 + payload.len());
+    batch.extend_from_slice(&encode_len(payload.len())?);
+    batch.extend_from_slice(&crc32(&payload).to_be_bytes());
+    batch.extend_from_slice(&payload);
+
+    Ok(batch)
+}
+
+fn encode_len(len: usize) -> Result<[u8; 4], KVError> {
+    if len > u32::max_value() as usize {
+        return Err(KVError::WriteFailed(format!(
+            "length {} exceeds the append-log limit",
+            len
+        )));
+    }
+
+    Ok((len as u32).to_be_bytes())
+}
+
+/// Whether a complete batch (having a matching checksum) starts anywhere within `bytes`.
+///
+/// Since the batches are appended one after the other, a torn batch is never followed by a complete one.
+fn contains_batch(bytes: &[u8]) -> bool {
+    (0..bytes.len()).any(|start| {
+        let batch = &bytes[start..];
+
+        if batch.len() < 8 {
+            return false;
+        }
+
+        // batches are never empty
+        let payload_len = read_u32(&batch[0..4]) as usize;
+
+        payload_len > 0
+            && batch.len() - 8 >= payload_len
+            && crc32(&batch[8..8 + payload_len]) == read_u32(&batch[4..8])
+    })
+}
+
+#[inline]
+fn read_u32(bytes: &[u8]) -> u32 {
+    let mut buf = [0; 4];
+    buf.copy_from_slice(&bytes[0..4]);
+
+    u32::from_be_bytes(buf)
+}
+
+/// CRC-32 (IEEE) checksum
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+
+    for byte in bytes {
+        crc ^= *byte as u32;
+
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+
+    !crc
+}
+
+fn compaction_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".compaction");
+
+    PathBuf::from(name)
+}
+
+/// Makes a `rename` under the directory of `path` durable (best-effort).
+fn sync_parent_dir(path: &Path) {
+    #[cfg(unix)]
+    {
+        if let Some(dir) = path.parent() {
+            let dir = if dir.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                dir
+            };
+
+            if let Ok(dir) = File::open(dir) {
+                let _ = dir.sync_all();
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+    }
+}
+
+fn read_failed(op: &str, e: std::io::Error) -> KVError {
+    KVError::ReadFailed(format!("append-log `{}` failed: {}", op, e))
+}
+
+fn write_failed(op: &str, e: std::io::Error) -> KVError {
+    KVError::WriteFailed(format!("append-log `{}` failed: {}", op, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc32_check_value() {
+        assert_eq!(0xCBF4_3926, crc32(b"123456789"));
+        assert_eq!(0, crc32(b""));
+    }
+
+    #[test]
+    fn batch_encoding() {
+        let batch = encode_batch(&[Op::Put(&[1, 2], &[3]), Op::Delete(&[4])]).unwrap();
+
+        let payload = vec![
+            OP_PUT, 0, 0, 0, 2, 0, 0, 0, 1, 1, 2, 3, OP_DELETE, 0, 0, 0, 1, 4,
+        ];
+
+        assert_eq!(&[0, 0, 0, 18], &batch[0..4]);
+        assert_eq!(&crc32(&payload).to_be_bytes(), &batch[4..8]);
+        assert_eq!(&payload[..], &batch[8..]);
+    }
+
+    #[test]
+    fn contains_batch_of_matching_checksum() {
+        let batch = encode_batch(&[Op::Put(&[1, 2], &[3])]).unwrap();
+
+        let mut bytes = vec![0xFF; 3];
+        bytes.extend_from_slice(&batch);
+        assert!(contains_batch(&bytes));
+
+        assert!(!contains_batch(&batch[0..batch.len() - 1]));
+        assert!(!contains_batch(&[0; 16]));
+    }
+
+    #[test]
+    fn compaction_path_is_next_to_the_log() {
+        assert_eq!(
+            PathBuf::from("/tmp/kv.log.compaction"),
+            compaction_path(Path::new("/tmp/kv.log"))
+        );
+    }
+}
@@ -0,0 +1,37 @@
+use std::error;
+use std::fmt;
+
+/// An error returned by a `KVStore` operation.
+#[derive(Debug, PartialEq, Clone)]
+pub enum KVError {
+    /// Reading from (or iterating over) the underlying store has failed.
+    ReadFailed(String),
+
+    /// Writing (storing or deleting) to the underlying store has failed.
+    WriteFailed(String),
+
+    /// The stored data is missing or malformed.
+    DataCorruption(String),
+}
+
+impl error::Error for KVError {
+    fn description(&self) -> &'static str {
+        match self {
+            KVError::ReadFailed(..) => "Read failed",
+            KVError::WriteFailed(..) => "Write failed",
+            KVError::DataCorruption(..) => "Data corruption",
+        }
+    }
+}
+
+impl fmt::Display for KVError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KVError::ReadFailed(reason) => write!(f, "key-value store read failed: {}", reason),
+            KVError::WriteFailed(reason) => write!(f, "key-value store write failed: {}", reason),
+            KVError::DataCorruption(reason) => {
+                write!(f, "key-value store data corruption: {}", reason)
+            }
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use crate::{
+    error::KVError,
+    overlay::merge_changes,
+    traits::{KVChange, KVEntry, KVStore},
+};
+
+use std::collections::BTreeMap;
+
+use log::{info, warn};
+
+/// A fault injected into a write (`store` / `delete` / `write`) of a `FaultyKV`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fault {
+    /// The write fails (returning `KVError::WriteFailed`) without applying any of its changes.
+    Fail,
+
+    /// The write panics without applying any of its changes (simulating the process being killed).
+    Panic,
+
+    /// Only the first `n` changes of the write are applied, then it fails (returning `KVError::WriteFailed`).
+    /// Simulates a torn (non-atomic) batch.
+    PartialApply(usize),
+}
+
+/// A `KVStore` wrapper, injecting scripted faults into the writes of the wrapped store.
+/// Intended for testing crash-consistency.
+///
+/// * Faults are scripted per write (see `inject`). Each `store` / `delete` / `write` call counts as one write.
+/// * When created `with_write_back`, writes are buffered (and visible to reads) until `flush`.
+///   A process crash is simulated by `crash`, dropping the unflushed writes.
+/// * Reads fail (returning `KVError::ReadFailed`) while set so by `fail_reads`.
+pub struct FaultyKV<KV: KVStore> {
+    inner: KV,
+
+    write_back: bool,
+
+    /// `None` marks a deleted key
+    unflushed: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+
+    writes: usize,
+
+    /// The write index each fault is injected into
+    faults: BTreeMap<usize, Fault>,
+
+    fail_reads: bool,
+}
+
+impl<KV: KVStore> FaultyKV<KV> {
+    /// Wraps `inner` (writes go through right away, unless set otherwise by `with_write_back`)
+    pub fn new(inner: KV) -> Self {
+        info!("creating a new faulty kv");
+
+        Self {
+            inner,
+            write_back: false,
+            unflushed: BTreeMap::new(),
+            writes: 0,
+            faults: BTreeMap::new(),
+            fail_reads: false,
+        }
+    }
+
+    /// Buffers the writes until `flush` (see `crash`)
+    pub fn with_write_back(mut self) -> Self {
+        self.write_back = true;
+        self
+    }
+
+    /// Injects `fault` into the write following the next `skip` writes.
+    /// (i.e `inject(0, fault)` injects `fault` into the very next write).
+    pub fn inject(&mut self, skip: usize, fault: Fault) {
+        self.faults.insert(self.writes + skip, fault);
+    }
+
+    /// Sets whether the reads (`get` / `iter_prefix` / `iter_range`) fail
+    pub fn fail_reads(&mut self, fail: bool) {
+        self.fail_reads = fail;
+    }
+
+    /// Removes the faults not injected yet
+    pub fn clear_faults(&mut self) {
+        self.faults.clear();
+    }
+
+    /// The number of writes (`store` / `delete` / `write` calls) so far, including the faulty ones
+    #[inline]
+    pub fn writes_count(&self) -> usize {
+        self.writes
+    }
+
+    /// The wrapped store (i.e the durable data, not including the unflushed writes)
+    pub fn inner(&self) -> &KV {
+        &self.inner
+    }
+
+    /// The number of unflushed (stored or deleted) keys
+    pub fn unflushed_count(&self) -> usize {
+        self.unflushed.len()
+    }
+
+    /// Writes the unflushed writes into the wrapped store
+    pub fn flush(&mut self) -> Result<(), KVError> {
+        let changes: Vec<KVChange> = self
+            .unflushed
+            .iter()
+            .map(|(k, v)| (&k[..], v.as_ref().map(|v| &v[..])))
+            .collect();
+
+        self.inner.write(&changes)?;
+
+        self.unflushed.clear();
+
+        Ok(())
+    }
+
+    /// Simulates a process crash: the unflushed writes are lost.
+    pub fn crash(&mut self) {
+        warn!(
+            "simulating a crash (dropping {} unflushed keys)",
+            self.unflushed.len()
+        );
+
+        self.unflushed.clear();
+    }
+
+    fn maybe_fail_read(&self) -> Result<(), KVError> {
+        if self.fail_reads {
+            warn!("injecting a read failure");
+
+            Err(KVError::ReadFailed("injected read failure".to_string()))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Counts a new write of `changes` and performs it using `apply`, unless a fault is injected into it.
+    fn maybe_fail<T, F>(&mut self, changes: &[T], apply: F) -> Result<(), KVError>
+    where
+        F: FnOnce(&mut Self, &[T]) -> Result<(), KVError>,
+    {
+        let write = self.writes;
+        let fault = self.faults.remove(&write);
+
+        self.writes += 1;
+
+        if let Some(fault) = fault {
+            warn!("injecting {:?} into write #{}", fault, write);
+        }
+
+        match fault {
+            None => apply(self, changes),
+            Some(Fault::Fail) => Err(injected_failure(write)),
+            Some(Fault::Panic) => panic!("injected panic into write #{}", write),
+            Some(Fault::PartialApply(n)) => {
+                let n = n.min(changes.len());
+
+                apply(self, &changes[..n])?;
+
+                Err(injected_failure(write))
+            }
+        }
+    }
+
+    fn do_store(&mut self, changes: &[(&[u8], &[u8])]) -> Result<(), KVError> {
+        if self.write_back {
+            for (k, v) in changes {
+                self.unflushed.insert(k.to_vec(), Some(v.to_vec()));
+            }
+
+            Ok(())
+        } else {
+            self.inner.store(changes)
+        }
+    }
+
+    fn do_delete(&mut self, keys: &[&[u8]]) -> Result<(), KVError> {
+        if self.write_back {
+            for k in keys {
+                self.unflushed.insert(k.to_vec(), None);
+            }
+
+            Ok(())
+        } else {
+            self.inner.delete(keys)
+        }
+    }
+
+    fn do_write(&mut self, changes: &[KVChange]) -> Result<(), KVError> {
+        if self.write_back {
+            for (k, v) in changes {
+                self.unflushed.insert(k.to_vec(), v.map(|v| v.to_vec()));
+            }
+
+            Ok(())
+        } else {
+            self.inner.write(changes)
+        }
+    }
+}
+
+impl<KV: KVStore> KVStore for FaultyKV<KV> {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, KVError> {
+        self.maybe_fail_read()?;
+
+        match self.unflushed.get(key) {
+            Some(v) => Ok(v.clone()),
+            None => self.inner.get(key),
+        }
+    }
+
+    fn store(&mut self, changes: &[(&[u8], &[u8])]) -> Result<(), KVError> {
+        self.maybe_fail(changes, |kv, changes| kv.do_store(changes))
+    }
+
+    fn delete(&mut self, keys: &[&[u8]]) -> Result<(), KVError> {
+        self.maybe_fail(keys, |kv, keys| kv.do_delete(keys))
+    }
+
+    fn write(&mut self, changes: &[KVChange]) -> Result<(), KVError> {
+        self.maybe_fail(changes, |kv, changes| kv.do_write(changes))
+    }
+
+    fn iter_prefix(&self, prefix: &[u8]) -> Result<Vec<KVEntry>, KVError> {
+        self.maybe_fail_read()?;
+
+        let entries = self.inner.iter_prefix(prefix)?;
+
+        Ok(merge_changes(entries, &self.unflushed, prefix, |k| {
+            k.starts_with(prefix)
+        }))
+    }
+
+    fn iter_range(&self, start: &[u8], end: &[u8]) -> Result<Vec<KVEntry>, KVError> {
+        self.maybe_fail_read()?;
+
+        if start >= end {
+            return Ok(Vec::new());
+        }
+
+        let entries = self.inner.iter_range(start, end)?;
+
+        Ok(merge_changes(entries, &self.unflushed, start, |k| k < end))
+    }
+}
+
+fn injected_failure(write: usize) -> KVError {
+    KVError::WriteFailed(format!("injected failure into write #{}", write))
+}
@@ -4,13 +4,29 @@
 //! The `svm-kv` is responsible on providing different implementations for the `KVStore` trait.
 //! (defined in `traits.rs`).
 
-/// Defines the `KVStore` trait.
+/// Defines the `KVStore`, `KVBackend` and `Overlay` traits.
 pub mod traits;
 
+mod error;
+pub use error::KVError;
+
+/// A `KVStore` buffering writes on top of another `KVStore`
+pub mod overlay;
+
+/// Committing the writes of many stores as a single atomic batch
+pub mod unit_of_work;
+
+/// A `KVStore` wrapper injecting scripted faults (for testing crash-consistency)
+pub mod faulty;
+
 /// An in-memory implementation for `KVStore`
 #[cfg(feature = "memory")]
 pub mod memory;
 
+/// `KVStore` backed by a pure-Rust append-only log file
+#[cfg(feature = "append-log")]
+pub mod append_log;
+
 /// `KVStore` backed by rocksdb
 #[cfg(feature = "default-rocksdb")]
 pub mod rocksdb;
@@ -1,11 +1,15 @@
-use crate::traits::KVStore;
-use std::collections::{hash_map, HashMap};
+use crate::{
+    error::KVError,
+    traits::{KVChange, KVEntry, KVStore},
+};
+use std::collections::{btree_map, BTreeMap};
 
 use log::info;
 
-/// An implementation for a key-value store (implements `KVStore`) store backed by an underlying `HashMap`
+/// An implementation for a key-value store (implements `KVStore`) store backed by an underlying `BTreeMap`
+/// (keeping the keys sorted allows iterating by prefix or range).
 pub struct MemKVStore {
-    map: HashMap<Vec<u8>, Vec<u8>>,
+    map: BTreeMap<Vec<u8>, Vec<u8>>,
 }
 
 impl MemKVStore {
@@ -15,7 +19,7 @@ impl MemKVStore {
         info!("creating a new in-memory kv");
 
         Self {
-            map: HashMap::new(),
+            map: BTreeMap::new(),
         }
     }
 
@@ -26,33 +30,77 @@ impl MemKVStore {
         self.map.clear();
     }
 
-    /// Returns an iterator for the internal `HashMap`
-    pub fn iter(&self) -> hash_map::Iter<Vec<u8>, Vec<u8>> {
+    /// Returns an iterator for the internal `BTreeMap`
+    pub fn iter(&self) -> btree_map::Iter<Vec<u8>, Vec<u8>> {
         (&self.map).iter()
     }
 
     /// Returns an iterator over the keys
-    pub fn keys(&self) -> hash_map::Keys<Vec<u8>, Vec<u8>> {
+    pub fn keys(&self) -> btree_map::Keys<Vec<u8>, Vec<u8>> {
         self.map.keys()
     }
 }
 
 impl KVStore for MemKVStore {
-    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
-        let entry = self.map.get(key);
-
-        if let Some(entry) = entry {
-            Some(entry.clone())
-        } else {
-            None
-        }
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, KVError> {
+        Ok(self.map.get(key).cloned())
     }
 
-    fn store(&mut self, changes: &[(&[u8], &[u8])]) {
+    fn store(&mut self, changes: &[(&[u8], &[u8])]) -> Result<(), KVError> {
         info!("storing in-memory kv changeset");
 
         for (k, v) in changes {
             self.map.insert(k.to_vec(), v.to_vec());
         }
+
+        Ok(())
+    }
+
+    fn delete(&mut self, keys: &[&[u8]]) -> Result<(), KVError> {
+        info!("deleting in-memory kv keys");
+
+        for k in keys {
+            self.map.remove(*k);
+        }
+
+        Ok(())
+    }
+
+    fn write(&mut self, changes: &[KVChange]) -> Result<(), KVError> {
+        info!("writing in-memory kv changeset");
+
+        for (k, v) in changes {
+            match v {
+                Some(v) => self.map.insert(k.to_vec(), v.to_vec()),
+                None => self.map.remove(*k),
+            };
+        }
+
+        Ok(())
+    }
+
+    fn iter_prefix(&self, prefix: &[u8]) -> Result<Vec<KVEntry>, KVError> {
+        let entries = self
+            .map
+            .range(prefix.to_vec()..)
+            .take_while(|(k, _v)| k.starts_with(prefix))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+
+        Ok(entries)
+    }
+
+    fn iter_range(&self, start: &[u8], end: &[u8]) -> Result<Vec<KVEntry>, KVError> {
+        if start >= end {
+            return Ok(Vec::new());
+        }
+
+        let entries = self
+            .map
+            .range(start.to_vec()..end.to_vec())
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+
+        Ok(entries)
     }
 }
@@ -0,0 +1,171 @@
+use crate::{
+    error::KVError,
+    traits::{KVChange, KVEntry, KVStore, Overlay},
+};
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use log::{debug, info};
+
+/// A `KVStore` buffering its writes in-memory on top of a base `KVStore`.
+///
+/// * Reads see the overlay own writes first (a deleted key is hidden even when the base store has it).
+/// * Nothing is written to the base store until `commit` (see the `Overlay` trait).
+///   Calling `discard` drops the buffered writes.
+/// * Overlays can be stacked (`OverlayKV<OverlayKV<KV>>`). Committing the top overlay
+///   moves its writes into the overlay beneath it.
+pub struct OverlayKV<KV: KVStore> {
+    base: Rc<RefCell<KV>>,
+
+    /// `None` marks a deleted key
+    changes: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+}
+
+impl<KV: KVStore> OverlayKV<KV> {
+    /// Creates a new empty overlay on top of `base`.
+    pub fn new(base: Rc<RefCell<KV>>) -> Self {
+        info!("creating a new overlay kv");
+
+        Self {
+            base,
+            changes: BTreeMap::new(),
+        }
+    }
+
+    /// The underlying base store.
+    pub fn base(&self) -> &Rc<RefCell<KV>> {
+        &self.base
+    }
+
+    /// The number of buffered (stored or deleted) keys.
+    pub fn pending_count(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// The buffered changes, sorted by key (a `None` value marks a deleted key).
+    pub fn changes(&self) -> Vec<KVChange> {
+        self.changes
+            .iter()
+            .map(|(k, v)| (&k[..], v.as_ref().map(|v| &v[..])))
+            .collect()
+    }
+}
+
+impl<KV: KVStore> KVStore for OverlayKV<KV> {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, KVError> {
+        match self.changes.get(key) {
+            Some(v) => Ok(v.clone()),
+            None => self.base.borrow().get(key),
+        }
+    }
+
+    /// Reads the keys not changed by the overlay using a single `get_many` of the base store.
+    fn get_many(&self, keys: &[&[u8]]) -> Result<Vec<Option<Vec<u8>>>, KVError> {
+        let missing: Vec<&[u8]> = keys
+            .iter()
+            .filter(|k| !self.changes.contains_key(**k))
+            .cloned()
+            .collect();
+
+        let mut base_values = self.base.borrow().get_many(&missing)?.into_iter();
+
+        let values = keys
+            .iter()
+            .map(|k| match self.changes.get(*k) {
+                Some(v) => v.clone(),
+                None => base_values.next().unwrap(),
+            })
+            .collect();
+
+        Ok(values)
+    }
+
+    fn store(&mut self, changes: &[(&[u8], &[u8])]) -> Result<(), KVError> {
+        for (k, v) in changes {
+            self.changes.insert(k.to_vec(), Some(v.to_vec()));
+        }
+
+        Ok(())
+    }
+
+    fn delete(&mut self, keys: &[&[u8]]) -> Result<(), KVError> {
+        for k in keys {
+            self.changes.insert(k.to_vec(), None);
+        }
+
+        Ok(())
+    }
+
+    fn iter_prefix(&self, prefix: &[u8]) -> Result<Vec<KVEntry>, KVError> {
+        let base_entries = self.base.borrow().iter_prefix(prefix)?;
+
+        Ok(merge_changes(base_entries, &self.changes, prefix, |k| {
+            k.starts_with(prefix)
+        }))
+    }
+
+    fn iter_range(&self, start: &[u8], end: &[u8]) -> Result<Vec<KVEntry>, KVError> {
+        if start >= end {
+            return Ok(Vec::new());
+        }
+
+        let base_entries = self.base.borrow().iter_range(start, end)?;
+
+        Ok(merge_changes(base_entries, &self.changes, start, |k| {
+            k < end
+        }))
+    }
+}
+
+impl<KV: KVStore> Overlay for OverlayKV<KV> {
+    fn commit(&mut self) -> Result<(), KVError> {
+        debug!("committing {} overlay changes", self.changes.len());
+
+        {
+            let changes = self.changes();
+
+            // a single write (atomic, when supported by the base store).
+            // on failure, the changes are kept (re-committing them later is safe)
+            self.base.borrow_mut().write(&changes)?;
+        }
+
+        self.changes.clear();
+
+        Ok(())
+    }
+
+    fn discard(&mut self) {
+        debug!("discarding {} overlay changes", self.changes.len());
+
+        self.changes.clear();
+    }
+}
+
+/// Applies the buffered `changes` of keys starting at `start` (while `in_range` holds) on top of `base_entries`.
+/// (a `None` value of `changes` marks a deleted key).
+pub(crate) fn merge_changes<F>(
+    base_entries: Vec<KVEntry>,
+    changes: &BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+    start: &[u8],
+    in_range: F,
+) -> Vec<KVEntry>
+where
+    F: Fn(&[u8]) -> bool,
+{
+    let mut entries: BTreeMap<Vec<u8>, Vec<u8>> = base_entries.into_iter().collect();
+
+    let changes = changes
+        .range(start.to_vec()..)
+        .take_while(|(k, _v)| in_range(k));
+
+    for (k, v) in changes {
+        match v {
+            Some(v) => entries.insert(k.clone(), v.clone()),
+            None => entries.remove(k),
+        };
+    }
+
+    entries.into_iter().collect()
+}
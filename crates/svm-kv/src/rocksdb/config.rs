@@ -0,0 +1,49 @@
+/// Compression applied to the data stored under `rocksdb`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RocksdbCompression {
+    /// No compression
+    None,
+
+    /// `lz4` compression
+    Lz4,
+}
+
+/// `rocksdb` tuning options
+#[derive(Debug, Clone, PartialEq)]
+pub struct RocksdbConfig {
+    /// Size (in bytes) of the `rocksdb` block cache
+    pub cache_size: usize,
+
+    /// Compression applied to the stored data
+    pub compression: RocksdbCompression,
+}
+
+impl Default for RocksdbConfig {
+    fn default() -> Self {
+        Self {
+            cache_size: 8 * 1024 * 1024,
+            compression: RocksdbCompression::Lz4,
+        }
+    }
+}
+
+impl RocksdbConfig {
+    pub(crate) fn options(&self) -> rocksdb::Options {
+        let mut opts = rocksdb::Options::default();
+
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+
+        let compression = match self.compression {
+            RocksdbCompression::None => rocksdb::DBCompressionType::None,
+            RocksdbCompression::Lz4 => rocksdb::DBCompressionType::Lz4,
+        };
+        opts.set_compression_type(compression);
+
+        let mut block_opts = rocksdb::BlockBasedOptions::default();
+        block_opts.set_lru_cache(self.cache_size);
+        opts.set_block_based_table_factory(&block_opts);
+
+        opts
+    }
+}
@@ -1,11 +1,24 @@
-use crate::traits::KVStore;
+use crate::{
+    error::KVError,
+    rocksdb::RocksdbConfig,
+    traits::{KVBackend, KVChange, KVEntry, KVStore},
+};
+use std::cell::RefCell;
 use std::path::Path;
+use std::rc::Rc;
 
 use log::info;
 
+/// The maximum number of threads serving a `get_many` (see `Rocksdb::get_many`).
+const MAX_READ_THREADS: usize = 8;
+
 /// An implementation of `KVStore` trait against `rocksdb`.
+///
+/// A `Rocksdb` is a handle to a column family of an opened `rocksdb` database.
+/// Many handles (one per column family) may share the same database (see `column_family`).
 pub struct Rocksdb {
-    pub(crate) db: rocksdb::DB,
+    pub(crate) db: Rc<rocksdb::DB>,
+    cf: Option<String>,
 }
 
 impl Rocksdb {
@@ -14,39 +27,258 @@ impl Rocksdb {
         info!("opening rocksdb. (path = \"{}\")", path.as_ref().display());
 
         Self {
-            db: rocksdb::DB::open_default(path).unwrap(),
+            db: Rc::new(rocksdb::DB::open_default(path).unwrap()),
+            cf: None,
+        }
+    }
+
+    /// Opens (or creates) a `rocksdb` database under the given `path`, tuned by `config`
+    /// and having column families `column_families`.
+    /// The returned handle is of the `default` column family.
+    pub fn open<P: AsRef<Path>>(
+        path: P,
+        config: &RocksdbConfig,
+        column_families: &[&str],
+    ) -> Result<Self, String> {
+        info!(
+            "opening rocksdb. (path = \"{}\", config = {:?}, column families = {:?})",
+            path.as_ref().display(),
+            config,
+            column_families
+        );
+
+        let db = rocksdb::DB::open_cf(&config.options(), path, column_families)
+            .or_else(|e| Err(format!("failed opening rocksdb: {}", e)))?;
+
+        Ok(Self {
+            db: Rc::new(db),
+            cf: None,
+        })
+    }
+
+    /// Returns a handle to column family `name`, sharing the same underlying database.
+    /// The column family must have been declared when opening the database.
+    pub fn column_family(&self, name: &str) -> Self {
+        assert!(
+            self.db.cf_handle(name).is_some(),
+            "unknown column family: `{}`",
+            name
+        );
+
+        Self {
+            db: Rc::clone(&self.db),
+            cf: Some(name.to_string()),
         }
     }
+
+    #[inline]
+    fn cf_handle(&self, name: &str) -> rocksdb::ColumnFamily {
+        // column families existence is asserted by `column_family`
+        self.db.cf_handle(name).unwrap()
+    }
+}
+
+impl Rocksdb {
+    /// Returns an iterator starting at `start` (inclusive) in ascending keys order.
+    fn iter_from(&self, start: &[u8]) -> Result<rocksdb::DBIterator, KVError> {
+        let mode = rocksdb::IteratorMode::From(start, rocksdb::Direction::Forward);
+
+        match self.cf.as_ref() {
+            Some(name) => self
+                .db
+                .iterator_cf(self.cf_handle(name), mode)
+                .or_else(|e| Err(KVError::ReadFailed(format!("failed iterating: {}", e)))),
+            None => Ok(self.db.iterator(mode)),
+        }
+    }
+
+    /// Adds `changes` of column family `cf` (`None` stands for the `default` column family) to `batch`.
+    fn batch_changes(
+        &self,
+        batch: &mut rocksdb::WriteBatch,
+        cf: Option<&str>,
+        changes: &[KVChange],
+    ) -> Result<(), KVError> {
+        for (k, v) in changes {
+            let res = match (cf, v) {
+                (Some(name), Some(v)) => batch.put_cf(self.cf_handle(name), k, v),
+                (Some(name), None) => batch.delete_cf(self.cf_handle(name), k),
+                (None, Some(v)) => batch.put(k, v),
+                (None, None) => batch.delete(k),
+            };
+
+            res.or_else(|e| {
+                Err(KVError::WriteFailed(format!(
+                    "failed adding data to batch: {}",
+                    e
+                )))
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn db_write(&self, batch: rocksdb::WriteBatch) -> Result<(), KVError> {
+        self.db.write(batch).or_else(|e| {
+            Err(KVError::WriteFailed(format!(
+                "failed `write`-ing batch: {}",
+                e
+            )))
+        })
+    }
+}
+
+/// Reads `key` of column family `cf` (`None` stands for the `default` column family).
+fn db_get(db: &rocksdb::DB, cf: Option<&str>, key: &[u8]) -> Result<Option<Vec<u8>>, KVError> {
+    let res = match cf {
+        // column families existence is asserted by `column_family`
+        Some(name) => db.get_cf(db.cf_handle(name).unwrap(), key),
+        None => db.get(key),
+    };
+
+    match res {
+        Ok(dbvec) => Ok(dbvec.map(|dbvec| dbvec.to_vec())),
+        Err(e) => Err(KVError::ReadFailed(format!(
+            "Error reading key `{:?}`: {}",
+            key, e
+        ))),
+    }
 }
 
 impl KVStore for Rocksdb {
-    #[allow(clippy::match_wild_err_arm)]
-    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
-        match self.db.get(key) {
-            Ok(dbvec) => match dbvec {
-                None => None,
-                Some(dbvec) => Some(dbvec.to_vec()),
-            },
-            Err(_) => panic!("Error reading key: `{:?}`", key),
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, KVError> {
+        db_get(&self.db, self.cf.as_ref().map(String::as_str), key)
+    }
+
+    /// Reads `keys` in parallel. The keys are split between up to `MAX_READ_THREADS` threads,
+    /// each issuing its reads against the database (which is safe to be read concurrently).
+    fn get_many(&self, keys: &[&[u8]]) -> Result<Vec<Option<Vec<u8>>>, KVError> {
+        if keys.len() <= 1 {
+            return keys.iter().map(|k| self.get(k)).collect();
         }
+
+        let db: &rocksdb::DB = &self.db;
+        let cf = self.cf.as_ref().map(String::as_str);
+
+        let threads = std::cmp::min(keys.len(), MAX_READ_THREADS);
+        let chunk_size = (keys.len() + threads - 1) / threads;
+
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = keys
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || chunk.iter().map(|k| db_get(db, cf, k)).collect::<Vec<_>>())
+                })
+                .collect();
+
+            let mut values = Vec::with_capacity(keys.len());
+
+            for handle in handles {
+                let chunk_values = handle.join().or_else(|_e| {
+                    Err(KVError::ReadFailed(
+                        "a `rocksdb` read thread has panicked".to_string(),
+                    ))
+                })?;
+
+                for v in chunk_values {
+                    values.push(v?);
+                }
+            }
+
+            Ok(values)
+        })
     }
 
-    fn store(&mut self, changes: &[(&[u8], &[u8])]) {
+    /// Stores a batch of changes atomically.
+    fn store(&mut self, changes: &[(&[u8], &[u8])]) -> Result<(), KVError> {
         let mut batch = rocksdb::WriteBatch::default();
 
         for (k, v) in changes {
-            let res = batch.put(k, v);
+            let res = match self.cf.as_ref() {
+                Some(name) => batch.put_cf(self.cf_handle(name), k, v),
+                None => batch.put(k, v),
+            };
 
-            if res.is_err() {
-                panic!("failed `put`-ing bach data");
-            }
+            res.or_else(|e| {
+                Err(KVError::WriteFailed(format!(
+                    "failed `put`-ing batch data: {}",
+                    e
+                )))
+            })?;
         }
 
-        let res = self.db.write(batch);
+        self.db_write(batch)
+    }
+
+    /// Deletes `keys` as a single `rocksdb` batch.
+    fn delete(&mut self, keys: &[&[u8]]) -> Result<(), KVError> {
+        let mut batch = rocksdb::WriteBatch::default();
+
+        for k in keys {
+            let res = match self.cf.as_ref() {
+                Some(name) => batch.delete_cf(self.cf_handle(name), k),
+                None => batch.delete(k),
+            };
 
-        if res.is_err() {
-            panic!("failed `write`-ing bach");
+            res.or_else(|e| {
+                Err(KVError::WriteFailed(format!(
+                    "failed `delete`-ing batch data: {}",
+                    e
+                )))
+            })?;
         }
+
+        self.db_write(batch)
+    }
+
+    /// Writes `changes` atomically (as a single `rocksdb` batch).
+    fn write(&mut self, changes: &[KVChange]) -> Result<(), KVError> {
+        let mut batch = rocksdb::WriteBatch::default();
+
+        let cf = self.cf.as_ref().map(String::as_str);
+        self.batch_changes(&mut batch, cf, changes)?;
+
+        self.db_write(batch)
+    }
+
+    fn iter_prefix(&self, prefix: &[u8]) -> Result<Vec<KVEntry>, KVError> {
+        let entries = self
+            .iter_from(prefix)?
+            .take_while(|(k, _v)| k.starts_with(prefix))
+            .map(|(k, v)| (k.to_vec(), v.to_vec()))
+            .collect();
+
+        Ok(entries)
+    }
+
+    fn iter_range(&self, start: &[u8], end: &[u8]) -> Result<Vec<KVEntry>, KVError> {
+        let entries = self
+            .iter_from(start)?
+            .take_while(|(k, _v)| &k[..] < end)
+            .map(|(k, v)| (k.to_vec(), v.to_vec()))
+            .collect();
+
+        Ok(entries)
+    }
+}
+
+/// A `rocksdb` database serves as a backend whose stores are its column families.
+impl KVBackend for Rocksdb {
+    type Store = Rocksdb;
+
+    fn open_store(&self, name: &str) -> Rc<RefCell<Rocksdb>> {
+        Rc::new(RefCell::new(self.column_family(name)))
+    }
+
+    /// Writes `batch` as a single `rocksdb` batch (spanning the database column families).
+    fn write_batch(&mut self, batch: &[(&str, &[KVChange])]) -> Result<(), KVError> {
+        let mut wb = rocksdb::WriteBatch::default();
+
+        for (name, changes) in batch {
+            self.batch_changes(&mut wb, Some(*name), changes)?;
+        }
+
+        self.db_write(wb)
     }
 }
 
@@ -64,15 +296,42 @@ mod tests {
     fn rocksdb_sanity() {
         let mut db = Rocksdb::new("rocksdb-tests");
 
-        db.store(&[(&[10, 20, 30], &[40, 50, 60])]);
+        db.store(&[(&[10, 20, 30], &[40, 50, 60])]).unwrap();
 
-        let v = db.get(&[10, 20, 30]).unwrap();
+        let v = db.get(&[10, 20, 30]).unwrap().unwrap();
         assert_eq!(vec![40, 50, 60], v);
 
         drop(db);
 
-        let db = Rocksdb::new("rocksdb-tests");
-        let v = db.get(&[10, 20, 30]).unwrap();
+        let mut db = Rocksdb::new("rocksdb-tests");
+        let v = db.get(&[10, 20, 30]).unwrap().unwrap();
         assert_eq!(vec![40, 50, 60], v);
+
+        db.delete(&[&[10, 20, 30]]).unwrap();
+        assert_eq!(Ok(None), db.get(&[10, 20, 30]));
+    }
+
+    #[test]
+    fn rocksdb_column_families() {
+        let config = RocksdbConfig::default();
+        let db = Rocksdb::open("rocksdb-cf-tests", &config, &["foo", "bar"]).unwrap();
+
+        let mut foo = db.column_family("foo");
+        let bar = db.column_family("bar");
+
+        foo.store(&[(&[10, 20, 30], &[40, 50, 60])]).unwrap();
+
+        assert_eq!(Ok(Some(vec![40, 50, 60])), foo.get(&[10, 20, 30]));
+        assert_eq!(Ok(None), bar.get(&[10, 20, 30]));
+        assert_eq!(Ok(None), db.get(&[10, 20, 30]));
+
+        drop(foo);
+        drop(bar);
+        drop(db);
+
+        let db = Rocksdb::open("rocksdb-cf-tests", &config, &["foo", "bar"]).unwrap();
+        let foo = db.column_family("foo");
+
+        assert_eq!(Ok(Some(vec![40, 50, 60])), foo.get(&[10, 20, 30]));
     }
 }
@@ -1,3 +1,5 @@
+mod config;
 mod db;
 
+pub use config::{RocksdbCompression, RocksdbConfig};
 pub use db::Rocksdb;
@@ -1,9 +1,83 @@
+use crate::error::KVError;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// A key-value entry (`key`, `value`) as returned by the iteration methods.
+pub type KVEntry = (Vec<u8>, Vec<u8>);
+
+/// A change written by `KVStore::write` (`key`, `value`). A `None` value marks a deleted key.
+pub type KVChange<'a> = (&'a [u8], Option<&'a [u8]>);
+
 /// `KVStore` is a trait for defining an interface against key-value stores. for example `in-memory / rocksdb`
+///
+/// Each operation returns a `KVError` when the underlying store fails,
+/// leaving it up to the caller to decide how to surface the failure.
 pub trait KVStore {
     /// Retrieves the value pointed by `key` (Optional).
-    #[must_use]
-    fn get(&self, key: &[u8]) -> Option<Vec<u8>>;
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, KVError>;
+
+    /// Retrieves the values pointed by `keys` (in the same order).
+    ///
+    /// The default implementation reads the keys one after the other.
+    /// Stores able to serve many reads at once should override it.
+    fn get_many(&self, keys: &[&[u8]]) -> Result<Vec<Option<Vec<u8>>>, KVError> {
+        keys.iter().map(|k| self.get(k)).collect()
+    }
 
     /// Stores a batch of changes. Each change is `key` -> `value` association.
-    fn store(&mut self, changes: &[(&[u8], &[u8])]);
+    fn store(&mut self, changes: &[(&[u8], &[u8])]) -> Result<(), KVError>;
+
+    /// Deletes a batch of keys. Deleting a non-existing key does nothing.
+    fn delete(&mut self, keys: &[&[u8]]) -> Result<(), KVError>;
+
+    /// Returns the entries whose key starts with `prefix`, sorted by key.
+    fn iter_prefix(&self, prefix: &[u8]) -> Result<Vec<KVEntry>, KVError>;
+
+    /// Returns the entries whose key is within `[start, end)`, sorted by key.
+    fn iter_range(&self, start: &[u8], end: &[u8]) -> Result<Vec<KVEntry>, KVError>;
+
+    /// Writes a batch of stores and deletes (see `KVChange`). Each key may appear only once.
+    ///
+    /// The default implementation stores and then deletes (i.e two separate writes).
+    /// Stores able to apply the whole batch atomically should override it.
+    fn write(&mut self, changes: &[KVChange]) -> Result<(), KVError> {
+        let stores: Vec<(&[u8], &[u8])> = changes
+            .iter()
+            .filter_map(|(k, v)| v.map(|v| (*k, v)))
+            .collect();
+
+        let deletes: Vec<&[u8]> = changes
+            .iter()
+            .filter(|(_k, v)| v.is_none())
+            .map(|(k, _v)| *k)
+            .collect();
+
+        self.store(&stores)?;
+        self.delete(&deletes)
+    }
+}
+
+/// A key-value backend made of many named stores (for example, a `rocksdb` database and its column families).
+/// Writes spanning many of its stores are applied as a single atomic batch (see `crate::unit_of_work::UnitOfWork`).
+pub trait KVBackend {
+    /// The backend stores type
+    type Store: KVStore;
+
+    /// Returns the store named `name`.
+    fn open_store(&self, name: &str) -> Rc<RefCell<Self::Store>>;
+
+    /// Writes `batch` atomically. Each item of `batch` is a store name along with its changes.
+    fn write_batch(&mut self, batch: &[(&str, &[KVChange])]) -> Result<(), KVError>;
+}
+
+/// An `Overlay` buffers writes on top of an underlying store until they're committed or discarded.
+/// (see `crate::overlay::OverlayKV`)
+pub trait Overlay {
+    /// Writes the buffered changes into the underlying store.
+    /// On failure, the buffered changes are kept.
+    fn commit(&mut self) -> Result<(), KVError>;
+
+    /// Drops the buffered changes.
+    fn discard(&mut self);
 }
@@ -0,0 +1,126 @@
+use crate::{
+    error::KVError,
+    overlay::OverlayKV,
+    traits::{KVBackend, KVChange, KVStore, Overlay},
+};
+
+use std::cell::{Ref, RefCell};
+use std::rc::Rc;
+
+use log::{debug, info};
+
+/// A store opened through a `UnitOfWork`
+pub type UnitOfWorkStore<KV> = Rc<RefCell<OverlayKV<KV>>>;
+
+/// Gathers the writes of many stores of a `KVBackend`, and commits them as a single atomic batch.
+///
+/// * Each store opened through the unit-of-work (see `open_store`) buffers its writes using an `OverlayKV`.
+/// * `commit` (see the `Overlay` trait) writes the changes of all the stores using a single `KVBackend::write_batch`.
+///   So either all the changes are persisted or none of them (on failure, the changes are kept).
+/// * `discard` drops the changes of all the stores.
+pub struct UnitOfWork<B: KVBackend> {
+    backend: B,
+
+    stores: Vec<(String, UnitOfWorkStore<B::Store>)>,
+}
+
+impl<B: KVBackend> UnitOfWork<B> {
+    /// Creates a new unit-of-work over `backend`
+    pub fn new(backend: B) -> Self {
+        info!("creating a new unit-of-work");
+
+        Self {
+            backend,
+            stores: Vec::new(),
+        }
+    }
+
+    /// Opens store `name` of the backend, having its writes buffered by the unit-of-work.
+    /// Opening the same store again returns the same (shared) overlay.
+    pub fn open_store(&mut self, name: &str) -> UnitOfWorkStore<B::Store> {
+        if let Some((_name, store)) = self.stores.iter().find(|(n, _store)| n == name) {
+            return Rc::clone(store);
+        }
+
+        let store = Rc::new(RefCell::new(OverlayKV::new(self.backend.open_store(name))));
+
+        self.stores.push((name.to_string(), Rc::clone(&store)));
+
+        store
+    }
+
+    /// The underlying backend
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// The number of buffered (stored or deleted) keys, summed over all the stores.
+    pub fn pending_count(&self) -> usize {
+        self.stores
+            .iter()
+            .map(|(_name, store)| store.borrow().pending_count())
+            .sum()
+    }
+}
+
+impl<B: KVBackend> Overlay for UnitOfWork<B> {
+    fn commit(&mut self) -> Result<(), KVError> {
+        debug!("committing unit-of-work ({} changes)", self.pending_count());
+
+        {
+            let stores: Vec<(&str, Ref<OverlayKV<B::Store>>)> = self
+                .stores
+                .iter()
+                .map(|(name, store)| (&name[..], store.borrow()))
+                .collect();
+
+            let changes: Vec<(&str, Vec<KVChange>)> = stores
+                .iter()
+                .map(|(name, store)| (*name, store.changes()))
+                .filter(|(_name, changes)| !changes.is_empty())
+                .collect();
+
+            let batch: Vec<(&str, &[KVChange])> = changes
+                .iter()
+                .map(|(name, changes)| (*name, &changes[..]))
+                .collect();
+
+            // on failure, the changes are kept (re-committing them later is safe)
+            self.backend.write_batch(&batch)?;
+        }
+
+        for (_name, store) in self.stores.iter() {
+            store.borrow_mut().discard();
+        }
+
+        Ok(())
+    }
+
+    fn discard(&mut self) {
+        debug!("discarding unit-of-work ({} changes)", self.pending_count());
+
+        for (_name, store) in self.stores.iter() {
+            store.borrow_mut().discard();
+        }
+    }
+}
+
+/// A single `KVStore` serves as a backend, all of whose stores are the store itself.
+/// (so the keys of the different stores must not collide).
+impl<KV: KVStore> KVBackend for Rc<RefCell<KV>> {
+    type Store = KV;
+
+    fn open_store(&self, _name: &str) -> Rc<RefCell<KV>> {
+        Rc::clone(self)
+    }
+
+    /// Writes `batch` as a single `KVStore::write`.
+    fn write_batch(&mut self, batch: &[(&str, &[KVChange])]) -> Result<(), KVError> {
+        let changes: Vec<KVChange> = batch
+            .iter()
+            .flat_map(|(_name, changes)| changes.iter().cloned())
+            .collect();
+
+        self.borrow_mut().write(&changes)
+    }
+}
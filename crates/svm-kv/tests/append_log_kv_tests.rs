@@ -0,0 +1,312 @@
+#![cfg(feature = "append-log")]
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+use svm_kv::append_log::{AppendLogKV, LOG_HEADER_SIZE};
+use svm_kv::traits::KVStore;
+use svm_kv::KVError;
+
+mod asserts;
+
+fn init() {
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+/// Returns the path of a new (non-existing) log (each test has its own log file).
+fn log_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("svm-append-log-{}", name));
+    let _ = fs::remove_file(&path);
+
+    path
+}
+
+fn log_open(name: &str) -> AppendLogKV {
+    AppendLogKV::open(log_path(name)).unwrap()
+}
+
+fn append_raw(path: &PathBuf, bytes: &[u8]) {
+    let mut file = OpenOptions::new().append(true).open(path).unwrap();
+    file.write_all(bytes).unwrap();
+}
+
+#[test]
+fn append_log_a_key_does_not_exit_by_default() {
+    init();
+
+    asserts::assert_missing_key(&mut log_open("missing-key"));
+}
+
+#[test]
+fn append_log_key_store_and_then_key_get() {
+    init();
+
+    asserts::assert_store_and_get(&mut log_open("store-get"));
+}
+
+#[test]
+fn append_log_key_store_override_existing_entry() {
+    init();
+
+    asserts::assert_store_overrides_existing_entry(&mut log_open("override"));
+}
+
+#[test]
+fn append_log_delete_keys() {
+    init();
+
+    asserts::assert_delete_keys(&mut log_open("delete"));
+}
+
+#[test]
+fn append_log_iter_prefix() {
+    init();
+
+    asserts::assert_iter_prefix(&mut log_open("iter-prefix"));
+}
+
+#[test]
+fn append_log_iter_range() {
+    init();
+
+    asserts::assert_iter_range(&mut log_open("iter-range"));
+}
+
+#[test]
+fn append_log_write_stores_and_deletes() {
+    init();
+
+    asserts::assert_write_stores_and_deletes(&mut log_open("write"));
+}
+
+#[test]
+fn append_log_get_many() {
+    init();
+
+    asserts::assert_get_many(&mut log_open("get-many"));
+}
+
+#[test]
+fn append_log_reopen_replays_the_log() {
+    init();
+
+    let path = log_path("reopen");
+    let mut kv = AppendLogKV::open(&path).unwrap();
+
+    kv.store(&[(b"a", b"10"), (b"b", b"20"), (b"c", b"30")])
+        .unwrap();
+    kv.store(&[(b"a", b"40")]).unwrap();
+    kv.delete(&[b"b"]).unwrap();
+
+    let size = kv.size();
+    let stale_bytes = kv.stale_bytes();
+    drop(kv);
+
+    let kv = AppendLogKV::open(&path).unwrap();
+
+    assert_eq!(size, kv.size());
+    assert_eq!(stale_bytes, kv.stale_bytes());
+
+    assert_eq!(Some(b"40".to_vec()), kv.get(b"a").unwrap());
+    assert_eq!(None, kv.get(b"b").unwrap());
+    assert_eq!(Some(b"30".to_vec()), kv.get(b"c").unwrap());
+}
+
+#[test]
+fn append_log_discards_a_torn_batch() {
+    init();
+
+    let path = log_path("torn-batch");
+    let mut kv = AppendLogKV::open(&path).unwrap();
+
+    kv.store(&[(b"a", b"10")]).unwrap();
+    let size = kv.size();
+    drop(kv);
+
+    // a crash while appending a batch of `b -> 20`
+    let source_path = log_path("torn-batch-source");
+    let mut source = AppendLogKV::open(&source_path).unwrap();
+    source.store(&[(b"b", b"20")]).unwrap();
+
+    let batch = fs::read(&source_path).unwrap()[LOG_HEADER_SIZE as usize..].to_vec();
+
+    append_raw(&path, &batch[0..batch.len() - 1]);
+
+    let mut kv = AppendLogKV::open(&path).unwrap();
+
+    assert_eq!(size, kv.size());
+    assert_eq!(size, fs::metadata(&path).unwrap().len());
+
+    assert_eq!(Some(b"10".to_vec()), kv.get(b"a").unwrap());
+    assert_eq!(None, kv.get(b"b").unwrap());
+
+    // the log is usable after the recovery
+    kv.store(&[(b"b", b"30")]).unwrap();
+    drop(kv);
+
+    let kv = AppendLogKV::open(&path).unwrap();
+    assert_eq!(Some(b"30".to_vec()), kv.get(b"b").unwrap());
+}
+
+#[test]
+fn append_log_rejects_a_corrupted_batch() {
+    init();
+
+    let path = log_path("corrupted-batch");
+    let mut kv = AppendLogKV::open(&path).unwrap();
+
+    kv.store(&[(b"a", b"10")]).unwrap();
+    kv.store(&[(b"b", b"20")]).unwrap();
+    drop(kv);
+
+    // flipping the value of `a` (the batch is followed by another one, so it isn't a torn batch)
+    let mut bytes = fs::read(&path).unwrap();
+    let idx = LOG_HEADER_SIZE as usize + 8 + 9 + 1;
+    assert_eq!(b'1', bytes[idx]);
+    bytes[idx] = b'2';
+    fs::write(&path, &bytes).unwrap();
+
+    match AppendLogKV::open(&path) {
+        Err(KVError::DataCorruption(..)) => (),
+        Err(e) => panic!("unexpected error: {}", e),
+        Ok(..) => panic!("expected a data corruption"),
+    }
+}
+
+#[test]
+fn append_log_rejects_a_batch_of_corrupted_length() {
+    init();
+
+    let path = log_path("corrupted-batch-length");
+    let mut kv = AppendLogKV::open(&path).unwrap();
+
+    kv.store(&[(b"a", b"10")]).unwrap();
+    kv.store(&[(b"b", b"20")]).unwrap();
+    drop(kv);
+
+    // the first batch seems incomplete, but it's followed by another batch (so it isn't a torn batch)
+    let mut bytes = fs::read(&path).unwrap();
+    let idx = LOG_HEADER_SIZE as usize + 2;
+    bytes[idx] = 0xFF;
+    fs::write(&path, &bytes).unwrap();
+
+    match AppendLogKV::open(&path) {
+        Err(KVError::DataCorruption(..)) => (),
+        Err(e) => panic!("unexpected error: {}", e),
+        Ok(..) => panic!("expected a data corruption"),
+    }
+
+    // the log isn't truncated
+    assert_eq!(bytes, fs::read(&path).unwrap());
+}
+
+#[test]
+fn append_log_rejects_a_foreign_file() {
+    init();
+
+    let path = log_path("foreign-file");
+    fs::write(&path, b"not an append-log").unwrap();
+
+    match AppendLogKV::open(&path) {
+        Err(KVError::DataCorruption(..)) => (),
+        Err(e) => panic!("unexpected error: {}", e),
+        Ok(..) => panic!("expected a data corruption"),
+    }
+}
+
+#[test]
+fn append_log_compaction() {
+    init();
+
+    let path = log_path("compaction");
+    let mut kv = AppendLogKV::open(&path).unwrap();
+
+    for i in 0..100u8 {
+        kv.store(&[(&[i % 10], &[i])]).unwrap();
+    }
+
+    kv.delete(&[&[0], &[1]]).unwrap();
+
+    let size = kv.size();
+    assert!(kv.stale_bytes() > 0);
+
+    kv.compact().unwrap();
+
+    assert!(kv.size() < size);
+    assert_eq!(0, kv.stale_bytes());
+    assert_eq!(kv.size(), fs::metadata(&path).unwrap().len());
+
+    let expected: Vec<(Vec<u8>, Vec<u8>)> = (2..10u8).map(|i| (vec![i], vec![90 + i])).collect();
+    assert_eq!(expected, kv.iter_prefix(&[]).unwrap());
+
+    // writes after the compaction are appended to the compacted log
+    kv.store(&[(&[0], &[100])]).unwrap();
+    drop(kv);
+
+    let kv = AppendLogKV::open(&path).unwrap();
+
+    assert_eq!(Some(vec![100]), kv.get(&[0]).unwrap());
+    assert_eq!(None, kv.get(&[1]).unwrap());
+    assert_eq!(9, kv.iter_prefix(&[]).unwrap().len());
+}
+
+#[test]
+fn append_log_removes_an_interrupted_compaction() {
+    init();
+
+    let path = log_path("interrupted-compaction");
+    let mut kv = AppendLogKV::open(&path).unwrap();
+    kv.store(&[(b"a", b"10")]).unwrap();
+    drop(kv);
+
+    let mut compaction_path = path.clone().into_os_string();
+    compaction_path.push(".compaction");
+    fs::write(&compaction_path, b"SVML").unwrap();
+
+    let kv = AppendLogKV::open(&path).unwrap();
+
+    assert_eq!(Some(b"10".to_vec()), kv.get(b"a").unwrap());
+    assert!(!PathBuf::from(compaction_path).exists());
+}
+
+#[test]
+fn append_log_failed_automatic_compaction_keeps_the_written_batch() {
+    init();
+
+    let path = log_path("failed-compaction");
+    let mut kv = AppendLogKV::open(&path).unwrap();
+
+    // a directory in place of the compaction file fails the compaction
+    let mut compaction_path = path.clone().into_os_string();
+    compaction_path.push(".compaction");
+    let compaction_path = PathBuf::from(compaction_path);
+    let _ = fs::remove_dir(&compaction_path);
+    fs::create_dir(&compaction_path).unwrap();
+
+    let value = vec![0xAA; 600 * 1024];
+
+    // the third `store` makes the stale bytes exceed both the compaction threshold and the live bytes
+    for _ in 0..3 {
+        kv.store(&[(b"a", &value)]).unwrap();
+    }
+
+    let stale_bytes = kv.stale_bytes();
+    assert!(stale_bytes > 0);
+
+    kv.store(&[(b"b", b"20")]).unwrap();
+    assert!(kv.stale_bytes() >= stale_bytes);
+
+    fs::remove_dir(&compaction_path).unwrap();
+
+    // the log is intact (and compacted by the next write)
+    kv.store(&[(b"c", b"30")]).unwrap();
+    assert_eq!(0, kv.stale_bytes());
+    drop(kv);
+
+    let kv = AppendLogKV::open(&path).unwrap();
+
+    assert_eq!(Some(value), kv.get(b"a").unwrap());
+    assert_eq!(Some(b"20".to_vec()), kv.get(b"b").unwrap());
+    assert_eq!(Some(b"30".to_vec()), kv.get(b"c").unwrap());
+}
@@ -1,7 +1,10 @@
+use svm_common::Address;
+use svm_kv::traits::KVStore;
+
 #[macro_export]
 macro_rules! assert_key_value {
     ($kv: expr, $key: expr, $expected: expr) => {{
-        let actual = $kv.get(&$key).unwrap();
+        let actual = $kv.get(&$key).unwrap().unwrap();
         assert_eq!($expected, &actual[..]);
     }};
 }
@@ -9,7 +12,7 @@ macro_rules! assert_key_value {
 #[macro_export]
 macro_rules! assert_no_key {
     ($kv: expr, $key: expr) => {{
-        assert!($kv.get(&$key).is_none());
+        assert!($kv.get(&$key).unwrap().is_none());
     }};
 }
 
@@ -37,3 +40,134 @@ macro_rules! assert_same_keys {
         assert_eq!(&expected[..], &actual[..]);
     }};
 }
+
+// The `KVStore` conformance tests.
+// Each `KVStore` implementation runs each of them against a new empty store.
+
+pub fn assert_missing_key<KV: KVStore>(kv: &mut KV) {
+    let addr = Address::of("@someone");
+
+    assert_no_key!(kv, addr.as_slice());
+}
+
+pub fn assert_store_and_get<KV: KVStore>(kv: &mut KV) {
+    let addr = Address::of("someone");
+    kv.store(&[(addr.as_slice(), &[10, 20, 30])]).unwrap();
+
+    assert_key_value!(kv, addr.as_slice(), vec![10, 20, 30]);
+}
+
+pub fn assert_store_overrides_existing_entry<KV: KVStore>(kv: &mut KV) {
+    let addr = Address::of("someone");
+
+    kv.store(&[(addr.as_slice(), &[10, 20, 30])]).unwrap();
+    assert_key_value!(kv, addr.as_slice(), vec![10, 20, 30]);
+
+    kv.store(&[(addr.as_slice(), &[40, 50, 60])]).unwrap();
+    assert_key_value!(kv, addr.as_slice(), vec![40, 50, 60]);
+}
+
+pub fn assert_delete_keys<KV: KVStore>(kv: &mut KV) {
+    let addr1 = Address::of("Alice");
+    let addr2 = Address::of("Bob");
+    let addr3 = Address::of("Carol");
+
+    kv.store(&[
+        (addr1.as_slice(), &[10, 20, 30]),
+        (addr2.as_slice(), &[40, 50, 60]),
+    ])
+    .unwrap();
+
+    // deleting a non-existing key (`addr3`) does nothing
+    kv.delete(&[addr1.as_slice(), addr3.as_slice()]).unwrap();
+
+    assert_no_key!(kv, addr1.as_slice());
+    assert_key_value!(kv, addr2.as_slice(), vec![40, 50, 60]);
+    assert_no_key!(kv, addr3.as_slice());
+}
+
+pub fn assert_iter_prefix<KV: KVStore>(kv: &mut KV) {
+    kv.store(&[
+        (b"b:2", b"20"),
+        (b"a:1", b"10"),
+        (b"b:1", b"30"),
+        (b"b", b"40"),
+        (b"c:1", b"50"),
+    ])
+    .unwrap();
+
+    let entries = kv.iter_prefix(b"b:").unwrap();
+
+    assert_eq!(
+        vec![
+            (b"b:1".to_vec(), b"30".to_vec()),
+            (b"b:2".to_vec(), b"20".to_vec())
+        ],
+        entries
+    );
+
+    assert!(kv.iter_prefix(b"d").unwrap().is_empty());
+    assert_eq!(5, kv.iter_prefix(b"").unwrap().len());
+}
+
+pub fn assert_iter_range<KV: KVStore>(kv: &mut KV) {
+    kv.store(&[
+        (&[1], &[10]),
+        (&[2], &[20]),
+        (&[2, 0], &[30]),
+        (&[3], &[40]),
+    ])
+    .unwrap();
+
+    // `start` is inclusive, `end` is exclusive
+    let entries = kv.iter_range(&[2], &[3]).unwrap();
+
+    assert_eq!(vec![(vec![2], vec![20]), (vec![2, 0], vec![30])], entries);
+
+    assert!(kv.iter_range(&[3], &[3]).unwrap().is_empty());
+    assert!(kv.iter_range(&[3], &[1]).unwrap().is_empty());
+    assert_eq!(4, kv.iter_range(&[0], &[4]).unwrap().len());
+}
+
+pub fn assert_write_stores_and_deletes<KV: KVStore>(kv: &mut KV) {
+    let addr1 = Address::of("Alice");
+    let addr2 = Address::of("Bob");
+    let addr3 = Address::of("Carol");
+
+    kv.store(&[(addr1.as_slice(), &[10, 20, 30])]).unwrap();
+
+    kv.write(&[
+        (addr1.as_slice(), None),
+        (addr2.as_slice(), Some(&[40, 50, 60])),
+        (addr3.as_slice(), None),
+    ])
+    .unwrap();
+
+    assert_no_key!(kv, addr1.as_slice());
+    assert_key_value!(kv, addr2.as_slice(), vec![40, 50, 60]);
+    assert_no_key!(kv, addr3.as_slice());
+}
+
+pub fn assert_get_many<KV: KVStore>(kv: &mut KV) {
+    let keys: Vec<Vec<u8>> = (0..20).map(|i| vec![i]).collect();
+
+    // storing only the even keys
+    let changes: Vec<(&[u8], &[u8])> = keys.iter().step_by(2).map(|k| (&k[..], &k[..])).collect();
+    kv.store(&changes).unwrap();
+
+    let mut query: Vec<&[u8]> = keys.iter().map(|k| &k[..]).rev().collect();
+    query.push(&[4]);
+
+    let values = kv.get_many(&query).unwrap();
+    assert_eq!(query.len(), values.len());
+
+    for (k, v) in query.iter().zip(values.iter()) {
+        if k[0] % 2 == 0 {
+            assert_eq!(Some(k.to_vec()), *v);
+        } else {
+            assert_eq!(None, *v);
+        }
+    }
+
+    assert!(kv.get_many(&[]).unwrap().is_empty());
+}
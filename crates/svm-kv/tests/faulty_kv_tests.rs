@@ -0,0 +1,171 @@
+use svm_kv::{
+    faulty::{Fault, FaultyKV},
+    memory::MemKVStore,
+    traits::KVStore,
+    KVError,
+};
+
+mod asserts;
+
+fn init() {
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+fn faulty_kv() -> FaultyKV<MemKVStore> {
+    FaultyKV::new(MemKVStore::new())
+}
+
+#[test]
+fn faulty_kv_conformance() {
+    init();
+
+    asserts::assert_missing_key(&mut faulty_kv());
+    asserts::assert_store_and_get(&mut faulty_kv());
+    asserts::assert_store_overrides_existing_entry(&mut faulty_kv());
+    asserts::assert_delete_keys(&mut faulty_kv());
+    asserts::assert_iter_prefix(&mut faulty_kv());
+    asserts::assert_iter_range(&mut faulty_kv());
+    asserts::assert_write_stores_and_deletes(&mut faulty_kv());
+}
+
+#[test]
+fn faulty_kv_write_back_conformance() {
+    init();
+
+    asserts::assert_missing_key(&mut faulty_kv().with_write_back());
+    asserts::assert_store_and_get(&mut faulty_kv().with_write_back());
+    asserts::assert_store_overrides_existing_entry(&mut faulty_kv().with_write_back());
+    asserts::assert_delete_keys(&mut faulty_kv().with_write_back());
+    asserts::assert_iter_prefix(&mut faulty_kv().with_write_back());
+    asserts::assert_iter_range(&mut faulty_kv().with_write_back());
+    asserts::assert_write_stores_and_deletes(&mut faulty_kv().with_write_back());
+}
+
+#[test]
+fn faulty_kv_injected_failure() {
+    init();
+
+    let mut kv = faulty_kv();
+
+    kv.inject(1, Fault::Fail);
+
+    kv.store(&[(b"a", b"10")]).unwrap();
+
+    match kv.store(&[(b"b", b"20")]) {
+        Err(KVError::WriteFailed(..)) => (),
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    assert_no_key!(kv, b"b"[..]);
+
+    // a fault is injected once
+    kv.store(&[(b"b", b"20")]).unwrap();
+
+    assert_key_value!(kv, b"a"[..], b"10");
+    assert_key_value!(kv, b"b"[..], b"20");
+    assert_eq!(3, kv.writes_count());
+}
+
+#[test]
+fn faulty_kv_injected_partial_apply() {
+    init();
+
+    let mut kv = faulty_kv();
+
+    kv.inject(0, Fault::PartialApply(1));
+    assert!(kv.store(&[(b"a", b"10"), (b"b", b"20")]).is_err());
+
+    assert_key_value!(kv, b"a"[..], b"10");
+    assert_no_key!(kv, b"b"[..]);
+
+    kv.store(&[(b"c", b"30")]).unwrap();
+
+    kv.inject(0, Fault::PartialApply(1));
+    assert!(kv.delete(&[b"a", b"c"]).is_err());
+
+    assert_no_key!(kv, b"a"[..]);
+    assert_key_value!(kv, b"c"[..], b"30");
+}
+
+#[test]
+#[should_panic(expected = "injected panic into write #1")]
+fn faulty_kv_injected_panic() {
+    init();
+
+    let mut kv = faulty_kv();
+
+    kv.inject(1, Fault::Panic);
+
+    kv.store(&[(b"a", b"10")]).unwrap();
+    let _ = kv.delete(&[b"a"]);
+}
+
+#[test]
+fn faulty_kv_crash_drops_unflushed_writes() {
+    init();
+
+    let mut kv = faulty_kv().with_write_back();
+
+    kv.store(&[(b"a", b"10"), (b"b", b"20")]).unwrap();
+    kv.flush().unwrap();
+
+    kv.store(&[(b"a", b"30"), (b"c", b"40")]).unwrap();
+    kv.delete(&[b"b"]).unwrap();
+
+    // reads see the unflushed writes
+    assert_key_value!(kv, b"a"[..], b"30");
+    assert_no_key!(kv, b"b"[..]);
+    assert_key_value!(kv, b"c"[..], b"40");
+    assert_no_key!(kv.inner(), b"c"[..]);
+    assert_eq!(3, kv.unflushed_count());
+
+    kv.crash();
+
+    assert_eq!(0, kv.unflushed_count());
+    assert_key_value!(kv, b"a"[..], b"10");
+    assert_key_value!(kv, b"b"[..], b"20");
+    assert_no_key!(kv, b"c"[..]);
+}
+
+#[test]
+fn faulty_kv_write_is_a_single_write() {
+    init();
+
+    let mut kv = faulty_kv();
+
+    kv.store(&[(b"a", b"10")]).unwrap();
+
+    kv.inject(0, Fault::PartialApply(1));
+    assert!(kv.write(&[(b"a", None), (b"b", Some(b"20"))]).is_err());
+
+    assert_no_key!(kv, b"a"[..]);
+    assert_no_key!(kv, b"b"[..]);
+
+    kv.write(&[(b"b", Some(b"20")), (b"c", Some(b"30"))])
+        .unwrap();
+
+    assert_key_value!(kv, b"b"[..], b"20");
+    assert_key_value!(kv, b"c"[..], b"30");
+    assert_eq!(3, kv.writes_count());
+}
+
+#[test]
+fn faulty_kv_failing_reads() {
+    init();
+
+    let mut kv = faulty_kv();
+
+    kv.store(&[(b"a", b"10")]).unwrap();
+
+    kv.fail_reads(true);
+
+    match kv.get(b"a") {
+        Err(KVError::ReadFailed(..)) => (),
+        res => panic!("unexpected result: {:?}", res),
+    }
+    assert!(kv.iter_prefix(b"a").is_err());
+
+    kv.fail_reads(false);
+
+    assert_key_value!(kv, b"a"[..], b"10");
+}
@@ -11,35 +11,56 @@ fn init() {
 fn a_key_does_not_exit_by_default() {
     init();
 
-    let kv = MemKVStore::new();
-    let addr = Address::of("@someone");
-
-    assert_no_key!(kv, addr.as_slice());
+    asserts::assert_missing_key(&mut MemKVStore::new());
 }
 
 #[test]
 fn key_store_and_then_key_get() {
     init();
 
-    let mut kv = MemKVStore::new();
-    let addr = Address::of("someone");
-    kv.store(&[(addr.as_slice(), &[10, 20, 30])]);
-
-    assert_key_value!(kv, addr.as_slice(), vec![10, 20, 30]);
+    asserts::assert_store_and_get(&mut MemKVStore::new());
 }
 
 #[test]
 fn key_store_override_existing_entry() {
     init();
 
-    let mut kv = MemKVStore::new();
-    let addr = Address::of("someone");
+    asserts::assert_store_overrides_existing_entry(&mut MemKVStore::new());
+}
+
+#[test]
+fn delete_keys() {
+    init();
+
+    asserts::assert_delete_keys(&mut MemKVStore::new());
+}
 
-    kv.store(&[(addr.as_slice(), &[10, 20, 30])]);
-    assert_key_value!(kv, addr.as_slice(), vec![10, 20, 30]);
+#[test]
+fn iter_prefix() {
+    init();
+
+    asserts::assert_iter_prefix(&mut MemKVStore::new());
+}
+
+#[test]
+fn iter_range() {
+    init();
+
+    asserts::assert_iter_range(&mut MemKVStore::new());
+}
+
+#[test]
+fn write_stores_and_deletes() {
+    init();
+
+    asserts::assert_write_stores_and_deletes(&mut MemKVStore::new());
+}
+
+#[test]
+fn get_many() {
+    init();
 
-    kv.store(&[(addr.as_slice(), &[40, 50, 60])]);
-    assert_key_value!(kv, addr.as_slice(), vec![40, 50, 60]);
+    asserts::assert_get_many(&mut MemKVStore::new());
 }
 
 #[test]
@@ -53,7 +74,8 @@ fn clear() {
     kv.store(&[
         (addr1.as_slice(), &[10, 20, 30]),
         (addr2.as_slice(), &[40, 50, 60]),
-    ]);
+    ])
+    .unwrap();
 
     assert_key_value!(kv, addr1.as_slice(), vec![10, 20, 30]);
     assert_key_value!(kv, addr2.as_slice(), vec![40, 50, 60]);
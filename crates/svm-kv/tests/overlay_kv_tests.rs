@@ -0,0 +1,194 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use svm_kv::{
+    faulty::{Fault, FaultyKV},
+    memory::MemKVStore,
+    overlay::OverlayKV,
+    traits::{KVStore, Overlay},
+};
+
+mod asserts;
+
+fn init() {
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+fn overlay_init() -> (Rc<RefCell<MemKVStore>>, OverlayKV<MemKVStore>) {
+    let base = Rc::new(RefCell::new(MemKVStore::new()));
+    let overlay = OverlayKV::new(Rc::clone(&base));
+
+    (base, overlay)
+}
+
+#[test]
+fn overlay_kv_conformance() {
+    init();
+
+    asserts::assert_missing_key(&mut overlay_init().1);
+    asserts::assert_store_and_get(&mut overlay_init().1);
+    asserts::assert_store_overrides_existing_entry(&mut overlay_init().1);
+    asserts::assert_delete_keys(&mut overlay_init().1);
+    asserts::assert_iter_prefix(&mut overlay_init().1);
+    asserts::assert_iter_range(&mut overlay_init().1);
+    asserts::assert_write_stores_and_deletes(&mut overlay_init().1);
+    asserts::assert_get_many(&mut overlay_init().1);
+}
+
+#[test]
+fn overlay_kv_reads_own_writes_without_touching_base() {
+    init();
+
+    let (base, mut overlay) = overlay_init();
+
+    base.borrow_mut()
+        .store(&[(&[1], &[10]), (&[2], &[20])])
+        .unwrap();
+
+    overlay.store(&[(&[1], &[11]), (&[3], &[30])]).unwrap();
+    overlay.delete(&[&[2]]).unwrap();
+
+    assert_key_value!(overlay, [1], [11]);
+    assert_no_key!(overlay, [2]);
+    assert_key_value!(overlay, [3], [30]);
+    assert_eq!(3, overlay.pending_count());
+
+    // the base store is untouched
+    assert_key_value!(base.borrow(), [1], [10]);
+    assert_key_value!(base.borrow(), [2], [20]);
+    assert_no_key!(base.borrow(), [3]);
+}
+
+#[test]
+fn overlay_kv_get_many_merges_own_writes_and_base() {
+    init();
+
+    let (base, mut overlay) = overlay_init();
+
+    base.borrow_mut()
+        .store(&[(&[1], &[10]), (&[2], &[20]), (&[4], &[40])])
+        .unwrap();
+
+    overlay.store(&[(&[1], &[11]), (&[3], &[30])]).unwrap();
+    overlay.delete(&[&[2]]).unwrap();
+
+    let values = overlay.get_many(&[&[4], &[3], &[2], &[1], &[5]]).unwrap();
+
+    assert_eq!(
+        vec![Some(vec![40]), Some(vec![30]), None, Some(vec![11]), None],
+        values
+    );
+}
+
+#[test]
+fn overlay_kv_iteration_merges_base_and_overlay() {
+    init();
+
+    let (base, mut overlay) = overlay_init();
+
+    base.borrow_mut()
+        .store(&[(b"a:1", b"10"), (b"a:2", b"20"), (b"b:1", b"30")])
+        .unwrap();
+
+    overlay.store(&[(b"a:0", b"40"), (b"a:2", b"50")]).unwrap();
+    overlay.delete(&[b"a:1"]).unwrap();
+
+    let expected = vec![
+        (b"a:0".to_vec(), b"40".to_vec()),
+        (b"a:2".to_vec(), b"50".to_vec()),
+    ];
+
+    assert_eq!(expected, overlay.iter_prefix(b"a:").unwrap());
+    assert_eq!(expected, overlay.iter_range(b"a", b"b").unwrap());
+}
+
+#[test]
+fn overlay_kv_commit() {
+    init();
+
+    let (base, mut overlay) = overlay_init();
+
+    base.borrow_mut()
+        .store(&[(&[1], &[10]), (&[2], &[20])])
+        .unwrap();
+
+    overlay.store(&[(&[1], &[11])]).unwrap();
+    overlay.delete(&[&[2]]).unwrap();
+    overlay.commit().unwrap();
+
+    assert_eq!(0, overlay.pending_count());
+
+    assert_key_value!(base.borrow(), [1], [11]);
+    assert_no_key!(base.borrow(), [2]);
+}
+
+#[test]
+fn overlay_kv_commit_is_a_single_write() {
+    init();
+
+    let base = Rc::new(RefCell::new(FaultyKV::new(MemKVStore::new())));
+    let mut overlay = OverlayKV::new(Rc::clone(&base));
+
+    base.borrow_mut().store(&[(&[2], &[20])]).unwrap();
+
+    overlay.store(&[(&[1], &[10])]).unwrap();
+    overlay.delete(&[&[2]]).unwrap();
+
+    // the failed commit keeps the changes
+    base.borrow_mut().inject(0, Fault::Fail);
+    assert!(overlay.commit().is_err());
+    assert_eq!(2, overlay.pending_count());
+
+    overlay.commit().unwrap();
+
+    assert_key_value!(base.borrow(), [1], [10]);
+    assert_no_key!(base.borrow(), [2]);
+
+    // the initial store, the failed commit and the successful commit
+    assert_eq!(3, base.borrow().writes_count());
+}
+
+#[test]
+fn overlay_kv_discard() {
+    init();
+
+    let (base, mut overlay) = overlay_init();
+
+    base.borrow_mut().store(&[(&[1], &[10])]).unwrap();
+
+    overlay.store(&[(&[1], &[11]), (&[2], &[20])]).unwrap();
+    overlay.discard();
+
+    assert_eq!(0, overlay.pending_count());
+
+    assert_key_value!(overlay, [1], [10]);
+    assert_no_key!(overlay, [2]);
+    assert_no_key!(base.borrow(), [2]);
+}
+
+#[test]
+fn overlay_kv_stacked() {
+    init();
+
+    let (base, overlay) = overlay_init();
+
+    let lower = Rc::new(RefCell::new(overlay));
+    let mut upper = OverlayKV::new(Rc::clone(&lower));
+
+    upper.store(&[(&[1], &[10])]).unwrap();
+
+    assert_key_value!(upper, [1], [10]);
+    assert_no_key!(lower.borrow(), [1]);
+
+    // committing the upper overlay moves its changes into the lower overlay
+    upper.commit().unwrap();
+
+    assert_key_value!(lower.borrow(), [1], [10]);
+    assert_no_key!(base.borrow(), [1]);
+
+    // discarding the lower overlay drops the changes of both
+    lower.borrow_mut().discard();
+
+    assert_no_key!(upper, [1]);
+    assert_no_key!(base.borrow(), [1]);
+}
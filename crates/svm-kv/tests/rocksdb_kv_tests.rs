@@ -0,0 +1,72 @@
+#![cfg(feature = "default-rocksdb")]
+
+use svm_kv::rocksdb::Rocksdb;
+
+mod asserts;
+
+fn init() {
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+/// Opens a new empty `Rocksdb` (each test has its own database directory).
+fn rocksdb_open(path: &str) -> Rocksdb {
+    let _ = std::fs::remove_dir_all(path);
+
+    Rocksdb::new(path)
+}
+
+#[test]
+fn rocksdb_a_key_does_not_exit_by_default() {
+    init();
+
+    asserts::assert_missing_key(&mut rocksdb_open("rocksdb-kv-missing-key"));
+}
+
+#[test]
+fn rocksdb_key_store_and_then_key_get() {
+    init();
+
+    asserts::assert_store_and_get(&mut rocksdb_open("rocksdb-kv-store-get"));
+}
+
+#[test]
+fn rocksdb_key_store_override_existing_entry() {
+    init();
+
+    asserts::assert_store_overrides_existing_entry(&mut rocksdb_open("rocksdb-kv-override"));
+}
+
+#[test]
+fn rocksdb_delete_keys() {
+    init();
+
+    asserts::assert_delete_keys(&mut rocksdb_open("rocksdb-kv-delete"));
+}
+
+#[test]
+fn rocksdb_iter_prefix() {
+    init();
+
+    asserts::assert_iter_prefix(&mut rocksdb_open("rocksdb-kv-iter-prefix"));
+}
+
+#[test]
+fn rocksdb_iter_range() {
+    init();
+
+    asserts::assert_iter_range(&mut rocksdb_open("rocksdb-kv-iter-range"));
+}
+
+#[test]
+fn rocksdb_write_stores_and_deletes() {
+    init();
+
+    asserts::assert_write_stores_and_deletes(&mut rocksdb_open("rocksdb-kv-write"));
+}
+
+#[test]
+fn rocksdb_get_many() {
+    init();
+
+    asserts::assert_get_many(&mut rocksdb_open("rocksdb-kv-get-many"));
+}
@@ -0,0 +1,120 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use svm_kv::{
+    faulty::{Fault, FaultyKV},
+    memory::MemKVStore,
+    traits::{KVStore, Overlay},
+    unit_of_work::UnitOfWork,
+};
+
+mod asserts;
+
+fn init() {
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+fn backend_init() -> Rc<RefCell<FaultyKV<MemKVStore>>> {
+    Rc::new(RefCell::new(FaultyKV::new(MemKVStore::new())))
+}
+
+#[test]
+fn unit_of_work_opening_a_store_twice() {
+    init();
+
+    let mut uow = UnitOfWork::new(backend_init());
+
+    let store1 = uow.open_store("apps");
+    let store2 = uow.open_store("apps");
+    let store3 = uow.open_store("pages");
+
+    assert!(Rc::ptr_eq(&store1, &store2));
+    assert!(!Rc::ptr_eq(&store1, &store3));
+}
+
+#[test]
+fn unit_of_work_commit_is_a_single_write() {
+    init();
+
+    let backend = backend_init();
+    let mut uow = UnitOfWork::new(Rc::clone(&backend));
+
+    let apps = uow.open_store("apps");
+    let pages = uow.open_store("pages");
+
+    backend.borrow_mut().store(&[(b"page:2", b"20")]).unwrap();
+
+    apps.borrow_mut().store(&[(b"app:1", b"10")]).unwrap();
+    pages.borrow_mut().store(&[(b"page:1", b"30")]).unwrap();
+    pages.borrow_mut().delete(&[b"page:2"]).unwrap();
+
+    assert_eq!(3, uow.pending_count());
+
+    // nothing is written until `commit`
+    assert_eq!(1, backend.borrow().writes_count());
+    assert_no_key!(backend.borrow(), b"app:1"[..]);
+
+    uow.commit().unwrap();
+
+    assert_eq!(0, uow.pending_count());
+    assert_eq!(2, backend.borrow().writes_count());
+
+    assert_key_value!(backend.borrow(), b"app:1"[..], b"10");
+    assert_key_value!(backend.borrow(), b"page:1"[..], b"30");
+    assert_no_key!(backend.borrow(), b"page:2"[..]);
+}
+
+#[test]
+fn unit_of_work_failed_commit_writes_nothing() {
+    init();
+
+    let backend = backend_init();
+    let mut uow = UnitOfWork::new(Rc::clone(&backend));
+
+    let apps = uow.open_store("apps");
+    let pages = uow.open_store("pages");
+
+    apps.borrow_mut().store(&[(b"app:1", b"10")]).unwrap();
+    pages.borrow_mut().store(&[(b"page:1", b"30")]).unwrap();
+
+    backend.borrow_mut().inject(0, Fault::Fail);
+    assert!(uow.commit().is_err());
+
+    assert_no_key!(backend.borrow(), b"app:1"[..]);
+    assert_no_key!(backend.borrow(), b"page:1"[..]);
+
+    // the changes are kept, so committing again is possible
+    assert_eq!(2, uow.pending_count());
+    assert_key_value!(apps.borrow(), b"app:1"[..], b"10");
+
+    uow.commit().unwrap();
+
+    assert_key_value!(backend.borrow(), b"app:1"[..], b"10");
+    assert_key_value!(backend.borrow(), b"page:1"[..], b"30");
+}
+
+#[test]
+fn unit_of_work_discard() {
+    init();
+
+    let backend = backend_init();
+    let mut uow = UnitOfWork::new(Rc::clone(&backend));
+
+    let apps = uow.open_store("apps");
+    let pages = uow.open_store("pages");
+
+    apps.borrow_mut().store(&[(b"app:1", b"10")]).unwrap();
+    pages.borrow_mut().store(&[(b"page:1", b"30")]).unwrap();
+
+    uow.discard();
+
+    assert_eq!(0, uow.pending_count());
+    assert_no_key!(apps.borrow(), b"app:1"[..]);
+    assert_no_key!(pages.borrow(), b"page:1"[..]);
+
+    // committing nothing still results in a single (empty) write
+    uow.commit().unwrap();
+
+    assert_eq!(1, backend.borrow().writes_count());
+    assert_no_key!(backend.borrow(), b"app:1"[..]);
+}
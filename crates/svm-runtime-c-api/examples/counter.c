@@ -112,6 +112,9 @@ svm_byte_array deploy_template_bytes() {
     2  +  // `#admins`    (we'll set it to `0`)
     2  +  // `#deps`      (we'll set it to `0`)
     2  +  // `page_count` (we'll set it to `0`)
+    1  +  // `page size` (log2, we'll set it to `12` i.e 4096 bytes)
+    2  +  // `#map pages` (we'll set it to `0`)
+    2  +  // `#abi funcs` (we'll set it to `0`)
     8  +  //  code length (Big-Endian)
     (uint64_t)file.length; // code
 
@@ -149,6 +152,20 @@ svm_byte_array deploy_template_bytes() {
   bytes[cursor + 1] = 0;
   cursor += 2;
 
+  // `page size` (log2) takes 1 byte
+  bytes[cursor] = 12;
+  cursor += 1;
+
+  // `#map pages` takes 2 bytes (no key-value map)
+  bytes[cursor + 0] = 0;
+  bytes[cursor + 1] = 0;
+  cursor += 2;
+
+  // `#abi funcs` takes 2 bytes (an empty ABI)
+  bytes[cursor + 0] = 0;
+  bytes[cursor + 1] = 0;
+  cursor += 2;
+
   // set code-length (Big-Endian)
   uint8_t* code_length = (uint8_t*)&file.length;
 
@@ -174,6 +191,7 @@ svm_byte_array spawn_app_bytes(svm_byte_array template_addr) {
   uint64_t length =
     4  +  // proto version
     template_addr.length +  // length(`template_addr)
+    4 +  // nonce
     1 +  // ctor #slices
     1;   // ctor func #args
 
@@ -192,6 +210,13 @@ svm_byte_array spawn_app_bytes(svm_byte_array template_addr) {
   memcpy(&bytes[cursor], template_addr.bytes, template_addr.length);
   cursor += template_addr.length;
 
+  // set `nonce=0`
+  bytes[cursor + 0] = 0;
+  bytes[cursor + 1] = 0;
+  bytes[cursor + 2] = 0;
+  bytes[cursor + 3] = 0;
+  cursor += 4;
+
   // `ctor buf #slices` take 1 byte 
   bytes[cursor] = 0; // no `ctor func buf`
   cursor += 1;
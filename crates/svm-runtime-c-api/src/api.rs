@@ -319,6 +319,27 @@ pub unsafe extern "C" fn svm_exec_app(
     }
 }
 
+/// Returns the global-state root (a single commitment over the current state of all apps)
+/// via the `root` parameter.
+#[must_use]
+#[no_mangle]
+pub unsafe extern "C" fn svm_global_state_root(
+    root: *mut svm_byte_array,
+    runtime: *const c_void,
+) -> svm_result_t {
+    debug!("`svm_global_state_root` start");
+
+    let runtime = helpers::cast_to_runtime(runtime);
+    let state = runtime.global_state_root();
+
+    // returning the root as `svm_byte_array`
+    // client should call later `svm_byte_array_destroy`
+    state_to_svm_byte_array!(root, state);
+
+    debug!("`svm_global_state_root` returns `SVM_SUCCESS`");
+    svm_result_t::SVM_SUCCESS
+}
+
 /// Returns a raw pointer to `the host` extracted from a raw pointer to `wasmer` context.
 #[must_use]
 #[no_mangle]
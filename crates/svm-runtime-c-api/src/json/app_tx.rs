@@ -0,0 +1,225 @@
+use byteorder::{BigEndian, WriteBytesExt};
+use serde::Deserialize;
+
+use super::{helpers, value::JsonValue, JsonError};
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JsonAppTx {
+    app: String,
+
+    func: String,
+
+    #[serde(default)]
+    args: Vec<JsonValue>,
+
+    #[serde(default)]
+    buf: Vec<String>,
+
+    #[serde(default)]
+    access_list: Option<Vec<u16>>,
+}
+
+/// Encodes a JSON `exec-app` call into the raw `AppTransaction` format.
+///
+/// Expected input:
+///
+/// ```json
+/// {
+///   "app": "0x0102...",
+///   "func": "transfer",
+///   "args": [{"type": "i32", "value": 10}, {"type": "i64", "value": 20}],
+///   "buf": ["0xAABBCC", "0x10203040"],
+///   "access_list": [0, 3]
+/// }
+/// ```
+///
+/// * `app`  - the app address (20 bytes, hex-encoded)
+/// * `func` - the function to execute
+/// * `args` - the function args (optional)
+/// * `buf`  - the `func-buf` slices, each hex-encoded (optional)
+/// * `access_list` - the indexes of the app pages the transaction is going to touch (optional)
+pub fn encode_app_tx(json: &str) -> Result<Vec<u8>, JsonError> {
+    let tx: JsonAppTx =
+        serde_json::from_str(json).or_else(|e| Err(JsonError::InvalidJson(format!("{}", e))))?;
+
+    let mut buf = Vec::new();
+
+    helpers::write_version(&mut buf);
+    helpers::write_address(&tx.app, "app", &mut buf)?;
+    write_func_name(&tx.func, &mut buf)?;
+    helpers::write_func_buf(&tx.buf, "buf", &mut buf)?;
+    helpers::write_func_args(&tx.args, "args", &mut buf)?;
+    write_access_list(&tx.access_list, &mut buf);
+
+    Ok(buf)
+}
+
+fn write_func_name(func: &str, buf: &mut Vec<u8>) -> Result<(), JsonError> {
+    let bytes = func.as_bytes();
+
+    if bytes.is_empty() || bytes.len() > 0xFF {
+        return Err(JsonError::InvalidField {
+            field: "func",
+            reason: "function name length should be between 1 and 255 bytes".to_string(),
+        });
+    }
+
+    buf.write_u8(bytes.len() as u8).unwrap();
+    buf.extend_from_slice(bytes);
+
+    Ok(())
+}
+
+fn write_access_list(pages: &Option<Vec<u16>>, buf: &mut Vec<u8>) {
+    // the access-list section is optional
+    if let Some(pages) = pages {
+        buf.write_u16::<BigEndian>(pages.len() as u16).unwrap();
+
+        for page_idx in pages {
+            buf.write_u16::<BigEndian>(*page_idx).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use svm_common::Address;
+
+    use svm_app::{
+        raw::parse_app_tx,
+        types::{AppTransaction, BufferSlice, WasmValue},
+    };
+
+    #[test]
+    fn encode_app_tx_round_trip() {
+        let app = Address::from(0x10_20_30_40);
+        let sender = Address::from(0x50_60_70_80);
+
+        let json = format!(
+            r#"{{
+              "app": "{}",
+              "func": "transfer",
+              "args": [{{"type": "i32", "value": 10}}, {{"type": "i64", "value": 20}}],
+              "buf": ["0xAABBCC", "10203040"],
+              "access_list": [3, 0]
+            }}"#,
+            helpers::hex_encode(app.as_slice())
+        );
+
+        let bytes = encode_app_tx(&json).unwrap();
+        let actual = parse_app_tx(&bytes, &sender).unwrap();
+
+        let expected = AppTransaction {
+            app,
+            sender,
+            func_name: "transfer".to_string(),
+            func_args: vec![WasmValue::I32(10), WasmValue::I64(20)],
+            func_buf: vec![
+                BufferSlice {
+                    data: vec![0xAA, 0xBB, 0xCC],
+                },
+                BufferSlice {
+                    data: vec![0x10, 0x20, 0x30, 0x40],
+                },
+            ],
+            access_list: vec![3, 0],
+        };
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn encode_app_tx_without_args_and_buf() {
+        let app = Address::from(0x10_20_30_40);
+        let sender = Address::from(0x50_60_70_80);
+
+        let json = format!(
+            r#"{{"app": "{}", "func": "inc"}}"#,
+            helpers::hex_encode(app.as_slice())
+        );
+
+        let bytes = encode_app_tx(&json).unwrap();
+        let actual = parse_app_tx(&bytes, &sender).unwrap();
+
+        let expected = AppTransaction {
+            app,
+            sender,
+            func_name: "inc".to_string(),
+            func_args: vec![],
+            func_buf: vec![],
+            access_list: vec![],
+        };
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn encode_app_tx_invalid_app_address() {
+        let json = r#"{"app": "0x1020", "func": "inc"}"#;
+
+        let expected = JsonError::InvalidField {
+            field: "app",
+            reason: "address should be 20 bytes (got 2 bytes)".to_string(),
+        };
+
+        assert_eq!(Err(expected), encode_app_tx(json));
+    }
+
+    #[test]
+    fn encode_app_tx_negative_i32_arg() {
+        let app = Address::from(0x10_20_30_40);
+        let sender = Address::from(0x50_60_70_80);
+
+        let json = format!(
+            r#"{{
+              "app": "{}",
+              "func": "transfer",
+              "args": [{{"type": "i32", "value": -10}}, {{"type": "i32", "value": 4294967295}}]
+            }}"#,
+            helpers::hex_encode(app.as_slice())
+        );
+
+        let bytes = encode_app_tx(&json).unwrap();
+        let actual = parse_app_tx(&bytes, &sender).unwrap();
+
+        let expected = vec![WasmValue::I32(-10i32 as u32), WasmValue::I32(0xFF_FF_FF_FF)];
+        assert_eq!(expected, actual.func_args);
+    }
+
+    #[test]
+    fn encode_app_tx_i32_arg_out_of_range() {
+        let app = Address::from(0x10_20_30_40);
+
+        for value in &["4294967296", "-2147483649"] {
+            let json = format!(
+                r#"{{"app": "{}", "func": "inc", "args": [{{"type": "i32", "value": {}}}]}}"#,
+                helpers::hex_encode(app.as_slice()),
+                value
+            );
+
+            let expected = JsonError::InvalidField {
+                field: "args",
+                reason: format!("`i32` value out of range: `{}`", value),
+            };
+
+            assert_eq!(Err(expected), encode_app_tx(&json));
+        }
+    }
+
+    #[test]
+    fn encode_app_tx_invalid_arg_type() {
+        let json = r#"{
+          "app": "0x0000000000000000000000000000000000000000",
+          "func": "inc",
+          "args": [{"type": "f32", "value": 10}]
+        }"#;
+
+        match encode_app_tx(json) {
+            Err(JsonError::InvalidJson(..)) => (),
+            _ => panic!("expected `JsonError::InvalidJson`"),
+        }
+    }
+}
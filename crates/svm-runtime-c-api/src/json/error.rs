@@ -0,0 +1,22 @@
+use std::fmt;
+
+/// JSON encoding / decoding errors
+#[allow(missing_docs)]
+#[derive(Debug, PartialEq, Clone)]
+pub enum JsonError {
+    InvalidJson(String),
+    InvalidField { field: &'static str, reason: String },
+    InvalidReceipt(String),
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JsonError::InvalidJson(reason) => write!(fmt, "Invalid JSON: {}", reason),
+            JsonError::InvalidField { field, reason } => {
+                write!(fmt, "Invalid field `{}`: {}", field, reason)
+            }
+            JsonError::InvalidReceipt(reason) => write!(fmt, "Invalid receipt: {}", reason),
+        }
+    }
+}
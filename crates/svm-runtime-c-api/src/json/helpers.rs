@@ -0,0 +1,124 @@
+use byteorder::{BigEndian, WriteBytesExt};
+
+use super::{value::JsonValue, JsonError};
+
+use svm_app::types::WasmType;
+use svm_common::Address;
+
+pub(crate) fn hex_encode(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+pub(crate) fn hex_decode(s: &str, field: &'static str) -> Result<Vec<u8>, JsonError> {
+    let s = s.trim_start_matches("0x");
+
+    hex::decode(s).or_else(|e| {
+        Err(JsonError::InvalidField {
+            field,
+            reason: format!("{}", e),
+        })
+    })
+}
+
+pub(crate) fn write_version(buf: &mut Vec<u8>) {
+    // we only have `version=0` for now.
+    buf.write_u32::<BigEndian>(0).unwrap();
+}
+
+pub(crate) fn write_address(
+    addr: &str,
+    field: &'static str,
+    buf: &mut Vec<u8>,
+) -> Result<(), JsonError> {
+    let bytes = hex_decode(addr, field)?;
+
+    if bytes.len() != Address::len() {
+        return Err(JsonError::InvalidField {
+            field,
+            reason: format!(
+                "address should be {} bytes (got {} bytes)",
+                Address::len(),
+                bytes.len()
+            ),
+        });
+    }
+
+    buf.extend_from_slice(&bytes);
+
+    Ok(())
+}
+
+pub(crate) fn write_func_buf(
+    slices: &[String],
+    field: &'static str,
+    buf: &mut Vec<u8>,
+) -> Result<(), JsonError> {
+    if slices.len() > 0xFF {
+        return Err(JsonError::InvalidField {
+            field,
+            reason: "at most 255 slices are allowed".to_string(),
+        });
+    }
+
+    buf.write_u8(slices.len() as u8).unwrap();
+
+    for slice in slices {
+        let bytes = hex_decode(slice, field)?;
+
+        if bytes.len() > 0xFFFF {
+            return Err(JsonError::InvalidField {
+                field,
+                reason: "a slice can't exceed 65535 bytes".to_string(),
+            });
+        }
+
+        buf.write_u16::<BigEndian>(bytes.len() as u16).unwrap();
+        buf.extend_from_slice(&bytes);
+    }
+
+    Ok(())
+}
+
+pub(crate) fn write_func_args(
+    args: &[JsonValue],
+    field: &'static str,
+    buf: &mut Vec<u8>,
+) -> Result<(), JsonError> {
+    if args.len() > 0xFF {
+        return Err(JsonError::InvalidField {
+            field,
+            reason: "at most 255 args are allowed".to_string(),
+        });
+    }
+
+    buf.write_u8(args.len() as u8).unwrap();
+
+    for arg in args {
+        match arg {
+            JsonValue::I32(v) => {
+                let v = i32_bits(*v, field)?;
+
+                buf.write_u8(WasmType::I32.into()).unwrap();
+                buf.write_u32::<BigEndian>(v).unwrap();
+            }
+            JsonValue::I64(v) => {
+                buf.write_u8(WasmType::I64.into()).unwrap();
+                buf.write_u64::<BigEndian>(*v).unwrap();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the 32 bits of an `i32` value given either signed or unsigned.
+fn i32_bits(v: i64, field: &'static str) -> Result<u32, JsonError> {
+    if v < i64::from(std::i32::MIN) || v > i64::from(std::u32::MAX) {
+        return Err(JsonError::InvalidField {
+            field,
+            reason: format!("`i32` value out of range: `{}`", v),
+        });
+    }
+
+    Ok(v as u32)
+}
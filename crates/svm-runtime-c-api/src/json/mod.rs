@@ -0,0 +1,25 @@
+//! JSON encoding of the client-facing binary formats.
+//!
+//! * `encode_app_tx` - turns a JSON description of an `exec-app` call into
+//!   the raw `AppTransaction` format (see `svm_app::raw::parse_app_tx`).
+//!
+//! * `encode_spawn_app` - turns a JSON description of a `spawn-app` into
+//!   the raw `Spawn-App` format (see `svm_app::raw::parse_app`).
+//!
+//! * `decode_receipt` - turns an encoded `exec-app` receipt (as returned by `svm_exec_app`)
+//!   into JSON.
+//!
+//! Addresses, states and `func-buf` slices are represented as hex strings (an optional `0x` prefix is allowed).
+//! Wasm values are represented as `{"type": "i32", "value": 10}` or `{"type": "i64", "value": 20}`.
+
+mod app_tx;
+mod error;
+mod helpers;
+mod receipt;
+mod spawn_app;
+mod value;
+
+pub use app_tx::encode_app_tx;
+pub use error::JsonError;
+pub use receipt::decode_receipt;
+pub use spawn_app::encode_spawn_app;
@@ -0,0 +1,199 @@
+use std::convert::TryFrom;
+use std::io::{Cursor, Read};
+
+use byteorder::{BigEndian, ReadBytesExt};
+use serde_json::{json, Value};
+
+use super::{helpers, value::JsonValue, JsonError};
+use crate::svm_value_type;
+
+use svm_common::State;
+
+/// Decodes an encoded `exec-app` receipt (see `receipt.rs` for the binary format) into JSON.
+///
+/// On success:
+///
+/// ```json
+/// {
+///   "success": true,
+///   "new_state": "0x...",
+///   "returns": [{"type": "i32", "value": 10}]
+/// }
+/// ```
+///
+/// On failure:
+///
+/// ```json
+/// {
+///   "success": false,
+///   "error": "..."
+/// }
+/// ```
+pub fn decode_receipt(bytes: &[u8]) -> Result<Value, JsonError> {
+    let mut cursor = Cursor::new(bytes);
+
+    let version = read_u32(&mut cursor, "version")?;
+    if version != 0 {
+        return Err(JsonError::InvalidReceipt(format!(
+            "unsupported receipt version: `{}`",
+            version
+        )));
+    }
+
+    let is_success = read_u8(&mut cursor, "is_success")?;
+
+    match is_success {
+        0 => decode_failure(&mut cursor),
+        1 => decode_success(&mut cursor),
+        _ => Err(JsonError::InvalidReceipt(format!(
+            "invalid `is_success` value: `{}`",
+            is_success
+        ))),
+    }
+}
+
+fn decode_failure(cursor: &mut Cursor<&[u8]>) -> Result<Value, JsonError> {
+    let len = read_u16(cursor, "error size")? as usize;
+    let bytes = read_bytes(cursor, len, "error data")?;
+
+    let error = String::from_utf8(bytes).or_else(|_e| {
+        Err(JsonError::InvalidReceipt(
+            "error data isn't UTF-8".to_string(),
+        ))
+    })?;
+
+    Ok(json!({
+        "success": false,
+        "error": error,
+    }))
+}
+
+fn decode_success(cursor: &mut Cursor<&[u8]>) -> Result<Value, JsonError> {
+    let new_state = read_bytes(cursor, State::len(), "new_state")?;
+
+    let nrets = read_u8(cursor, "#returns")?;
+    let mut returns = Vec::with_capacity(nrets as usize);
+
+    for _ in 0..nrets {
+        let raw_ty = read_u8(cursor, "return type")?;
+
+        let ret = match svm_value_type::try_from(raw_ty) {
+            Ok(svm_value_type::SVM_I32) => JsonValue::I32(read_u32(cursor, "return value")? as i64),
+            Ok(svm_value_type::SVM_I64) => JsonValue::I64(read_u64(cursor, "return value")?),
+            Err(e) => return Err(JsonError::InvalidReceipt(e)),
+        };
+
+        returns.push(ret);
+    }
+
+    Ok(json!({
+        "success": true,
+        "new_state": helpers::hex_encode(&new_state),
+        "returns": returns,
+    }))
+}
+
+fn not_enough_bytes(field: &str) -> JsonError {
+    JsonError::InvalidReceipt(format!("not enough bytes for `{}`", field))
+}
+
+fn read_u8(cursor: &mut Cursor<&[u8]>, field: &str) -> Result<u8, JsonError> {
+    cursor.read_u8().or_else(|_e| Err(not_enough_bytes(field)))
+}
+
+fn read_u16(cursor: &mut Cursor<&[u8]>, field: &str) -> Result<u16, JsonError> {
+    cursor
+        .read_u16::<BigEndian>()
+        .or_else(|_e| Err(not_enough_bytes(field)))
+}
+
+fn read_u32(cursor: &mut Cursor<&[u8]>, field: &str) -> Result<u32, JsonError> {
+    cursor
+        .read_u32::<BigEndian>()
+        .or_else(|_e| Err(not_enough_bytes(field)))
+}
+
+fn read_u64(cursor: &mut Cursor<&[u8]>, field: &str) -> Result<u64, JsonError> {
+    cursor
+        .read_u64::<BigEndian>()
+        .or_else(|_e| Err(not_enough_bytes(field)))
+}
+
+fn read_bytes(cursor: &mut Cursor<&[u8]>, len: usize, field: &str) -> Result<Vec<u8>, JsonError> {
+    let mut buf = vec![0; len];
+
+    cursor
+        .read_exact(&mut buf)
+        .or_else(|_e| Err(not_enough_bytes(field)))?;
+
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::receipt::encode_receipt;
+
+    use svm_common::Address;
+    use svm_runtime::{error::ExecAppError, value::Value as WasmValue, Receipt};
+
+    #[test]
+    fn decode_receipt_success_round_trip() {
+        let new_state = State::from(0x10_20_30_40);
+
+        let receipt = Receipt {
+            success: true,
+            error: None,
+            new_state: Some(new_state.clone()),
+            returns: Some(vec![WasmValue::I32(10), WasmValue::I64(20)]),
+        };
+
+        let bytes = encode_receipt(&receipt);
+        let actual = decode_receipt(&bytes).unwrap();
+
+        let expected = json!({
+            "success": true,
+            "new_state": helpers::hex_encode(new_state.as_slice()),
+            "returns": [
+                {"type": "i32", "value": 10},
+                {"type": "i64", "value": 20},
+            ]
+        });
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn decode_receipt_failure_round_trip() {
+        let error = ExecAppError::AppNotFound {
+            app_addr: Address::of("my-app"),
+        };
+
+        let expected = json!({
+            "success": false,
+            "error": format!("{:?}", error),
+        });
+
+        let receipt = Receipt {
+            success: false,
+            error: Some(error),
+            new_state: None,
+            returns: None,
+        };
+
+        let bytes = encode_receipt(&receipt);
+        let actual = decode_receipt(&bytes).unwrap();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn decode_receipt_not_enough_bytes() {
+        let bytes = vec![0, 0, 0, 0, 1, 0xAA];
+
+        let expected = JsonError::InvalidReceipt("not enough bytes for `new_state`".to_string());
+
+        assert_eq!(Err(expected), decode_receipt(&bytes));
+    }
+}
@@ -0,0 +1,105 @@
+use byteorder::{BigEndian, WriteBytesExt};
+use serde::Deserialize;
+
+use super::{helpers, value::JsonValue, JsonError};
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JsonSpawnApp {
+    template: String,
+
+    #[serde(default)]
+    nonce: u32,
+
+    #[serde(default)]
+    args: Vec<JsonValue>,
+
+    #[serde(default)]
+    buf: Vec<String>,
+}
+
+/// Encodes a JSON `spawn-app` description into the raw `Spawn-App` format.
+///
+/// Expected input:
+///
+/// ```json
+/// {
+///   "template": "0x0102...",
+///   "nonce": 0,
+///   "args": [{"type": "i32", "value": 10}],
+///   "buf": ["0xAABBCC"]
+/// }
+/// ```
+///
+/// * `template` - the template address (20 bytes, hex-encoded)
+/// * `nonce`    - the creator nonce (optional, defaults to `0`)
+/// * `args`     - the `ctor` args (optional)
+/// * `buf`      - the `ctor-buf` slices, each hex-encoded (optional)
+pub fn encode_spawn_app(json: &str) -> Result<Vec<u8>, JsonError> {
+    let spawn: JsonSpawnApp =
+        serde_json::from_str(json).or_else(|e| Err(JsonError::InvalidJson(format!("{}", e))))?;
+
+    let mut buf = Vec::new();
+
+    helpers::write_version(&mut buf);
+    helpers::write_address(&spawn.template, "template", &mut buf)?;
+    buf.write_u32::<BigEndian>(spawn.nonce).unwrap();
+    helpers::write_func_buf(&spawn.buf, "buf", &mut buf)?;
+    helpers::write_func_args(&spawn.args, "args", &mut buf)?;
+
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use svm_app::{
+        raw::parse_app,
+        types::{App, BufferSlice, SpawnApp, WasmValue},
+    };
+    use svm_common::Address;
+
+    #[test]
+    fn encode_spawn_app_round_trip() {
+        let template = Address::from(0x10_20_30_40);
+        let creator = Address::from(0x50_60_70_80);
+
+        let json = format!(
+            r#"{{
+              "template": "{}",
+              "nonce": 7,
+              "args": [{{"type": "i64", "value": 20}}],
+              "buf": ["0xAABBCC"]
+            }}"#,
+            helpers::hex_encode(template.as_slice())
+        );
+
+        let bytes = encode_spawn_app(&json).unwrap();
+        let actual = parse_app(&bytes, &creator).unwrap();
+
+        let expected = SpawnApp {
+            app: App {
+                template,
+                creator,
+                nonce: 7,
+            },
+            ctor_buf: vec![BufferSlice {
+                data: vec![0xAA, 0xBB, 0xCC],
+            }],
+            ctor_args: vec![WasmValue::I64(20)],
+        };
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn encode_spawn_app_missing_template() {
+        let json = r#"{"nonce": 1}"#;
+
+        match encode_spawn_app(json) {
+            Err(JsonError::InvalidJson(..)) => (),
+            _ => panic!("expected `JsonError::InvalidJson`"),
+        }
+    }
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// JSON representation of a wasm value: `{"type": "i32", "value": 10}`
+///
+/// An `i32` value is parsed as `i64` so it may be given either signed or unsigned
+/// (i.e within `i32::MIN..=u32::MAX`, see `helpers::write_func_args`).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", content = "value", rename_all = "lowercase")]
+pub(crate) enum JsonValue {
+    I32(i64),
+    I64(u64),
+}
@@ -10,6 +10,9 @@ pub mod helpers;
 /// Crate common tests specific helpers
 pub mod testing;
 
+/// JSON encoder / decoder for transactions and receipts
+pub mod json;
+
 mod api;
 mod import;
 mod receipt;
@@ -27,6 +30,7 @@ pub use import::{
     svm_byte_array, svm_import_func_sig_t, svm_import_func_t, svm_import_kind, svm_import_t,
     svm_import_value,
 };
+pub use receipt::encode_receipt;
 pub use result::svm_result_t;
 pub use value::{svm_value_type, svm_value_type_array};
 
@@ -35,7 +35,8 @@ const ERROR_LENGTH: usize = 2;
 const IS_SUCCESS: usize = 1;
 const HEADER: usize = PROTO_VER + IS_SUCCESS;
 
-pub(crate) fn encode_receipt(receipt: &Receipt) -> Vec<u8> {
+/// Encodes a `Receipt` into its raw format (see the format description above).
+pub fn encode_receipt(receipt: &Receipt) -> Vec<u8> {
     let size_hint = receipt_size_hint(receipt);
     let mut buf: Vec<u8> = Vec::with_capacity(size_hint);
 
@@ -9,7 +9,7 @@ use std::collections::HashMap;
 use std::ffi::c_void;
 
 use svm_app::types::WasmValue;
-use svm_common::Address;
+use svm_common::{Address, State};
 use svm_runtime::register::Register;
 
 #[derive(Debug)]
@@ -297,10 +297,17 @@ unsafe fn do_ffi_exec_app() {
 
     assert_eq!(expected, actual);
 
+    // 5) query the global-state root
+    let mut root = svm_byte_array::default();
+    let res = api::svm_global_state_root(&mut root, runtime);
+    assert_eq!(true, res.as_bool());
+    assert_eq!(State::len(), root.length as usize);
+
     let _ = api::svm_byte_array_destroy(template_addr);
     let _ = api::svm_byte_array_destroy(app_addr);
     let _ = api::svm_byte_array_destroy(init_state);
     let _ = api::svm_byte_array_destroy(receipt);
+    let _ = api::svm_byte_array_destroy(root);
     let _ = api::svm_imports_destroy(imports);
     let _ = api::svm_runtime_destroy(runtime);
 }
@@ -8,6 +8,9 @@ pub enum DeployTemplateError {
 
     /// Storing the template has failed (operating-system returned a failure).
     StoreFailed(StoreError),
+
+    /// A batch of app-transactions is pending (it must be flushed or discarded first).
+    BatchPending,
 }
 
 impl ToString for DeployTemplateError {
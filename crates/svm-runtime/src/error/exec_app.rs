@@ -1,8 +1,9 @@
 use std::error;
 use std::fmt;
 
-use svm_app::error::ParseError;
+use svm_app::error::{ParseError, StoreError};
 use svm_common::Address;
+use svm_kv::KVError;
 
 /// `exec-app` error
 #[allow(missing_docs)]
@@ -12,6 +13,10 @@ pub enum ExecAppError {
     AppNotFound {
         app_addr: Address,
     },
+    LoadFailed {
+        app_addr: Address,
+        reason: StoreError,
+    },
     CompilationFailed {
         app_addr: Address,
         template_addr: Address,
@@ -42,6 +47,11 @@ pub enum ExecAppError {
         func_args: String,
         reason: String,
     },
+    StoreFailed {
+        app_addr: Address,
+        reason: KVError,
+    },
+    BatchPending,
 }
 
 impl error::Error for ExecAppError {
@@ -49,11 +59,14 @@ impl error::Error for ExecAppError {
         match self {
             ExecAppError::ParseFailed(..) => "Parse failed",
             ExecAppError::AppNotFound { .. } => "App not found",
+            ExecAppError::LoadFailed { .. } => "Load failed",
             ExecAppError::CompilationFailed { .. } => "Compilation failed",
             ExecAppError::InstantiationFailed { .. } => "Instance Instantiation failed",
             ExecAppError::FuncNotFound { .. } => "Function not found",
             ExecAppError::ExecFailed { .. } => "Execution failed",
             ExecAppError::InvalidReturnValue { .. } => "Invalid return value",
+            ExecAppError::StoreFailed { .. } => "Store failed",
+            ExecAppError::BatchPending => "Batch pending",
         }
     }
 }
@@ -69,6 +82,7 @@ impl fmt::Display for ExecAppError {
         let msg = match self {
             ExecAppError::ParseFailed(e) => self.fmt_parse_failed(e),
             ExecAppError::AppNotFound { app_addr } => self.fmt_app_not_found(app_addr),
+            ExecAppError::LoadFailed { app_addr, reason } => self.fmt_load_failed(app_addr, reason),
             ExecAppError::CompilationFailed {
                 app_addr,
                 template_addr,
@@ -106,6 +120,10 @@ impl fmt::Display for ExecAppError {
                 func_rets,
                 reason,
             ),
+            ExecAppError::StoreFailed { app_addr, reason } => {
+                self.fmt_store_failed(app_addr, reason)
+            }
+            ExecAppError::BatchPending => self.fmt_batch_pending(),
         };
 
         write!(f, "{}", msg)
@@ -121,6 +139,13 @@ impl ExecAppError {
         format!("App `{:?}` not found", app_addr)
     }
 
+    fn fmt_load_failed(&self, app_addr: &Address, reason: &StoreError) -> String {
+        format!(
+            "Loading app `{:?}` (or its template) failed\nReason: {}",
+            app_addr, reason
+        )
+    }
+
     fn fmt_compilation_failed(
         &self,
         app_addr: &Address,
@@ -184,4 +209,15 @@ impl ExecAppError {
             "Function `{}` returned invalid values `{}` for input `{}` (app=`{:?}`, template=`{:?}`)\nReason: {}",
             func_name, func_rets, func_args, app_addr, template_addr, reason)
     }
+
+    fn fmt_store_failed(&self, app_addr: &Address, reason: &KVError) -> String {
+        format!(
+            "Storing the changes of app `{:?}` failed\nReason: {}",
+            app_addr, reason
+        )
+    }
+
+    fn fmt_batch_pending(&self) -> String {
+        "A batch of app-transactions is pending (it must be flushed or discarded first)".to_string()
+    }
 }
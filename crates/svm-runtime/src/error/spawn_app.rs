@@ -1,3 +1,5 @@
+use crate::error::ExecAppError;
+
 use svm_app::error::{ParseError, StoreError};
 use svm_common::Address;
 
@@ -12,6 +14,12 @@ pub enum SpawnAppError {
 
     /// Template not found. Returns the template address.
     TemplateNotFound(Address),
+
+    /// The app `ctor` has failed. Returns the `ctor` execution error.
+    CtorFailed(ExecAppError),
+
+    /// A batch of app-transactions is pending (it must be flushed or discarded first).
+    BatchPending,
 }
 
 impl ToString for SpawnAppError {
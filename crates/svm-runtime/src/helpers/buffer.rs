@@ -72,15 +72,14 @@ pub fn buffer_copy_to_storage(
     page_idx: u32,
     page_offset: u32,
     len: u32,
-) {
+) -> Result<(), String> {
     let buffer =
         wasmer_data_buffer(data, buf_id).expect(&format!("Buffer `{}` doesn't exist!", buf_id));
 
     let storage = helpers::wasmer_data_app_storage(data);
-    let layout = helpers::page_slice_layout(page_idx, page_offset, len);
 
     let data = buffer.read(buf_offset, len);
-    storage.write_page_slice(&layout, data);
+    helpers::storage_write_page_slice(storage, page_idx, page_offset, len, data)
 }
 
 pub fn buffer_copy_to_reg(
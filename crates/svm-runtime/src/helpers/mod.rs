@@ -28,15 +28,28 @@ pub fn storage_read_page_slice(
     storage.read_page_slice(&layout)
 }
 
+/// Writes a page-slice into `storage`.
+/// The pages dedicated to the storage map can't be written directly (they're updated only via the map vmcalls),
+/// so a write into them fails (and the failure traps the running wasm).
 pub fn storage_write_page_slice(
     storage: &mut AppStorage,
     page: u32,
     offset: u32,
     len: u32,
     data: &[u8],
-) {
+) -> Result<(), String> {
     let layout = page_slice_layout(page, offset, len);
+
+    if storage.is_map_page(layout.page_index()) {
+        return Err(format!(
+            "page #{} is dedicated to the storage map and can't be written directly",
+            page
+        ));
+    }
+
     storage.write_page_slice(&layout, data);
+
+    Ok(())
 }
 
 pub fn page_slice_layout(page_idx: u32, page_offset: u32, len: u32) -> PageSliceLayout {
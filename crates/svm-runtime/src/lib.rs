@@ -9,7 +9,10 @@ pub mod traits;
 /// Implements the most high-level API of `SVM`.
 mod runtime;
 
-pub use runtime::{create_rocksdb_runtime, DefaultRuntime, Receipt};
+pub use runtime::{
+    create_rocksdb_runtime, create_rocksdb_runtime_with_config, open_rocksdb_runtime,
+    DefaultRuntime, Receipt,
+};
 
 /// Implements `SvmCtx`. Used for running `SVM` instances.
 pub mod ctx;
@@ -1,6 +1,8 @@
+use std::cell::{Cell, RefCell};
 use std::convert::TryFrom;
 use std::ffi::c_void;
 use std::fmt;
+use std::rc::Rc;
 
 use log::{debug, error, info};
 
@@ -17,11 +19,18 @@ use crate::{
 };
 
 use svm_app::{
+    error::StoreError,
     traits::{Env, EnvTypes},
     types::{AppTemplate, AppTransaction, BufferSlice, HostCtx, SpawnApp, WasmValue},
 };
 use svm_common::{Address, State};
-use svm_storage::AppStorage;
+use svm_kv::{traits::Overlay, KVError};
+use svm_storage::{
+    page::PageIndex,
+    proof::{AppStateProof, ProofError},
+    traits::GlobalStateStorage,
+    AppStorage, PageCacheMetrics, SharedPageCache,
+};
 
 use wasmer_runtime::Value as WasmerValue;
 use wasmer_runtime_core::{
@@ -43,6 +52,26 @@ pub struct DefaultRuntime<ENV> {
     /// Determined by the app `Address` and `State` (app state) and app storage settings,
     /// builds a `AppStorage` instance.
     pub storage_builder: Box<StorageBuilderFn>,
+
+    /// The global-state (a commitment over all apps current `State`).
+    /// Updated upon each successful app-transaction (including the app `ctor`).
+    pub global_state: RefCell<Box<dyn GlobalStateStorage>>,
+
+    /// The overlays buffering the writes of the runtime stores (see `exec_batch`).
+    /// Flushed in order. When empty, the writes are persisted right away.
+    ///
+    /// `deploy_template`, `spawn_app` and `exec_app` flush the overlays when they succeed, and discard them otherwise
+    /// (so a failed operation doesn't persist any of its writes).
+    ///
+    /// A `UnitOfWork` overlay persists the writes of all the stores (including the env stores) atomically
+    /// (see `create_rocksdb_runtime`).
+    pub overlays: Vec<Rc<RefCell<dyn Overlay>>>,
+
+    /// The page cache shared by the app-storage of all app-transactions (optional).
+    pub page_cache: Option<SharedPageCache>,
+
+    /// Whether there are batch changes not flushed or discarded yet (see `exec_batch`).
+    batch_pending: Cell<bool>,
 }
 
 impl<TY, ENV> Runtime for DefaultRuntime<ENV>
@@ -58,8 +87,22 @@ where
     ) -> Result<Address, DeployTemplateError> {
         info!("runtime `deploy_template`");
 
-        let template = self.parse_template(author, bytes)?;
-        self.install_template(&template)
+        if self.batch_pending.get() {
+            return Err(DeployTemplateError::BatchPending);
+        }
+
+        let template_addr = match self.do_deploy_template(author, bytes) {
+            Ok(template_addr) => template_addr,
+            Err(e) => {
+                self.abort_op();
+                return Err(e);
+            }
+        };
+
+        self.commit_op()
+            .or_else(|e| Err(DeployTemplateError::StoreFailed(StoreError::from(e))))?;
+
+        Ok(template_addr)
     }
 
     fn spawn_app(
@@ -70,9 +113,20 @@ where
     ) -> Result<(Address, State), SpawnAppError> {
         info!("runtime `spawn_app`");
 
-        let spawn_app = self.parse_app(creator, bytes)?;
-        let app_addr = self.install_app(&spawn_app)?;
-        let state = self.call_ctor(creator, spawn_app, &app_addr, host_ctx)?;
+        if self.batch_pending.get() {
+            return Err(SpawnAppError::BatchPending);
+        }
+
+        let (app_addr, state) = match self.do_spawn_app(creator, host_ctx, bytes) {
+            Ok(spawned) => spawned,
+            Err(e) => {
+                self.abort_op();
+                return Err(e);
+            }
+        };
+
+        self.commit_op()
+            .or_else(|e| Err(SpawnAppError::StoreFailed(StoreError::from(e))))?;
 
         Ok((app_addr, state))
     }
@@ -82,9 +136,16 @@ where
         sender: &Address,
         bytes: &[u8],
     ) -> Result<AppTransaction, ExecAppError> {
-        self.env
+        let tx = self
+            .env
             .parse_app_tx(bytes, sender)
-            .or_else(|e| Err(ExecAppError::ParseFailed(e)))
+            .or_else(|e| Err(ExecAppError::ParseFailed(e)))?;
+
+        self.env
+            .validate_app_tx_abi(&tx)
+            .or_else(|e| Err(ExecAppError::ParseFailed(e)))?;
+
+        Ok(tx)
     }
 
     fn exec_app(
@@ -93,8 +154,39 @@ where
         state: State,
         host_ctx: HostCtx,
     ) -> Result<Receipt, ExecAppError> {
+        if self.batch_pending.get() {
+            return Err(ExecAppError::BatchPending);
+        }
+
+        let app_addr = tx.app.clone();
+
         let is_ctor = false;
-        self.inner_exec_app(tx, state, host_ctx, is_ctor)
+        let receipt = match self.inner_exec_app(tx, state, host_ctx, is_ctor) {
+            Ok(receipt) => receipt,
+            Err(e) => {
+                self.abort_op();
+                return Err(e);
+            }
+        };
+
+        if !receipt.success {
+            // a failed app-transaction leaves nothing behind
+            self.abort_op();
+            return Ok(receipt);
+        }
+
+        self.commit_op().or_else(|e| {
+            Err(ExecAppError::StoreFailed {
+                app_addr,
+                reason: e,
+            })
+        })?;
+
+        Ok(receipt)
+    }
+
+    fn global_state_root(&self) -> State {
+        self.global_state.borrow().get_root()
     }
 }
 
@@ -109,18 +201,120 @@ where
         env: ENV,
         imports: Vec<(String, String, Export)>,
         storage_builder: Box<StorageBuilderFn>,
+        global_state: Box<dyn GlobalStateStorage>,
     ) -> Self {
         Self {
             env,
             host,
             imports,
             storage_builder,
+            global_state: RefCell::new(global_state),
+            overlays: Vec::new(),
+            page_cache: None,
+            batch_pending: Cell::new(false),
+        }
+    }
+
+    /// Sets the page cache used by `storage_builder` (for exposing its metrics, see `page_cache_metrics`).
+    pub fn with_page_cache(mut self, page_cache: SharedPageCache) -> Self {
+        self.page_cache = Some(page_cache);
+
+        self
+    }
+
+    /// Returns the hit/miss metrics of the shared page cache (when there is one).
+    pub fn page_cache_metrics(&self) -> Option<PageCacheMetrics> {
+        self.page_cache.as_ref().map(|cache| cache.metrics())
+    }
+
+    /// Sets the overlays buffering the writes of the runtime stores (see `exec_batch`).
+    pub fn with_overlays(mut self, overlays: Vec<Rc<RefCell<dyn Overlay>>>) -> Self {
+        self.overlays = overlays;
+
+        self
+    }
+
+    /// Executes a batch of app-transactions speculatively (for example, the transactions of a candidate block).
+    /// Each app-transaction runs on top of its app current state under the global-state
+    /// (i.e including the changes of the preceding app-transactions of the batch).
+    ///
+    /// Nothing is persisted until calling `flush` (once the block is final).
+    /// Calling `discard` drops the batch changes instead. In the meantime, `global_state_root`
+    /// returns the root as of the batch execution.
+    ///
+    /// Important: while a batch is pending, `deploy_template`, `spawn_app` and `exec_app` fail
+    /// (returning a `BatchPending` error), since persisting their changes would persist the batch changes too.
+    pub fn exec_batch(
+        &self,
+        batch: Vec<(AppTransaction, HostCtx)>,
+    ) -> Vec<Result<Receipt, ExecAppError>> {
+        info!("runtime `exec_batch` ({} app-transactions)", batch.len());
+
+        self.batch_pending.set(true);
+
+        batch
+            .into_iter()
+            .map(|(tx, host_ctx)| {
+                let state = self.app_current_state(&tx.app)?;
+
+                let is_ctor = false;
+                self.inner_exec_app(tx, state, host_ctx, is_ctor)
+            })
+            .collect()
+    }
+
+    /// Persists the changes buffered by the overlays.
+    pub fn flush(&self) -> Result<(), KVError> {
+        for overlay in self.overlays.iter() {
+            overlay.borrow_mut().commit()?;
+        }
+
+        self.batch_pending.set(false);
+
+        Ok(())
+    }
+
+    /// Drops the changes buffered by the overlays, and reloads the global-state latest persisted root.
+    pub fn discard(&self) -> Result<(), KVError> {
+        for overlay in self.overlays.iter() {
+            overlay.borrow_mut().discard();
         }
+
+        self.batch_pending.set(false);
+
+        self.global_state.borrow_mut().reload()
+    }
+
+    /// Persists the changes of a succeeded `deploy_template` / `spawn_app` / `exec_app`.
+    /// When persisting fails, the changes are discarded (so they aren't persisted by a later operation).
+    fn commit_op(&self) -> Result<(), KVError> {
+        self.flush().or_else(|e| {
+            self.abort_op();
+
+            Err(e)
+        })
+    }
+
+    /// Discards the changes of a failed `deploy_template` / `spawn_app` / `exec_app`.
+    fn abort_op(&self) {
+        if let Err(e) = self.discard() {
+            error!(
+                "discarding the changes of a failed operation has failed: {}",
+                e
+            );
+        }
+    }
+
+    /// Returns a proof of app `addr` current state under the global-state root.
+    pub fn prove_app_state(&self, addr: &Address) -> Result<AppStateProof, ProofError> {
+        self.global_state.borrow().prove_app_state(addr)
     }
 
     /// Initialize a new `AppStorage` and returns it.
     /// This method is of `pub` visibility since it's also helpful for tests that want to
     /// observe that app storage data.
+    ///
+    /// The app key-value map (if any) is laid out over the map region of `settings` (see `AppSettings::map_region`).
     pub fn open_app_storage(
         &self,
         addr: &Address,
@@ -128,7 +322,35 @@ where
         settings: &AppSettings,
     ) -> AppStorage {
         let sb = &self.storage_builder;
-        sb(addr, state, settings)
+        let storage = sb(addr, state, settings);
+
+        match settings.map_region() {
+            Some(region) => storage.with_map_region(region),
+            None => storage,
+        }
+    }
+
+    fn do_deploy_template(
+        &mut self,
+        author: &Address,
+        bytes: &[u8],
+    ) -> Result<Address, DeployTemplateError> {
+        let template = self.parse_template(author, bytes)?;
+
+        self.install_template(&template)
+    }
+
+    fn do_spawn_app(
+        &mut self,
+        creator: &Address,
+        host_ctx: HostCtx,
+        bytes: &[u8],
+    ) -> Result<(Address, State), SpawnAppError> {
+        let spawn_app = self.parse_app(creator, bytes)?;
+        let app_addr = self.install_app(&spawn_app)?;
+        let state = self.call_ctor(creator, spawn_app, &app_addr, host_ctx)?;
+
+        Ok((app_addr, state))
     }
 
     fn call_ctor(
@@ -141,15 +363,14 @@ where
         let ctor = self.build_ctor_call(creator, spawn_app, &app_addr);
         let is_ctor = true;
 
-        match self.inner_exec_app(ctor, State::empty(), host_ctx, is_ctor) {
-            Ok(receipt) => {
-                let new_state = receipt.new_state.unwrap();
-                Ok(new_state)
-            }
-            Err(..) => {
-                todo!()
-                // return `SpawnAppError` of `ctor failed`
-            }
+        let receipt = self
+            .inner_exec_app(ctor, State::empty(), host_ctx, is_ctor)
+            .or_else(|e| Err(SpawnAppError::CtorFailed(e)))?;
+
+        match (receipt.new_state, receipt.error) {
+            (Some(new_state), _) => Ok(new_state),
+            (None, Some(e)) => Err(SpawnAppError::CtorFailed(e)),
+            (None, None) => unreachable!("a receipt has either a new state or an error"),
         }
     }
 
@@ -193,6 +414,7 @@ where
             func_name: "ctor".to_string(),
             func_args: spawn_app.ctor_args,
             func_buf: spawn_app.ctor_buf,
+            access_list: Vec::new(),
         }
     }
 
@@ -209,12 +431,22 @@ where
 
         let settings = AppSettings {
             page_count: template.page_count,
+            page_size: template.page_size,
+            map_pages: template.map_pages,
         };
 
-        let mut import_object = self.import_object_create(&tx.app, &state, host_ctx, &settings);
+        let mut import_object =
+            self.import_object_create(&tx.app, &state, &tx.access_list, host_ctx, &settings);
         self.import_object_extend(&mut import_object);
 
-        let result = self.do_exec_app(&tx, &template, &template_addr, &import_object, is_ctor);
+        let result = self
+            .do_exec_app(&tx, &template, &template_addr, &import_object, is_ctor)
+            .and_then(|(new_state, returns)| {
+                self.update_global_state(&tx.app, &new_state)?;
+
+                Ok((new_state, returns))
+            });
+
         let receipt = self.make_receipt(result);
 
         info!("receipt: {:?}", receipt);
@@ -222,6 +454,42 @@ where
         Ok(receipt)
     }
 
+    fn app_current_state(&self, app_addr: &Address) -> Result<State, ExecAppError> {
+        let state = self
+            .global_state
+            .borrow()
+            .get_app_state(app_addr)
+            .or_else(|e| {
+                Err(ExecAppError::StoreFailed {
+                    app_addr: app_addr.clone(),
+                    reason: e,
+                })
+            })?;
+
+        Ok(state.unwrap_or_else(State::empty))
+    }
+
+    fn update_global_state(
+        &self,
+        app_addr: &Address,
+        new_state: &State,
+    ) -> Result<(), ExecAppError> {
+        let mut global_state = self.global_state.borrow_mut();
+
+        global_state.set_app_state(app_addr, new_state);
+
+        let root = global_state.commit().or_else(|e| {
+            Err(ExecAppError::StoreFailed {
+                app_addr: app_addr.clone(),
+                reason: e,
+            })
+        })?;
+
+        debug!("global-state new root: {:?}", root);
+
+        Ok(())
+    }
+
     fn do_exec_app(
         &self,
         tx: &AppTransaction,
@@ -257,7 +525,12 @@ where
             }),
             Ok(returns) => {
                 let storage = self.instance_storage_mut(&mut instance);
-                let new_state = storage.commit();
+                let new_state = storage.commit().or_else(|e| {
+                    Err(ExecAppError::StoreFailed {
+                        app_addr: tx.app.clone(),
+                        reason: e,
+                    })
+                })?;
                 let returns = self.cast_wasmer_func_returns(tx, template_addr, returns)?;
 
                 Ok((new_state, returns))
@@ -402,6 +675,7 @@ where
         &self,
         addr: &Address,
         state: &State,
+        access_list: &[u16],
         host_ctx: HostCtx,
         settings: &AppSettings,
     ) -> ImportObject {
@@ -410,7 +684,13 @@ where
             addr, state, settings
         );
 
-        let storage = self.open_app_storage(addr, state, settings);
+        let mut storage = self.open_app_storage(addr, state, settings);
+
+        // the pages declared by the transaction access-list are loaded ahead of the module instantiation.
+        // the other pages are read on demand (during the execution).
+        let pages: Vec<PageIndex> = access_list.iter().map(|idx| PageIndex(*idx)).collect();
+        storage.prefetch(&pages);
+
         let host_ctx = svm_common::into_raw(host_ctx);
 
         let svm_ctx = SvmCtx::new(
@@ -452,11 +732,16 @@ where
     fn load_template(&self, tx: &AppTransaction) -> Result<(AppTemplate, Address), ExecAppError> {
         info!("runtime `load_template`");
 
-        self.env
-            .load_template_by_app(&tx.app)
-            .ok_or_else(|| ExecAppError::AppNotFound {
+        let template = self.env.load_template_by_app(&tx.app).or_else(|e| {
+            Err(ExecAppError::LoadFailed {
                 app_addr: tx.app.clone(),
+                reason: e,
             })
+        })?;
+
+        template.ok_or_else(|| ExecAppError::AppNotFound {
+            app_addr: tx.app.clone(),
+        })
     }
 
     fn compile_template(
@@ -4,4 +4,6 @@ mod rocksdb;
 
 pub use default::DefaultRuntime;
 pub use receipt::Receipt;
-pub use rocksdb::create_rocksdb_runtime;
+pub use rocksdb::{
+    create_rocksdb_runtime, create_rocksdb_runtime_with_config, open_rocksdb_runtime,
+};
@@ -4,64 +4,187 @@ use std::path::Path;
 use std::rc::Rc;
 
 use svm_common::{Address, State};
-use svm_kv::rocksdb::Rocksdb;
+use svm_kv::{overlay::OverlayKV, rocksdb::Rocksdb, traits::Overlay, unit_of_work::UnitOfWork};
 
 use svm_app::{
-    rocksdb::{RocksdbAppStore, RocksdbAppTemplateStore, RocksdbEnv},
+    kv::{KVAppStore, KVAppTemplateStore, KVEnv},
     traits::EnvSerializerTypes,
 };
 
 use svm_storage::{
-    rocksdb::{RocksdbAppPageCache, RocksdbAppPages},
-    AppStorage,
+    codec::PageCodec,
+    overlay::{OverlayAppPageCache, OverlayAppPages, OverlayGlobalState},
+    traits::GlobalStateStorage,
+    AppStorage, SharedPageCache,
 };
 
 use crate::runtime::DefaultRuntime;
-use crate::settings::AppSettings;
+use crate::settings::{AppSettings, RuntimeConfig};
+use crate::traits::StorageBuilderFn;
 
 use wasmer_runtime_core::export::Export;
 
-/// Creates a new `Runtime` backed by `rocksdb` for persistence.
+/// The column families of the `rocksdb` database shared by the runtime.
+const TEMPLATES_CF: &str = "templates";
+const APPS_CF: &str = "apps";
+const APP_STATES_CF: &str = "app-states";
+const PAGES_CF: &str = "pages";
+const GLOBAL_STATE_CF: &str = "global-state";
+
+/// Creates a new `Runtime` backed by `rocksdb` for persistence (using the default `RuntimeConfig`).
 pub fn create_rocksdb_runtime<P, Ser>(
     host: *mut c_void,
     path: &P,
     imports: Vec<(String, String, Export)>,
-) -> DefaultRuntime<RocksdbEnv<Ser>>
+) -> DefaultRuntime<KVEnv<OverlayKV<Rocksdb>, Ser>>
 where
     P: AsRef<Path>,
     Ser: EnvSerializerTypes,
 {
-    let env = app_env_build(path);
+    let config = RuntimeConfig::default();
 
-    DefaultRuntime::new(host, env, imports, Box::new(app_storage_build))
+    create_rocksdb_runtime_with_config(host, path, &config, imports)
 }
 
-fn app_env_build<P, Ser>(path: &P) -> RocksdbEnv<Ser>
+/// Creates a new `Runtime` backed by `rocksdb` for persistence.
+/// Panics when the database can't be opened (see `open_rocksdb_runtime`).
+pub fn create_rocksdb_runtime_with_config<P, Ser>(
+    host: *mut c_void,
+    path: &P,
+    config: &RuntimeConfig,
+    imports: Vec<(String, String, Export)>,
+) -> DefaultRuntime<KVEnv<OverlayKV<Rocksdb>, Ser>>
 where
     P: AsRef<Path>,
     Ser: EnvSerializerTypes,
 {
-    let app_store = RocksdbAppStore::<
+    open_rocksdb_runtime(host, path, config, imports).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Opens a new `Runtime` backed by `rocksdb` for persistence.
+/// Returns an error when the database (or its global-state) can't be opened.
+///
+/// A single `rocksdb` database is opened under `path` (tuned by `config`)
+/// and shared by the templates, apps, app-states, pages and global-state stores.
+/// Each store has its own column family.
+///
+/// The writes of all the stores are buffered by a single `UnitOfWork` (see `DefaultRuntime::exec_batch`).
+/// Hence, each flush persists the writes of all the column families as a single atomic `rocksdb` batch.
+pub fn open_rocksdb_runtime<P, Ser>(
+    host: *mut c_void,
+    path: &P,
+    config: &RuntimeConfig,
+    imports: Vec<(String, String, Export)>,
+) -> Result<DefaultRuntime<KVEnv<OverlayKV<Rocksdb>, Ser>>, String>
+where
+    P: AsRef<Path>,
+    Ser: EnvSerializerTypes,
+{
+    let db = Rocksdb::open(
+        path,
+        &config.rocksdb,
+        &[
+            TEMPLATES_CF,
+            APPS_CF,
+            APP_STATES_CF,
+            PAGES_CF,
+            GLOBAL_STATE_CF,
+        ],
+    )?;
+
+    let mut uow = UnitOfWork::new(db);
+
+    let global_state_kv = uow.open_store(GLOBAL_STATE_CF);
+    let templates_kv = uow.open_store(TEMPLATES_CF);
+    let apps_kv = uow.open_store(APPS_CF);
+
+    // the app pages open their stores through the unit-of-work (see `app_storage_builder`)
+    let uow = Rc::new(RefCell::new(uow));
+
+    let page_cache = config.page_cache_budget.map(SharedPageCache::new);
+
+    let env = app_env_build(&templates_kv, &apps_kv);
+    let storage_builder = app_storage_builder(&uow, &page_cache, config.page_codec);
+    let global_state = global_state_build(&global_state_kv)?;
+
+    let overlay: Rc<RefCell<dyn Overlay>> = uow;
+    let overlays = vec![overlay];
+
+    let runtime = DefaultRuntime::new(host, env, imports, storage_builder, global_state)
+        .with_overlays(overlays);
+
+    let runtime = match page_cache {
+        Some(page_cache) => runtime.with_page_cache(page_cache),
+        None => runtime,
+    };
+
+    Ok(runtime)
+}
+
+fn app_env_build<Ser>(
+    templates_kv: &Rc<RefCell<OverlayKV<Rocksdb>>>,
+    apps_kv: &Rc<RefCell<OverlayKV<Rocksdb>>>,
+) -> KVEnv<OverlayKV<Rocksdb>, Ser>
+where
+    Ser: EnvSerializerTypes,
+{
+    let app_store = KVAppStore::<
+        OverlayKV<Rocksdb>,
         <Ser as EnvSerializerTypes>::AppSerializer,
         <Ser as EnvSerializerTypes>::AppDeserializer,
-    >::new(path);
+    >::new(apps_kv);
 
-    let template_store = RocksdbAppTemplateStore::<
+    let template_store = KVAppTemplateStore::<
+        OverlayKV<Rocksdb>,
         <Ser as EnvSerializerTypes>::TemplateSerializer,
         <Ser as EnvSerializerTypes>::TemplateDeserializer,
-    >::new(path);
+    >::new(templates_kv);
 
-    RocksdbEnv::new(app_store, template_store)
+    KVEnv::new(app_store, template_store)
 }
 
-fn app_storage_build(addr: &Address, state: &State, settings: &AppSettings) -> AppStorage {
-    // TODO: inject path
-    let path = Path::new("apps");
+/// The app pages and states are kept under their own column families,
+/// both buffered by `uow` (so each commit of the app pages is persisted as a single batch).
+fn app_storage_builder(
+    uow: &Rc<RefCell<UnitOfWork<Rocksdb>>>,
+    page_cache: &Option<SharedPageCache>,
+    page_codec: PageCodec,
+) -> Box<StorageBuilderFn> {
+    let uow = Rc::clone(uow);
+    let page_cache = page_cache.clone();
 
-    let kv = Rc::new(RefCell::new(Rocksdb::new(path)));
+    Box::new(
+        move |addr: &Address, state: &State, settings: &AppSettings| {
+            // the page size has been validated upon parsing the app template
+            let pages = OverlayAppPages::new_with_unit_of_work(
+                addr.clone(),
+                &mut uow.borrow_mut(),
+                PAGES_CF,
+                APP_STATES_CF,
+                state.clone(),
+                settings.page_count,
+                settings.page_size,
+            )
+            .expect("app template has an invalid page size")
+            .with_codec(page_codec);
+
+            let pages = match page_cache {
+                Some(ref page_cache) => pages.with_shared_cache(page_cache.clone()),
+                None => pages,
+            };
+
+            let cache = OverlayAppPageCache::new(pages, settings.page_count);
+
+            AppStorage::new(Box::new(cache))
+        },
+    )
+}
 
-    let pages = RocksdbAppPages::new(addr.clone(), kv, state.clone(), settings.page_count);
-    let cache = RocksdbAppPageCache::new(pages, settings.page_count);
+fn global_state_build(
+    kv: &Rc<RefCell<OverlayKV<Rocksdb>>>,
+) -> Result<Box<dyn GlobalStateStorage>, String> {
+    let global_state = OverlayGlobalState::open_latest(Rc::clone(kv))
+        .or_else(|e| Err(format!("failed opening the global-state: {}", e)))?;
 
-    AppStorage::new(Box::new(cache))
+    Ok(Box::new(global_state))
 }
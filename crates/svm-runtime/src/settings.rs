@@ -1,6 +1,49 @@
+use svm_storage::{map::MapRegion, page::PageIndex};
+
 /// Holds settings for using the Runtime.
 #[derive(Debug, Clone)]
 pub struct AppSettings {
     /// number of pages required by the app storage
     pub page_count: u16,
+
+    /// the size (in bytes) of each page of the app storage (as declared by the app-template)
+    pub page_size: u32,
+
+    /// number of pages (the last ones) dedicated to the app key-value map (as declared by the app-template)
+    pub map_pages: u16,
+}
+
+impl AppSettings {
+    /// The app storage pages dedicated to the app key-value map (`None` when the app has no map).
+    ///
+    /// Settings declaring more map pages than app pages have no map as well
+    /// (such app-templates are rejected by `svm_app::raw::parse_template` to begin with).
+    pub fn map_region(&self) -> Option<MapRegion> {
+        if self.map_pages == 0 {
+            return None;
+        }
+
+        let first_page = self.page_count.checked_sub(self.map_pages)?;
+
+        let region = MapRegion {
+            first_page: PageIndex(first_page),
+            page_count: self.map_pages,
+        };
+
+        Some(region)
+    }
+}
+
+/// Tuning options of a `rocksdb`-backed runtime (see `create_rocksdb_runtime_with_config`).
+#[derive(Debug, Clone, Default)]
+pub struct RuntimeConfig {
+    /// The `rocksdb` options (cache size, compression) of the database shared by the runtime
+    pub rocksdb: svm_kv::rocksdb::RocksdbConfig,
+
+    /// The memory budget (in bytes) of the process-wide page cache shared by all app-transactions.
+    /// `None` disables the cache (see `svm_storage::SharedPageCache`).
+    pub page_cache_budget: Option<usize>,
+
+    /// The encoding of the committed pages (see `svm_storage::codec::PageCodec`)
+    pub page_codec: svm_storage::codec::PageCodec,
 }
@@ -5,15 +5,24 @@ use std::rc::Rc;
 
 use crate::{
     buffer::BufferRef, ctx::SvmCtx, helpers, helpers::DataWrapper, register::Register,
-    settings::AppSettings, traits::StorageBuilderFn, DefaultRuntime,
+    settings::AppSettings, DefaultRuntime,
 };
 
 use svm_common::{Address, State};
-use svm_kv::memory::MemKVStore;
-use svm_storage::AppStorage;
+use svm_kv::{
+    faulty::FaultyKV,
+    memory::MemKVStore,
+    overlay::OverlayKV,
+    traits::{KVStore, Overlay},
+    unit_of_work::UnitOfWork,
+};
+use svm_storage::{
+    overlay::{OverlayAppPageCache, OverlayAppPages, OverlayGlobalState},
+    AppStorage,
+};
 
 use svm_app::{
-    memory::{JsonMemAppStore, JsonMemAppTemplateStore, JsonMemoryEnv},
+    kv::{JsonKVAppStore, JsonKVAppTemplateStore, JsonKVEnv},
     testing::{AppBuilder, AppTemplateBuilder, AppTxBuilder, HostCtxBuilder},
     types::WasmValue,
 };
@@ -77,11 +86,34 @@ pub fn app_memory_state_creator(
     host: DataWrapper<*mut c_void>,
     host_ctx: DataWrapper<*const c_void>,
     page_count: u16,
+) -> (*mut c_void, fn(*mut c_void)) {
+    app_memory_map_state_creator(app_addr, state, host, host_ctx, page_count, 0)
+}
+
+/// Same as `app_memory_state_creator`, where the last `map_pages` pages are dedicated to the app key-value map.
+pub fn app_memory_map_state_creator(
+    app_addr: &Address,
+    state: &State,
+    host: DataWrapper<*mut c_void>,
+    host_ctx: DataWrapper<*const c_void>,
+    page_count: u16,
+    map_pages: u16,
 ) -> (*mut c_void, fn(*mut c_void)) {
     let kv = memory_kv_store_init();
 
     let storage = svm_storage::testing::app_storage_open(app_addr, state, &kv, page_count);
 
+    let settings = AppSettings {
+        page_count,
+        page_size: storage.page_size(),
+        map_pages,
+    };
+
+    let storage = match settings.map_region() {
+        Some(region) => storage.with_map_region(region),
+        None => storage,
+    };
+
     let ctx = SvmCtx::new(host, host_ctx, storage);
     let ctx: *mut SvmCtx = Box::into_raw(Box::new(ctx));
 
@@ -97,35 +129,85 @@ pub fn memory_kv_store_init() -> Rc<RefCell<MemKVStore>> {
 }
 
 /// Creates an in-memory `Runtime` backed by key-value, raw pointer to host and host vmcalls (`imports`)
+///
+/// The env stores, app storage and global-state writes are all buffered by a single `UnitOfWork` over `kv`
+/// (see `DefaultRuntime::exec_batch`). So a failed operation leaves nothing behind (including its `App` / `AppTemplate`).
 pub fn create_memory_runtime(
     host: *mut c_void,
     kv: &Rc<RefCell<MemKVStore>>,
     imports: Vec<(String, String, Export)>,
-) -> DefaultRuntime<JsonMemoryEnv> {
-    let storage_builder = runtime_memory_storage_builder(kv);
+) -> DefaultRuntime<JsonKVEnv<OverlayKV<MemKVStore>>> {
+    create_unit_of_work_runtime(host, kv, imports)
+}
 
-    let env = runtime_memory_env_builder();
+/// A `MemKVStore` wrapped by a fault-injecting `FaultyKV` (see `create_faulty_runtime`).
+pub type FaultyMemKVStore = FaultyKV<MemKVStore>;
 
-    DefaultRuntime::new(host, env, imports, Box::new(storage_builder))
+/// Initializes a new `FaultyKV` on top of a new `MemKVStore`
+pub fn faulty_kv_store_init() -> Rc<RefCell<FaultyMemKVStore>> {
+    Rc::new(RefCell::new(FaultyKV::new(MemKVStore::new())))
 }
 
-/// Creates an app storage builder function backed by key-value store `kv`.
-pub fn runtime_memory_storage_builder(kv: &Rc<RefCell<MemKVStore>>) -> Box<StorageBuilderFn> {
-    let kv = Rc::clone(kv);
+/// Creates an in-memory `Runtime` whose env stores, app storage and global-state all live under
+/// the fault-injecting key-value store `kv` (for testing crash-consistency).
+///
+/// The writes of all the stores are buffered by a single `UnitOfWork` over `kv`.
+/// Hence, each flush results in a single `kv` write (see `FaultyKV::inject`).
+pub fn create_faulty_runtime(
+    host: *mut c_void,
+    kv: &Rc<RefCell<FaultyMemKVStore>>,
+    imports: Vec<(String, String, Export)>,
+) -> DefaultRuntime<JsonKVEnv<OverlayKV<FaultyMemKVStore>>> {
+    create_unit_of_work_runtime(host, kv, imports)
+}
 
-    let func = move |addr: &Address, state: &State, settings: &AppSettings| {
-        svm_storage::testing::app_storage_open(addr, state, &kv, settings.page_count)
+fn create_unit_of_work_runtime<KV: KVStore + 'static>(
+    host: *mut c_void,
+    kv: &Rc<RefCell<KV>>,
+    imports: Vec<(String, String, Export)>,
+) -> DefaultRuntime<JsonKVEnv<OverlayKV<KV>>> {
+    let mut uow = UnitOfWork::new(Rc::clone(kv));
+
+    let global_state_kv = uow.open_store("global-state");
+    let templates_kv = uow.open_store("templates");
+    let apps_kv = uow.open_store("apps");
+
+    let uow = Rc::new(RefCell::new(uow));
+    let pages_uow = Rc::clone(&uow);
+
+    let storage_builder = move |addr: &Address, state: &State, settings: &AppSettings| {
+        let pages = OverlayAppPages::new_with_unit_of_work(
+            addr.clone(),
+            &mut pages_uow.borrow_mut(),
+            "pages",
+            "app-states",
+            state.clone(),
+            settings.page_count,
+            settings.page_size,
+        )
+        .unwrap();
+        let cache = OverlayAppPageCache::new(pages, settings.page_count);
+
+        AppStorage::new(Box::new(cache))
     };
 
-    Box::new(func)
-}
+    let app_store = JsonKVAppStore::new(&apps_kv);
+    let template_store = JsonKVAppTemplateStore::new(&templates_kv);
+    let env = JsonKVEnv::new(app_store, template_store);
+
+    let global_state = OverlayGlobalState::open_latest(Rc::clone(&global_state_kv)).unwrap();
 
-/// Creates a new in-memory runtime environment.
-pub fn runtime_memory_env_builder() -> JsonMemoryEnv {
-    let template_store = JsonMemAppTemplateStore::new();
-    let app_store = JsonMemAppStore::new();
+    let overlay: Rc<RefCell<dyn Overlay>> = uow;
+    let overlays = vec![overlay];
 
-    JsonMemoryEnv::new(app_store, template_store)
+    DefaultRuntime::new(
+        host,
+        env,
+        imports,
+        Box::new(storage_builder),
+        Box::new(global_state),
+    )
+    .with_overlays(overlays)
 }
 
 /// Synthesizes a raw deploy-template transaction.
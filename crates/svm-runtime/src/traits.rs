@@ -48,6 +48,9 @@ pub trait Runtime {
         state: State,
         host_ctx: HostCtx,
     ) -> Result<Receipt, ExecAppError>;
+
+    /// Returns the global-state root. A single commitment over the current `State` of all apps.
+    fn global_state_root(&self) -> State;
 }
 
 /// Represents a function that builds a `AppStorage` given its address, state and settings.
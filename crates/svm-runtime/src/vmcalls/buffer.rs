@@ -21,8 +21,8 @@ pub fn buffer_copy_to_storage(
     page_idx: u32,
     page_offset: u32,
     count: u32,
-) {
-    helpers::buffer_copy_to_storage(ctx.data, buf_id, buf_offset, page_idx, page_offset, count);
+) -> Result<(), String> {
+    helpers::buffer_copy_to_storage(ctx.data, buf_id, buf_offset, page_idx, page_offset, count)
 }
 
 pub fn buffer_copy_to_reg(
@@ -12,9 +12,10 @@ pub use host_ctx::{
 };
 pub use register::{reg_pop, reg_push};
 pub use storage::{
-    mem_to_reg_copy, reg_to_mem_copy, storage_read_i32_be, storage_read_i32_le,
-    storage_read_i64_be, storage_read_i64_le, storage_read_to_mem, storage_read_to_reg,
-    storage_write_from_mem, storage_write_from_reg,
+    mem_to_reg_copy, reg_to_mem_copy, storage_map_get, storage_map_remove, storage_map_set,
+    storage_read_i32_be, storage_read_i32_le, storage_read_i64_be, storage_read_i64_le,
+    storage_read_to_mem, storage_read_to_reg, storage_write_from_mem, storage_write_from_reg,
+    MAP_SET_EMPTY_VALUE, MAP_SET_MAP_FULL, MAP_SET_NO_MAP, MAP_SET_OK, MAP_SET_VALUE_TOO_LARGE,
 };
 
 pub use wasmer_runtime_core::{
@@ -37,6 +38,11 @@ pub fn insert_vmcalls(ns: &mut Namespace) {
     ns.insert("storage_read_i64_le", func!(storage_read_i64_le));
     ns.insert("storage_read_i64_be", func!(storage_read_i64_be));
 
+    // `storage map` vmcalls
+    ns.insert("storage_map_get", func!(storage_map_get));
+    ns.insert("storage_map_set", func!(storage_map_set));
+    ns.insert("storage_map_remove", func!(storage_map_remove));
+
     // `register` vmcalls
     ns.insert("reg_push", func!(reg_push));
     ns.insert("reg_pop", func!(reg_pop));
@@ -2,8 +2,25 @@ use crate::helpers;
 
 use wasmer_runtime::Ctx as WasmerCtx;
 
+use svm_storage::map::MapError;
+
 use byteorder::{BigEndian, ByteOrder, LittleEndian};
 
+/// `storage_map_set` succeeded
+pub const MAP_SET_OK: u32 = 0;
+
+/// `storage_map_set` failed since the value is empty
+pub const MAP_SET_EMPTY_VALUE: u32 = 1;
+
+/// `storage_map_set` failed since the value exceeds `svm_storage::map::MAP_MAX_VALUE_SIZE` bytes
+pub const MAP_SET_VALUE_TOO_LARGE: u32 = 2;
+
+/// `storage_map_set` failed since the storage map has no vacant slots
+pub const MAP_SET_MAP_FULL: u32 = 3;
+
+/// `storage_map_set` failed since the app has no storage map
+pub const MAP_SET_NO_MAP: u32 = 4;
+
 /// Copies the contents of `wasmer` memory cells under addresses:
 /// `mem_offset, mem_offset + 1, .. , mem_offset + count (exclusive)`
 /// into `SVM` register
@@ -115,6 +132,7 @@ pub fn storage_read_to_mem(
 }
 
 /// Writes into `SVM` storage, a page-slice copied from `wasmer` memory
+/// (traps when the page is dedicated to the storage map).
 ///
 /// * `ctx`         - `wasmer` context (holds a `data` field. we use `SvmCtx`)
 /// * `mem_idx`     - The memory index we start to copy from
@@ -129,17 +147,18 @@ pub fn storage_write_from_mem(
     page_idx: u32,
     page_offset: u32,
     count: u32,
-) {
+) -> Result<(), String> {
     let (mem_idx, start, end) = rustify_mem_params(mem_idx, mem_offset, count);
     let cells = &ctx.memory(mem_idx).view()[start..end];
 
     let data = cells.iter().map(|cell| cell.get()).collect::<Vec<u8>>();
     let storage = helpers::wasmer_data_app_storage(ctx.data);
 
-    helpers::storage_write_page_slice(storage, page_idx, page_offset, count, &data);
+    helpers::storage_write_page_slice(storage, page_idx, page_offset, count, &data)
 }
 
 /// Writes into `SVM` storage, a page-slice copied from `SVM wasmer` register
+/// (traps when the page is dedicated to the storage map).
 ///
 /// * `ctx`         - `wasmer` context (holds a `data` field. we use `SvmCtx`)
 /// * `reg_bits`    - The type of the register (determined by its #bits) we want to copy data from
@@ -154,12 +173,12 @@ pub fn storage_write_from_reg(
     page_idx: u32,
     page_offset: u32,
     count: u32,
-) {
+) -> Result<(), String> {
     let reg = helpers::wasmer_data_reg(ctx.data, reg_bits, reg_idx);
     let storage = helpers::wasmer_data_app_storage(ctx.data);
     let data = reg.getn(count as usize);
 
-    helpers::storage_write_page_slice(storage, page_idx, page_offset, count, data);
+    helpers::storage_write_page_slice(storage, page_idx, page_offset, count, data)
 }
 
 pub fn storage_read_i32_be(
@@ -210,6 +229,104 @@ fn storage_read_int<T: ByteOrder>(
     T::read_uint(&buf[..], count as usize)
 }
 
+/// Loads the value of a key of the `SVM` instance's storage map into `wasmer` memory.
+/// Returns the value length (`0` when the key has no value, in which case the memory is left untouched).
+/// An app without a storage map has no values.
+///
+/// * `ctx`          - `wasmer` context (holds a `data` field. we use `SvmCtx`)
+/// * `mem_idx`      - The memory index of both the key and the value
+/// * `key_offset`   - Memory address of the key
+/// * `key_len`      - Key length (in bytes)
+/// * `value_offset` - The destination memory address of the value (should have `MAP_MAX_VALUE_SIZE` bytes available)
+pub fn storage_map_get(
+    ctx: &mut WasmerCtx,
+    mem_idx: u32,
+    key_offset: u32,
+    key_len: u32,
+    value_offset: u32,
+) -> u32 {
+    let key = mem_read(ctx, mem_idx, key_offset, key_len);
+
+    let storage = helpers::wasmer_data_app_storage(ctx.data);
+
+    let value = match storage.map() {
+        Ok(mut map) => map.get(&key),
+        Err(..) => None,
+    };
+
+    match value {
+        Some(value) => {
+            let count = value.len() as u32;
+
+            let (mem_idx, start, end) = rustify_mem_params(mem_idx, value_offset, count);
+            let cells = &ctx.memory(mem_idx).view()[start..end];
+
+            for (cell, byte) in cells.iter().zip(value.iter()) {
+                cell.set(*byte);
+            }
+
+            count
+        }
+        None => 0,
+    }
+}
+
+/// Sets the value of a key of the `SVM` instance's storage map, both copied from `wasmer` memory.
+/// Returns `MAP_SET_OK` on success, and the `MAP_SET_*` code of the failure otherwise (the map is left untouched).
+///
+/// * `ctx`          - `wasmer` context (holds a `data` field. we use `SvmCtx`)
+/// * `mem_idx`      - The memory index of both the key and the value
+/// * `key_offset`   - Memory address of the key
+/// * `key_len`      - Key length (in bytes)
+/// * `value_offset` - Memory address of the value
+/// * `value_len`    - Value length (in bytes). Must be between `1` and `MAP_MAX_VALUE_SIZE`
+pub fn storage_map_set(
+    ctx: &mut WasmerCtx,
+    mem_idx: u32,
+    key_offset: u32,
+    key_len: u32,
+    value_offset: u32,
+    value_len: u32,
+) -> u32 {
+    let key = mem_read(ctx, mem_idx, key_offset, key_len);
+    let value = mem_read(ctx, mem_idx, value_offset, value_len);
+
+    let storage = helpers::wasmer_data_app_storage(ctx.data);
+
+    match storage.map().and_then(|mut map| map.set(&key, &value)) {
+        Ok(()) => MAP_SET_OK,
+        Err(MapError::EmptyValue) => MAP_SET_EMPTY_VALUE,
+        Err(MapError::ValueTooLarge(..)) => MAP_SET_VALUE_TOO_LARGE,
+        Err(MapError::MapFull) => MAP_SET_MAP_FULL,
+        Err(MapError::NoMapRegion) => MAP_SET_NO_MAP,
+    }
+}
+
+/// Removes the value of a key of the `SVM` instance's storage map.
+/// Returns `1` when the key had a value, and `0` otherwise (an app without a storage map has no values).
+///
+/// * `ctx`        - `wasmer` context (holds a `data` field. we use `SvmCtx`)
+/// * `mem_idx`    - The memory index of the key
+/// * `key_offset` - Memory address of the key
+/// * `key_len`    - Key length (in bytes)
+pub fn storage_map_remove(ctx: &mut WasmerCtx, mem_idx: u32, key_offset: u32, key_len: u32) -> u32 {
+    let key = mem_read(ctx, mem_idx, key_offset, key_len);
+
+    let storage = helpers::wasmer_data_app_storage(ctx.data);
+
+    match storage.map() {
+        Ok(mut map) => map.remove(&key) as u32,
+        Err(..) => 0,
+    }
+}
+
+fn mem_read(ctx: &WasmerCtx, mem_idx: u32, mem_offset: u32, count: u32) -> Vec<u8> {
+    let (mem_idx, start, end) = rustify_mem_params(mem_idx, mem_offset, count);
+    let cells = &ctx.memory(mem_idx).view()[start..end];
+
+    cells.iter().map(|cell| cell.get()).collect()
+}
+
 fn rustify_mem_params(mem_idx: u32, mem_offset: u32, count: u32) -> (u32, usize, usize) {
     let start = mem_offset as usize;
     let end = start + count as usize;
@@ -0,0 +1,341 @@
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+
+use svm_app::{
+    traits::Env,
+    types::{HostCtx, WasmValue},
+};
+use svm_common::Address;
+use svm_kv::{
+    faulty::{Fault, FaultyKV},
+    memory::MemKVStore,
+};
+use svm_runtime::{
+    error::SpawnAppError,
+    settings::AppSettings,
+    testing::{self, FaultyMemKVStore},
+    traits::Runtime,
+};
+use svm_storage::page::{PageIndex, PageOffset, PageSliceLayout, PAGE_SIZE};
+
+const PAGE_COUNT: u16 = 10;
+
+fn settings() -> AppSettings {
+    AppSettings {
+        page_count: PAGE_COUNT,
+        page_size: PAGE_SIZE,
+        map_pages: 0,
+    }
+}
+
+fn ctor_data() -> Vec<u8> {
+    vec![0xAA, 0xBB, 0xCC]
+}
+
+fn run_data() -> Vec<u8> {
+    vec![0x10, 0x20, 0x30, 0x40]
+}
+
+fn template_bytes(wasm: &str) -> Vec<u8> {
+    testing::build_template(0, "Template #1", PAGE_COUNT, wasm)
+}
+
+/// The progress of a (possibly interrupted) scenario run.
+#[derive(Default)]
+struct Progress {
+    template_addr: Option<Address>,
+    app_addr: Option<Address>,
+    executed: bool,
+
+    /// The template deployed (by the same runtime) following a failed step
+    recovery_template_addr: Option<Address>,
+}
+
+/// Deploys a template, spawns an app (its `ctor` writes page #0) and then executes
+/// an app-transaction (writing page #1).
+///
+/// Stops at the first failure, after deploying another template using the same runtime
+/// (for asserting the failed step doesn't leave any writes behind to be persisted by the next operation).
+fn run_scenario(kv: &Rc<RefCell<FaultyMemKVStore>>, progress: &mut Progress) {
+    let version = 0;
+    let host = std::ptr::null_mut();
+    let mut runtime = testing::create_faulty_runtime(host, kv, Vec::new());
+
+    // 1) deploying the template
+    let bytes = template_bytes(include_str!("wasm/crash_consistency.wast"));
+
+    let template_addr =
+        match runtime.deploy_template(&Address::of("author"), HostCtx::new(), &bytes) {
+            Ok(addr) => addr,
+            Err(..) => return recover(&mut runtime, progress),
+        };
+    progress.template_addr = Some(template_addr.clone());
+
+    // 2) spawning the app
+    let ctor_buf = vec![ctor_data()];
+    let ctor_args = vec![WasmValue::I32(ctor_data().len() as u32)];
+    let bytes = testing::build_app(version, &template_addr, &ctor_buf, &ctor_args);
+
+    let (app_addr, init_state) =
+        match runtime.spawn_app(&Address::of("creator"), HostCtx::new(), &bytes) {
+            Ok(spawned) => spawned,
+            Err(..) => return recover(&mut runtime, progress),
+        };
+    progress.app_addr = Some(app_addr.clone());
+
+    // 3) executing an app-transaction
+    let func_buf = vec![run_data()];
+    let func_args = vec![WasmValue::I32(run_data().len() as u32)];
+    let bytes = testing::build_app_tx(version, &app_addr, "run", &func_buf, &func_args);
+
+    let tx = runtime
+        .parse_exec_app(&Address::of("sender"), &bytes)
+        .unwrap();
+
+    match runtime.exec_app(tx, init_state, HostCtx::new()) {
+        Ok(receipt) => {
+            assert!(receipt.success);
+
+            progress.executed = true;
+        }
+        Err(..) => recover(&mut runtime, progress),
+    }
+}
+
+/// Deploys a template (by another author, so it has its own address) using `runtime`, following a failed operation.
+fn recover<R: Runtime>(runtime: &mut R, progress: &mut Progress) {
+    let bytes = template_bytes(include_str!("wasm/crash_consistency.wast"));
+
+    let template_addr = runtime
+        .deploy_template(&Address::of("recovery-author"), HostCtx::new(), &bytes)
+        .unwrap();
+
+    progress.recovery_template_addr = Some(template_addr);
+}
+
+/// Runs the scenario without faults. Returns its progress and the number of key-value writes it has taken.
+fn run_faultless() -> (Progress, usize) {
+    let kv = testing::faulty_kv_store_init();
+    let mut progress = Progress::default();
+
+    run_scenario(&kv, &mut progress);
+    assert!(progress.executed);
+
+    assert_consistent(&kv, &progress, &progress, "faultless run");
+
+    let writes = kv.borrow().writes_count();
+
+    (progress, writes)
+}
+
+/// Runs the scenario with `fault` injected into write #`write`.
+/// A panic stands for the process being killed (so the writes buffered by the runtime are lost).
+fn run_with_fault(write: usize, fault: Fault) -> (Rc<RefCell<FaultyMemKVStore>>, Progress) {
+    let kv = testing::faulty_kv_store_init();
+    kv.borrow_mut().inject(write, fault);
+
+    let mut progress = Progress::default();
+
+    let res = panic::catch_unwind(AssertUnwindSafe(|| run_scenario(&kv, &mut progress)));
+
+    if fault == Fault::Panic {
+        assert!(res.is_err());
+    }
+
+    kv.borrow_mut().clear_faults();
+
+    (kv, progress)
+}
+
+/// Reopens a runtime over `kv` (as after a restart), and asserts that the env stores,
+/// the global-state and the app pages agree. The steps completed successfully must be durable.
+fn assert_consistent(
+    kv: &Rc<RefCell<FaultyMemKVStore>>,
+    progress: &Progress,
+    expected: &Progress,
+    desc: &str,
+) {
+    let host = std::ptr::null_mut();
+    let runtime = testing::create_faulty_runtime(host, kv, Vec::new());
+
+    let template_addr = expected.template_addr.as_ref().unwrap();
+    let app_addr = expected.app_addr.as_ref().unwrap();
+
+    let template = runtime.env.load_template(template_addr).unwrap();
+    let app = runtime.env.load_app(app_addr).unwrap();
+    let state = runtime.prove_app_state(app_addr).unwrap().state;
+
+    if progress.template_addr.is_some() {
+        assert!(template.is_some(), "{}: deployed template is missing", desc);
+    }
+
+    if progress.app_addr.is_some() {
+        assert!(app.is_some(), "{}: spawned app is missing", desc);
+    }
+
+    // an app never exists without its template and state
+    if app.is_some() {
+        assert!(template.is_some(), "{}: app template is missing", desc);
+        assert!(state.is_some(), "{}: app state is missing", desc);
+    }
+
+    // the app state under the global-state always has its pages
+    if let Some(state) = state {
+        let mut storage = runtime.open_app_storage(app_addr, &state, &settings());
+
+        let report = storage.check_integrity().unwrap();
+        assert!(report.is_ok(), "{}: {:?}", desc, report.issues);
+
+        let len = ctor_data().len() as u32;
+        let layout = PageSliceLayout::new(PageIndex(0), PageOffset(0), len);
+        assert_eq!(ctor_data(), storage.read_page_slice(&layout), "{}", desc);
+
+        if progress.executed {
+            let len = run_data().len() as u32;
+            let layout = PageSliceLayout::new(PageIndex(1), PageOffset(0), len);
+            assert_eq!(run_data(), storage.read_page_slice(&layout), "{}", desc);
+        }
+    }
+}
+
+/// Asserts that the writes of the step failed while running the scenario haven't been persisted
+/// (even though the runtime has persisted another operation afterwards).
+fn assert_failed_step_left_nothing(
+    kv: &Rc<RefCell<FaultyMemKVStore>>,
+    progress: &Progress,
+    expected: &Progress,
+    desc: &str,
+) {
+    let host = std::ptr::null_mut();
+    let runtime = testing::create_faulty_runtime(host, kv, Vec::new());
+
+    let recovery_template_addr = progress
+        .recovery_template_addr
+        .as_ref()
+        .unwrap_or_else(|| panic!("{}: the runtime isn't usable after the failure", desc));
+
+    assert!(
+        runtime.env.load_template(recovery_template_addr).unwrap().is_some(),
+        "{}: the template deployed after the failure is missing",
+        desc
+    );
+
+    let template_addr = expected.template_addr.as_ref().unwrap();
+    let app_addr = expected.app_addr.as_ref().unwrap();
+    let state = runtime.prove_app_state(app_addr).unwrap().state;
+
+    if progress.template_addr.is_none() {
+        assert_eq!(Ok(None), runtime.env.load_template(template_addr), "{}", desc);
+    } else if progress.app_addr.is_none() {
+        assert_eq!(Ok(None), runtime.env.load_app(app_addr), "{}", desc);
+        assert_eq!(None, state, "{}", desc);
+    } else {
+        // the app-transaction pages aren't under the app state
+        let mut storage = runtime.open_app_storage(app_addr, &state.unwrap(), &settings());
+
+        let len = run_data().len() as u32;
+        let layout = PageSliceLayout::new(PageIndex(1), PageOffset(0), len);
+        assert_eq!(
+            vec![0; len as usize],
+            storage.read_page_slice(&layout),
+            "{}",
+            desc
+        );
+    }
+}
+
+#[test]
+fn crash_consistency_each_operation_is_a_single_write() {
+    let (_expected, writes) = run_faultless();
+
+    // deploy, spawn and exec each commit their writes as a single batch
+    assert_eq!(3, writes);
+}
+
+#[test]
+fn crash_consistency_write_failure_at_any_point() {
+    let (expected, writes) = run_faultless();
+
+    for write in 0..writes {
+        let (kv, progress) = run_with_fault(write, Fault::Fail);
+
+        assert!(!progress.executed);
+
+        let desc = format!("failure of write #{}", write);
+        assert_consistent(&kv, &progress, &expected, &desc);
+        assert_failed_step_left_nothing(&kv, &progress, &expected, &desc);
+    }
+}
+
+#[test]
+fn crash_consistency_failed_ctor_leaves_nothing_behind() {
+    let version = 0;
+    let kv = testing::faulty_kv_store_init();
+    let mut runtime = testing::create_faulty_runtime(std::ptr::null_mut(), &kv, Vec::new());
+
+    let bytes = template_bytes(include_str!("wasm/crash_consistency_failing_ctor.wast"));
+    let template_addr = runtime
+        .deploy_template(&Address::of("author"), HostCtx::new(), &bytes)
+        .unwrap();
+
+    let creator = Address::of("creator");
+    let ctor_buf = vec![ctor_data()];
+    let ctor_args = vec![WasmValue::I32(ctor_data().len() as u32)];
+    let bytes = testing::build_app(version, &template_addr, &ctor_buf, &ctor_args);
+
+    let spawn_app = runtime.env.parse_app(&bytes, &creator).unwrap();
+    let app_addr = runtime.env.derive_app_address(&spawn_app.app);
+
+    match runtime.spawn_app(&creator, HostCtx::new(), &bytes) {
+        Err(SpawnAppError::CtorFailed(..)) => (),
+        Err(e) => panic!("unexpected error: {:?}", e),
+        Ok(..) => panic!("expected the app `ctor` to fail"),
+    }
+
+    // the next operation doesn't persist the writes of the failed spawn
+    let mut progress = Progress::default();
+    recover(&mut runtime, &mut progress);
+
+    let runtime = testing::create_faulty_runtime(std::ptr::null_mut(), &kv, Vec::new());
+
+    assert_eq!(Ok(None), runtime.env.load_app(&app_addr));
+    assert_eq!(None, runtime.prove_app_state(&app_addr).unwrap().state);
+}
+
+#[test]
+fn crash_consistency_crash_at_any_point() {
+    let (expected, writes) = run_faultless();
+
+    for write in 0..writes {
+        let (kv, progress) = run_with_fault(write, Fault::Panic);
+
+        let desc = format!("crash at write #{}", write);
+        assert_consistent(&kv, &progress, &expected, &desc);
+    }
+}
+
+#[test]
+fn crash_consistency_unflushed_writes_are_lost() {
+    let (expected, _writes) = run_faultless();
+
+    let kv = Rc::new(RefCell::new(
+        FaultyKV::new(MemKVStore::new()).with_write_back(),
+    ));
+
+    let mut progress = Progress::default();
+    run_scenario(&kv, &mut progress);
+    assert!(progress.executed);
+
+    // none of the writes has been flushed by `kv`
+    kv.borrow_mut().crash();
+
+    let runtime = testing::create_faulty_runtime(std::ptr::null_mut(), &kv, Vec::new());
+
+    let template_addr = expected.template_addr.as_ref().unwrap();
+    let app_addr = expected.app_addr.as_ref().unwrap();
+
+    assert_eq!(Ok(None), runtime.env.load_template(template_addr));
+    assert_eq!(Ok(None), runtime.env.load_app(app_addr));
+    assert_eq!(None, runtime.prove_app_state(app_addr).unwrap().state);
+}
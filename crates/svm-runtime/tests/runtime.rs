@@ -1,7 +1,17 @@
-use svm_app::types::{HostCtx, WasmValue};
-use svm_common::{Address, State};
-use svm_runtime::{settings::AppSettings, testing, traits::Runtime};
-use svm_storage::page::{PageIndex, PageOffset, PageSliceLayout};
+use svm_app::{
+    testing::AppTxBuilder,
+    traits::Env,
+    types::{HostCtx, WasmValue},
+};
+use svm_common::{Address, DefaultKeyHasher, State};
+use svm_kv::traits::KVStore;
+use svm_runtime::{
+    error::{ExecAppError, SpawnAppError},
+    settings::AppSettings,
+    testing,
+    traits::Runtime,
+};
+use svm_storage::page::{PageIndex, PageOffset, PageSliceLayout, PAGE_SIZE};
 
 #[test]
 fn runtime_spawn_app_with_ctor() {
@@ -41,7 +51,11 @@ fn runtime_spawn_app_with_ctor() {
 
     let (app_addr, init_state) = runtime.spawn_app(&creator, HostCtx::new(), &bytes).unwrap();
 
-    let settings = AppSettings { page_count };
+    let settings = AppSettings {
+        page_count,
+        page_size: PAGE_SIZE,
+        map_pages: 0,
+    };
     let mut storage = runtime.open_app_storage(&app_addr, &init_state, &settings);
 
     let layout = PageSliceLayout::new(PageIndex(0), PageOffset(0), buf_size);
@@ -53,6 +67,52 @@ fn runtime_spawn_app_with_ctor() {
     );
 }
 
+#[test]
+fn runtime_spawn_app_with_failing_ctor() {
+    // 1) init
+    let version = 0;
+    let kv = testing::memory_kv_store_init();
+    let host = std::ptr::null_mut();
+    let mut runtime = testing::create_memory_runtime(host, &kv, Vec::new());
+    let page_count = 10;
+    let author = Address::of("author");
+    let creator = Address::of("creator");
+
+    // 2) deploying a template whose `ctor` writes page #0 and then traps
+    let bytes = testing::build_template(
+        version,
+        "Template #1",
+        page_count,
+        include_str!("wasm/crash_consistency_failing_ctor.wast"),
+    );
+
+    let template_addr = runtime
+        .deploy_template(&author, HostCtx::new(), &bytes)
+        .unwrap();
+
+    // 3) spawning the app fails
+    let ctor_buf = vec![vec![0xAA, 0xBB, 0xCC]];
+    let ctor_args = vec![WasmValue::I32(3)];
+    let bytes = testing::build_app(version, &template_addr, &ctor_buf, &ctor_args);
+
+    let spawn_app = runtime.env.parse_app(&bytes, &creator).unwrap();
+    let app_addr = runtime.env.derive_app_address(&spawn_app.app);
+
+    match runtime.spawn_app(&creator, HostCtx::new(), &bytes) {
+        Err(SpawnAppError::CtorFailed(..)) => (),
+        res => panic!("expected the app `ctor` to fail (got `{:?}`)", res),
+    }
+
+    // 4) the app hasn't been stored (while its template is kept)
+    assert_eq!(Ok(None), runtime.env.load_app(&app_addr));
+    assert!(runtime.env.load_template(&template_addr).unwrap().is_some());
+
+    let runtime = testing::create_memory_runtime(host, &kv, Vec::new());
+
+    assert_eq!(Ok(None), runtime.env.load_app(&app_addr));
+    assert_eq!(None, runtime.prove_app_state(&app_addr).unwrap().state);
+}
+
 #[test]
 fn runtime_exec_app() {
     // 1) init
@@ -127,7 +187,11 @@ fn runtime_exec_app() {
     // now we'll read directly from the app's storage
     // and assert that the data has been persisted as expected.
 
-    let settings = AppSettings { page_count };
+    let settings = AppSettings {
+        page_count,
+        page_size: PAGE_SIZE,
+        map_pages: 0,
+    };
     let mut storage = runtime.open_app_storage(&app_addr, new_state, &settings);
 
     let layout = PageSliceLayout::new(
@@ -138,4 +202,198 @@ fn runtime_exec_app() {
     let slice = storage.read_page_slice(&layout);
 
     assert_eq!(data, slice);
+
+    // the global-state commits to the app new state
+    let root = runtime.global_state_root();
+    let proof = runtime.prove_app_state(&app_addr).unwrap();
+
+    assert_eq!(Some(new_state.clone()), proof.state);
+    assert!(proof.verify::<DefaultKeyHasher>(&root));
+}
+
+#[test]
+fn runtime_exec_app_with_access_list() {
+    // 1) init
+    let version = 0;
+    let author = Address::of("author");
+    let creator = Address::of("creator");
+    let sender = Address::of("sender");
+    let page_count = 10;
+
+    let kv = testing::memory_kv_store_init();
+    let host = std::ptr::null_mut();
+    let imports = Vec::new();
+    let mut runtime = testing::create_memory_runtime(host, &kv, imports);
+
+    // 2) deploying the template and spawning the app
+    let bytes = testing::build_template(
+        version,
+        "My Template",
+        page_count,
+        include_str!("wasm/runtime_exec_app.wast"),
+    );
+
+    let template_addr = runtime
+        .deploy_template(&author, HostCtx::new(), &bytes)
+        .unwrap();
+
+    let bytes = testing::build_app(version, &template_addr, &vec![], &vec![]);
+    let (app_addr, init_state) = runtime.spawn_app(&creator, HostCtx::new(), &bytes).unwrap();
+
+    // 3) each app-transaction writes `data` to page #1 at `page_offset`.
+    // it declares page #1 (and some other pages, including an out-of-range one) under its access-list.
+    let access_list = vec![1, 2, 1, page_count + 5];
+
+    let build_tx = |data: Vec<u8>, page_offset: u32| {
+        let func_args = vec![
+            WasmValue::I32(0),
+            WasmValue::I32(0),
+            WasmValue::I32(128),
+            WasmValue::I32(3),
+            WasmValue::I32(data.len() as u32),
+            WasmValue::I32(1),
+            WasmValue::I32(page_offset),
+        ];
+
+        AppTxBuilder::new()
+            .with_version(version)
+            .with_app(&app_addr)
+            .with_func_name("run")
+            .with_func_buf(&vec![data])
+            .with_func_args(&func_args)
+            .with_access_list(&access_list)
+            .build()
+    };
+
+    let bytes = build_tx(vec![0x10, 0x20, 0x30], 20);
+    let tx = runtime.parse_exec_app(&sender, &bytes).unwrap();
+    assert_eq!(access_list, tx.access_list);
+
+    let receipt = runtime.exec_app(tx, init_state, HostCtx::new()).unwrap();
+    assert!(receipt.success);
+
+    // the 2nd app-transaction runs against a persisted page #1 (prefetched before execution)
+    let bytes = build_tx(vec![0x40, 0x50], 100);
+    let tx = runtime.parse_exec_app(&sender, &bytes).unwrap();
+
+    let state = receipt.new_state.unwrap();
+    let receipt = runtime.exec_app(tx, state, HostCtx::new()).unwrap();
+    assert!(receipt.success);
+
+    let state = receipt.new_state.unwrap();
+
+    // 4) both writes are reflected
+    let settings = AppSettings {
+        page_count,
+        page_size: PAGE_SIZE,
+        map_pages: 0,
+    };
+    let mut storage = runtime.open_app_storage(&app_addr, &state, &settings);
+
+    let layout = PageSliceLayout::new(PageIndex(1), PageOffset(20), 3);
+    assert_eq!(vec![0x10, 0x20, 0x30], storage.read_page_slice(&layout));
+
+    let layout = PageSliceLayout::new(PageIndex(1), PageOffset(100), 2);
+    assert_eq!(vec![0x40, 0x50], storage.read_page_slice(&layout));
+}
+
+#[test]
+fn runtime_exec_batch_speculatively() {
+    // 1) init
+    let version = 0;
+    let author = Address::of("author");
+    let creator = Address::of("creator");
+    let sender = Address::of("sender");
+    let page_count = 10;
+
+    let kv = testing::memory_kv_store_init();
+    let host = std::ptr::null_mut();
+    let imports = Vec::new();
+    let mut runtime = testing::create_memory_runtime(host, &kv, imports);
+
+    // 2) deploying the template and spawning the app
+    let bytes = testing::build_template(
+        version,
+        "My Template",
+        page_count,
+        include_str!("wasm/runtime_exec_app.wast"),
+    );
+
+    let template_addr = runtime
+        .deploy_template(&author, HostCtx::new(), &bytes)
+        .unwrap();
+
+    let bytes = testing::build_app(version, &template_addr, &vec![], &vec![]);
+    let (app_addr, _init_state) = runtime.spawn_app(&creator, HostCtx::new(), &bytes).unwrap();
+
+    let root = runtime.global_state_root();
+
+    // 3) the batch: two app-transactions writing the app storage (page #1, offset 20)
+    let build_batch = |data: Vec<Vec<u8>>| {
+        data.into_iter()
+            .map(|data| {
+                let count = data.len() as u32;
+                let func_args = vec![
+                    WasmValue::I32(0),
+                    WasmValue::I32(0),
+                    WasmValue::I32(128),
+                    WasmValue::I32(3),
+                    WasmValue::I32(count),
+                    WasmValue::I32(1),
+                    WasmValue::I32(20),
+                ];
+
+                let bytes =
+                    testing::build_app_tx(version, &app_addr, "run", &vec![data], &func_args);
+                let tx = runtime.parse_exec_app(&sender, &bytes).unwrap();
+
+                (tx, HostCtx::new())
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let data = vec![vec![0x10, 0x20, 0x30], vec![0x40, 0x50, 0x60]];
+
+    // 4) speculative execution, then discarding the batch
+    let receipts = runtime.exec_batch(build_batch(data.clone()));
+    assert!(receipts.iter().all(|r| r.as_ref().unwrap().success));
+
+    let new_state = receipts[1].as_ref().unwrap().new_state.clone().unwrap();
+
+    assert_ne!(root, runtime.global_state_root());
+    assert_eq!(None, kv.borrow().get(new_state.as_slice()).unwrap());
+
+    // a standalone call would persist the pending batch changes too
+    let (tx, host_ctx) = build_batch(vec![vec![0x70]]).remove(0);
+    let err = runtime
+        .exec_app(tx, new_state.clone(), host_ctx)
+        .unwrap_err();
+    assert_eq!(ExecAppError::BatchPending, err);
+
+    runtime.discard().unwrap();
+
+    assert_eq!(root, runtime.global_state_root());
+
+    // 5) executing the batch again, then flushing it (the block is final)
+    let receipts = runtime.exec_batch(build_batch(data));
+    assert_eq!(
+        Some(new_state.clone()),
+        receipts[1].as_ref().unwrap().new_state
+    );
+
+    let new_root = runtime.global_state_root();
+    runtime.flush().unwrap();
+
+    assert!(kv.borrow().get(new_state.as_slice()).unwrap().is_some());
+    assert_eq!(new_root, runtime.global_state_root());
+
+    let settings = AppSettings {
+        page_count,
+        page_size: PAGE_SIZE,
+        map_pages: 0,
+    };
+    let mut storage = runtime.open_app_storage(&app_addr, &new_state, &settings);
+
+    let layout = PageSliceLayout::new(PageIndex(1), PageOffset(20), 3);
+    assert_eq!(vec![0x40, 0x50, 0x60], storage.read_page_slice(&layout));
 }
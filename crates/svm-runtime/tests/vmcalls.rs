@@ -366,6 +366,146 @@ fn vmcalls_storage_write_from_reg() {
     assert_eq!(data, after);
 }
 
+#[test]
+fn vmcalls_storage_map() {
+    let key_offset = 100;
+    let value_offset = 200;
+    let key = b"alice".to_vec();
+    let key_len = key.len() as u32;
+    let value = vec![10, 20, 30];
+    let map_pages = 2;
+
+    let (app_addr, state, host, host_ctx, page_count) = default_test_args();
+
+    let import_object = imports! {
+        move || testing::app_memory_map_state_creator(&app_addr, &state, host, host_ctx, page_count, map_pages),
+
+        "svm" => {
+            "storage_map_get" => func!(vmcalls::storage_map_get),
+            "storage_map_set" => func!(vmcalls::storage_map_set),
+            "storage_map_remove" => func!(vmcalls::storage_map_remove),
+        },
+    };
+
+    let instance = testing::instantiate(&import_object, include_str!("wasm/storage_map.wast"));
+
+    let get: Func<(u32, u32, u32), u32> = instance.func("get").unwrap();
+    let set: Func<(u32, u32, u32, u32), u32> = instance.func("set").unwrap();
+    let remove: Func<(u32, u32), u32> = instance.func("remove").unwrap();
+
+    testing::instance_memory_init(&instance, key_offset, &key);
+    testing::instance_memory_init(&instance, value_offset, &value);
+
+    assert_eq!(0, get.call(key_offset, key_len, 300).unwrap());
+
+    let status = set
+        .call(key_offset, key_len, value_offset, value.len() as u32)
+        .unwrap();
+    assert_eq!(vmcalls::MAP_SET_OK, status);
+
+    // invalid values are rejected (and don't override the current value)
+    let status = set.call(key_offset, key_len, value_offset, 0).unwrap();
+    assert_eq!(vmcalls::MAP_SET_EMPTY_VALUE, status);
+
+    let status = set.call(key_offset, key_len, value_offset, 31).unwrap();
+    assert_eq!(vmcalls::MAP_SET_VALUE_TOO_LARGE, status);
+
+    // the value has been copied into memory cells `300..303`
+    assert_eq!(3, get.call(key_offset, key_len, 300).unwrap());
+    assert_eq!(value, testing::instance_memory_view(&instance, 300, 3));
+
+    // the map resides on the app last pages
+    let storage = instance_storage(&instance);
+    assert_eq!(Some(value), storage.map().unwrap().get(&key));
+    assert_eq!(
+        Some(PageIndex(page_count - map_pages)),
+        storage.map_region().map(|r| r.first_page)
+    );
+
+    assert_eq!(1, remove.call(key_offset, key_len).unwrap());
+    assert_eq!(0, remove.call(key_offset, key_len).unwrap());
+    assert_eq!(0, get.call(key_offset, key_len, 300).unwrap());
+}
+
+#[test]
+fn vmcalls_storage_map_of_app_without_map() {
+    let key_offset = 100;
+    let value_offset = 200;
+    let key = b"alice".to_vec();
+    let key_len = key.len() as u32;
+
+    let (app_addr, state, host, host_ctx, page_count) = default_test_args();
+
+    let import_object = imports! {
+        move || testing::app_memory_state_creator(&app_addr, &state, host, host_ctx, page_count),
+
+        "svm" => {
+            "storage_map_get" => func!(vmcalls::storage_map_get),
+            "storage_map_set" => func!(vmcalls::storage_map_set),
+            "storage_map_remove" => func!(vmcalls::storage_map_remove),
+        },
+    };
+
+    let instance = testing::instantiate(&import_object, include_str!("wasm/storage_map.wast"));
+
+    let get: Func<(u32, u32, u32), u32> = instance.func("get").unwrap();
+    let set: Func<(u32, u32, u32, u32), u32> = instance.func("set").unwrap();
+    let remove: Func<(u32, u32), u32> = instance.func("remove").unwrap();
+
+    testing::instance_memory_init(&instance, key_offset, &key);
+    testing::instance_memory_init(&instance, value_offset, &[10, 20, 30]);
+
+    let status = set.call(key_offset, key_len, value_offset, 3).unwrap();
+    assert_eq!(vmcalls::MAP_SET_NO_MAP, status);
+
+    assert_eq!(0, get.call(key_offset, key_len, 300).unwrap());
+    assert_eq!(0, remove.call(key_offset, key_len).unwrap());
+}
+
+#[test]
+fn vmcalls_storage_write_into_map_region_traps() {
+    let page_offset = 100;
+    let mem_offset = 200;
+    let data = vec![10, 20, 30];
+    let count = data.len() as u32;
+    let map_pages = 2;
+
+    let (app_addr, state, host, host_ctx, page_count) = default_test_args();
+    let map_page = (page_count - map_pages) as u32;
+
+    let import_object = imports! {
+        move || testing::app_memory_map_state_creator(&app_addr, &state, host, host_ctx, page_count, map_pages),
+
+        "svm" => {
+            "storage_write_from_mem" => func!(vmcalls::storage_write_from_mem),
+        },
+    };
+
+    let instance = testing::instantiate(
+        &import_object,
+        include_str!("wasm/storage_write_from_mem.wast"),
+    );
+
+    testing::instance_memory_init(&instance, mem_offset, &data[..]);
+
+    let func: Func<(u32, u32, u32, u32)> = instance.func("run").unwrap();
+
+    // the pages preceding the map region are writable
+    assert!(func
+        .call(mem_offset, map_page - 1, page_offset, count)
+        .is_ok());
+
+    assert!(func.call(mem_offset, map_page, page_offset, count).is_err());
+
+    let storage = instance_storage(&instance);
+    let layout = PageSliceLayout::new(
+        PageIndex(map_page as u16),
+        PageOffset(page_offset as u32),
+        count as u32,
+    );
+    assert_eq!(vec![0; count as usize], storage.read_page_slice(&layout));
+}
+
 #[test]
 fn vmcalls_register_push() {
     let reg_bits = 128;
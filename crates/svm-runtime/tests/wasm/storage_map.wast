@@ -0,0 +1,29 @@
+(module
+  ;; import `SVM` vmcalls
+  (func $storage_map_get (import "svm" "storage_map_get") (param i32 i32 i32 i32) (result i32))
+  (func $storage_map_set (import "svm" "storage_map_set") (param i32 i32 i32 i32 i32) (result i32))
+  (func $storage_map_remove (import "svm" "storage_map_remove") (param i32 i32 i32) (result i32))
+
+  (memory 1)  ;; memory `0` (default) is initialized with one page
+
+  ;; exported functions to be called
+  (func (export "get") (param i32 i32 i32) (result i32)
+        i32.const 0  ;; mem_idx
+        get_local 0  ;; key_offset
+        get_local 1  ;; key_len
+        get_local 2  ;; value_offset
+        call $storage_map_get)
+
+  (func (export "set") (param i32 i32 i32 i32) (result i32)
+        i32.const 0  ;; mem_idx
+        get_local 0  ;; key_offset
+        get_local 1  ;; key_len
+        get_local 2  ;; value_offset
+        get_local 3  ;; value_len
+        call $storage_map_set)
+
+  (func (export "remove") (param i32 i32) (result i32)
+        i32.const 0  ;; mem_idx
+        get_local 0  ;; key_offset
+        get_local 1  ;; key_len
+        call $storage_map_remove))
@@ -1,10 +1,23 @@
-use crate::page::{PageHash, PageIndex};
-use crate::traits::{PageHasher, PagesStorage, StateAwarePagesStorage, StateHasher};
+use crate::codec::{self, PageCodec};
+use crate::fsck::{self, FsckReport};
+use crate::gc;
+use crate::page::{self, InvalidPageSize, PageHash, PageIndex};
+use crate::proof::{PageProof, ProofError};
+use crate::shared_page_cache::SharedPageCache;
+use crate::state;
+use crate::traits::{
+    MerkleStateHasher, PageHasher, PagesStorage, StateAwarePagesStorage, StateHasher,
+};
 
 use svm_common::{Address, State};
-use svm_kv::traits::KVStore;
-
-use std::cell::RefCell;
+use svm_kv::{
+    overlay::OverlayKV,
+    traits::{KVBackend, KVChange, KVEntry, KVStore},
+    unit_of_work::UnitOfWork,
+    KVError,
+};
+
+use std::cell::{Cell, RefCell};
 use std::marker::PhantomData;
 use std::rc::Rc;
 
@@ -14,10 +27,22 @@ use log::{debug, error, trace};
 enum PageEntry {
     Uninitialized,
     NotModified(PageHash),
+    /// A not modified zero page (its page-hash is computed on demand, see `zero_hash`)
+    Zero,
     Modified(PageHash, Vec<u8>),
 }
 
 /// `AppPages` is an implemetation of the `PagesStorage` trait that is state aware.
+///
+/// Pages can optionally be read through a `SharedPageCache` (see `with_shared_cache`).
+/// Pages are persisted encoded by a `PageCodec` (see `with_codec`).
+/// Pages are of `PAGE_SIZE` bytes, unless configured otherwise (see `new_with_page_size`).
+///
+/// Zero pages (pages consisting only of zeros) are never persisted. Reading one doesn't hit the key-value store.
+/// In addition, the trailing zero pages are omitted from the state entry (see `init_pages_state`).
+///
+/// A failure of the underlying key-value store while reading isn't fatal.
+/// The first such failure is kept and returned by the next `commit` (nothing gets persisted then).
 /// `KV` - stands for `KVStore`
 /// `PH` - stands for `PageHasher`
 /// `SH` - stands for `StateHasher`
@@ -30,8 +55,15 @@ where
     state: State,
     addr: Address,
     pages: Vec<PageEntry>,
+    /// The zero pages hashes, computed on demand (see `zero_hash`)
+    zero_hashes: Vec<Cell<Option<PageHash>>>,
     kv: Rc<RefCell<KV>>,
+    states_kv: Rc<RefCell<KV>>,
     page_count: u16,
+    page_size: u32,
+    read_error: Option<KVError>,
+    shared_cache: Option<SharedPageCache>,
+    codec: PageCodec,
     marker: PhantomData<(PH, SH)>,
 }
 
@@ -46,53 +78,166 @@ where
     /// * `kv`          - The underlying kv-store used for retrieving a page raw-data when queried by its page-hash serving as a key.
     /// * `state`       - The current app-storage state prior execution of the current app-transaction.
     /// * `page_count` - The number of pages consumed by the app-storage (it's a fixed value per-app).
+    ///
+    /// The states entries (see `init_pages_state`) are stored under `kv` as well.
     pub fn new(addr: Address, kv: Rc<RefCell<KV>>, state: State, page_count: u16) -> Self {
+        let states_kv = Rc::clone(&kv);
+
+        Self::build(addr, kv, states_kv, state, page_count, page::PAGE_SIZE)
+    }
+
+    /// Same as `new`, but each page is of `page_size` bytes.
+    /// Returns `InvalidPageSize` when `page_size` isn't an allowed page size (see `page::is_valid_page_size`).
+    ///
+    /// The page size is part of the app state (see `state::compute_state`),
+    /// so an app storage must always be opened using the same page size.
+    pub fn new_with_page_size(
+        addr: Address,
+        kv: Rc<RefCell<KV>>,
+        state: State,
+        page_count: u16,
+        page_size: u32,
+    ) -> Result<Self, InvalidPageSize> {
+        let states_kv = Rc::clone(&kv);
+
+        Self::build_with_page_size(addr, kv, states_kv, state, page_count, page_size)
+    }
+
+    fn build_with_page_size(
+        addr: Address,
+        kv: Rc<RefCell<KV>>,
+        states_kv: Rc<RefCell<KV>>,
+        state: State,
+        page_count: u16,
+        page_size: u32,
+    ) -> Result<Self, InvalidPageSize> {
+        if !page::is_valid_page_size(page_size) {
+            return Err(InvalidPageSize(page_size));
+        }
+
+        Ok(Self::build(
+            addr, kv, states_kv, state, page_count, page_size,
+        ))
+    }
+
+    fn build(
+        addr: Address,
+        kv: Rc<RefCell<KV>>,
+        states_kv: Rc<RefCell<KV>>,
+        state: State,
+        page_count: u16,
+        page_size: u32,
+    ) -> Self {
         let mut storage = Self {
             state,
             kv,
+            states_kv,
             page_count,
+            page_size,
             addr,
             pages: vec![PageEntry::Uninitialized; page_count as usize],
+            zero_hashes: Vec::new(),
+            read_error: None,
+            shared_cache: None,
+            codec: PageCodec::default(),
             marker: PhantomData,
         };
 
-        storage.init_pages_state();
+        storage.init();
 
         storage
     }
 
+    /// Reads the pages through the process-wide `cache` (shared with other `AppPages`).
+    /// The committed pages are added to `cache` as well.
+    pub fn with_shared_cache(mut self, cache: SharedPageCache) -> Self {
+        self.shared_cache = Some(cache);
+
+        self
+    }
+
+    /// Encodes the committed pages using `codec` (`PageCodec::NoOp` by default).
+    /// Pages persisted using any other codec are still readable.
+    pub fn with_codec(mut self, codec: PageCodec) -> Self {
+        self.codec = codec;
+
+        self
+    }
+
+    fn init(&mut self) {
+        self.zero_hashes = vec![Cell::new(None); self.page_count as usize];
+
+        self.init_pages_state();
+    }
+
     /// Loads the entry:
     /// state ---> [page1_hash || page2_hash || .... || pageN_hash]
     ///
+    /// (when the entry ends with a zero-tail marker, the pages following the listed ones are zero pages).
+    ///
     /// Then, populates `self.pages`. Each page is initialized with `PageEntry::NotModified(page_hash)`
+    /// (or `PageEntry::Zero` for the zero pages omitted from the entry).
+    ///
+    /// When the entry can't be read (or is missing), the pages are initialized as zero-pages and the error is kept
+    /// (to be returned by `commit`).
     fn init_pages_state(&mut self) {
         debug!("initializating pages-storage with state {:?}", self.state);
 
-        if self.state == State::empty() {
-            // `self.state` is `000...0`. It means that state doesn't exist under the key-value store.
-            // This happens when an app runs for the first time.
-            // We initialize each page with its zero-page hash `HASH(addr || page_idx || 0...0)`
-
-            for page_idx in 0..(self.page_count as usize) {
-                let ph = self.compute_zero_page_hash(PageIndex(page_idx as u16));
-                self.pages[page_idx] = PageEntry::NotModified(ph);
+        if self.state != State::empty() {
+            let entry = self.states_kv.borrow().get(self.state.as_slice());
+
+            match entry {
+                Ok(Some(v)) => match state::decode_state_entry(&v) {
+                    Ok((pages_hash, zero_tail)) => {
+                        let listed = pages_hash.len();
+                        let page_count = self.page_count as usize;
+
+                        if listed == page_count || (zero_tail && listed < page_count) {
+                            for page_idx in 0..page_count {
+                                self.pages[page_idx] = match pages_hash.get(page_idx) {
+                                    Some(ph) => {
+                                        trace!("page #{}, has page-hash {:?}", page_idx, ph);
+
+                                        PageEntry::NotModified(*ph)
+                                    }
+                                    None => PageEntry::Zero,
+                                };
+                            }
+
+                            return;
+                        }
+
+                        self.set_read_error(KVError::DataCorruption(format!(
+                            "state {:?} lists {} pages-hash (page count: {})",
+                            self.state, listed, page_count
+                        )));
+                    }
+                    Err(e) => self.set_read_error(e),
+                },
+                Ok(None) => self.set_read_error(KVError::DataCorruption(format!(
+                    "Didn't find state: {:?}",
+                    self.state.as_slice()
+                ))),
+                Err(e) => self.set_read_error(e),
             }
-        } else if let Some(v) = self.kv.borrow().get(self.state.as_slice()) {
-            // `v` should be a concatenation of pages-hash. Each page hash consumes exactly 32 bytes.
-            assert!(v.len() % 32 == 0);
+        }
 
-            for (page_idx, raw_ph) in v.chunks_exact(32).enumerate() {
-                let ph = PageHash::from(raw_ph);
-                self.pages[page_idx] = PageEntry::NotModified(ph);
+        // `self.state` is `000...0`. It means that state doesn't exist under the key-value store.
+        // This happens when an app runs for the first time.
+        // We initialize each page as a zero-page (having page-hash `HASH(addr || page_idx || 0...0)`)
 
-                trace!("page #{}, has page-hash {:?}", page_idx, ph);
-            }
-        } else {
-            error!("Didn't find state: {:?}", self.state.as_slice());
-            panic!("Didn't find state: {:?}", self.state.as_slice());
+        for page in self.pages.iter_mut() {
+            *page = PageEntry::Zero;
         }
     }
 
+    /// Keeps the first read error (see `commit`).
+    fn set_read_error(&mut self, e: KVError) {
+        error!("pages-storage read failed: {}", e);
+
+        self.read_error.get_or_insert(e);
+    }
+
     /// Derives page hash, from its index `page_idx` and data `page_data`.
     #[must_use]
     #[inline]
@@ -104,30 +249,77 @@ where
     #[must_use]
     #[inline]
     pub fn compute_zero_page_hash(&self, page_idx: PageIndex) -> PageHash {
-        let zeros_page = crate::page::zero_page();
+        let zeros_page = page::zero_page(self.page_size);
         self.compute_page_hash(page_idx, zeros_page.as_ref())
     }
 
     /// The number of dirty pages
     pub fn dirty_page_count(&self) -> usize {
         self.pages.iter().fold(0, |acc, page| match page {
-            PageEntry::NotModified(..) => acc,
+            PageEntry::NotModified(..) | PageEntry::Zero => acc,
             PageEntry::Modified(..) => acc + 1,
             PageEntry::Uninitialized => unreachable!(),
         })
     }
 
-    fn prepare_changeset(&self) -> (State, Vec<PageHash>, Vec<(&[u8], &[u8])>) {
+    /// Returns the hash of page indexed `page_idx` when containing only zeros.
+    /// Computed upon first use (opening an app storage shouldn't hash a zero page per app page).
+    fn zero_hash(&self, page_idx: usize) -> PageHash {
+        let cached = &self.zero_hashes[page_idx];
+
+        match cached.get() {
+            Some(ph) => ph,
+            None => {
+                let ph = self.compute_zero_page_hash(PageIndex(page_idx as u16));
+                cached.set(Some(ph));
+
+                ph
+            }
+        }
+    }
+
+    /// Returns whether `ph` is the hash of page indexed `page_idx` when containing only zeros.
+    fn is_zero_hash(&self, page_idx: usize, ph: &PageHash) -> bool {
+        if let PageEntry::Modified(modified_ph, data) = &self.pages[page_idx] {
+            if modified_ph == ph && data.iter().any(|byte| *byte != 0) {
+                // no need to compute the zero page hash
+                return false;
+            }
+        }
+
+        self.zero_hash(page_idx) == *ph
+    }
+
+    /// Returns the reference entries of `new_state` to its pages (see `gc.rs`).
+    /// Zero pages aren't persisted, so they aren't referenced.
+    fn page_refs(&self, new_state: &State, pages_hash: &[PageHash]) -> Vec<KVEntry> {
+        pages_hash
+            .iter()
+            .enumerate()
+            .filter(|(page_idx, ph)| !self.is_zero_hash(*page_idx, ph))
+            .map(|(_page_idx, ph)| (gc::page_ref_key(ph, new_state), Vec::new()))
+            .collect()
+    }
+
+    /// Returns the app states history entries for appending `new_state` (as the most recent state).
+    fn push_app_state(&self, new_state: &State) -> Result<Vec<KVEntry>, KVError> {
+        gc::push_app_state(&*self.states_kv.borrow(), &self.addr, new_state)
+    }
+
+    /// The changeset values are the dirty pages encoded by `self.codec` (except for the zero pages).
+    fn prepare_changeset(&self) -> (State, Vec<PageHash>, Vec<KVEntry>) {
         let mut changes = Vec::new();
 
         let mut pages_hash: Vec<PageHash> = Vec::new();
 
-        for page in self.pages.iter() {
+        for (page_idx, page) in self.pages.iter().enumerate() {
             match page {
                 PageEntry::NotModified(ph) => pages_hash.push(*ph),
+                PageEntry::Zero => pages_hash.push(self.zero_hash(page_idx)),
                 PageEntry::Modified(ph, data) => {
-                    let change: (&[u8], &[u8]) = (&ph.0, data);
-                    changes.push(change);
+                    if !self.is_zero_hash(page_idx, ph) {
+                        changes.push((ph.0.to_vec(), self.codec.encode(data, self.page_size)));
+                    }
 
                     pages_hash.push(*ph);
                 }
@@ -135,13 +327,91 @@ where
             }
         }
 
-        let new_state_hash = SH::hash(pages_hash.as_slice());
-        let new_state = State::from(new_state_hash.as_ref());
+        let new_state = state::compute_state::<SH>(&pages_hash, self.page_size);
 
         (new_state, pages_hash, changes)
     }
 }
 
+impl<KV, PH, SH> AppPages<KV, PH, SH>
+where
+    KV: KVStore,
+    PH: PageHasher,
+    SH: MerkleStateHasher,
+{
+    /// Returns a proof that page indexed `page_idx` has its current content under the current state.
+    /// (see `PageProof::verify`)
+    ///
+    /// Proofs are produced only for committed states, so having dirty pages returns `ProofError::PendingChanges`.
+    /// Returns an error when `page_idx` is out of the pages range.
+    pub fn prove_page(&self, page_idx: PageIndex) -> Result<PageProof, ProofError> {
+        if self.dirty_page_count() > 0 {
+            return Err(ProofError::PendingChanges);
+        }
+
+        if page_idx.0 >= self.page_count {
+            return Err(ProofError::PageOutOfRange {
+                page_idx,
+                page_count: self.page_count,
+            });
+        }
+
+        let pages_hash: Vec<PageHash> = (0..self.page_count)
+            .map(|i| self.get_page_hash(PageIndex(i)))
+            .collect();
+
+        let ph = pages_hash[page_idx.0 as usize];
+
+        // a page with no persisted content is a zero page
+        let page = if self.is_zero_hash(page_idx.0 as usize, &ph) {
+            page::zero_page(self.page_size)
+        } else {
+            match self.kv.borrow().get(&ph.0)? {
+                Some(data) => codec::decode_page(&data, self.page_size)?,
+                None => page::zero_page(self.page_size),
+            }
+        };
+
+        Ok(PageProof {
+            page_idx,
+            page_count: self.page_count,
+            page_size: self.page_size,
+            page,
+            siblings: SH::prove(&pages_hash, page_idx),
+        })
+    }
+}
+
+impl<S, PH, SH> AppPages<OverlayKV<S>, PH, SH>
+where
+    S: KVStore,
+    PH: PageHasher,
+    SH: StateHasher,
+{
+    /// Same as `new_with_page_size`, but the pages and the states entries are kept apart,
+    /// under stores `pages_store` and `states_store` of `uow` (for example, different `rocksdb` column families).
+    ///
+    /// Both stores have their writes buffered by `uow`. So a `commit` is persisted as a single batch
+    /// once `uow` is committed (see `UnitOfWork`).
+    pub fn new_with_unit_of_work<B>(
+        addr: Address,
+        uow: &mut UnitOfWork<B>,
+        pages_store: &str,
+        states_store: &str,
+        state: State,
+        page_count: u16,
+        page_size: u32,
+    ) -> Result<Self, InvalidPageSize>
+    where
+        B: KVBackend<Store = S>,
+    {
+        let kv = uow.open_store(pages_store);
+        let states_kv = uow.open_store(states_store);
+
+        Self::build_with_page_size(addr, kv, states_kv, state, page_count, page_size)
+    }
+}
+
 impl<KV, PH, SH> StateAwarePagesStorage for AppPages<KV, PH, SH>
 where
     KV: KVStore,
@@ -159,9 +429,36 @@ where
         match self.pages[page_idx.0 as usize] {
             PageEntry::NotModified(ph) => ph,
             PageEntry::Modified(ph, _) => ph,
+            PageEntry::Zero => self.zero_hash(page_idx.0 as usize),
             PageEntry::Uninitialized => unreachable!(),
         }
     }
+
+    #[inline]
+    fn page_size(&self) -> u32 {
+        self.page_size
+    }
+
+    fn is_zero_page(&self, page_idx: PageIndex) -> bool {
+        if let PageEntry::Zero = self.pages[page_idx.0 as usize] {
+            return true;
+        }
+
+        let ph = self.get_page_hash(page_idx);
+
+        self.is_zero_hash(page_idx.0 as usize, &ph)
+    }
+
+    fn check_integrity(&self) -> Result<FsckReport, KVError> {
+        fsck::check_app_state::<KV, PH, SH>(
+            &self.addr,
+            &self.state,
+            self.page_count,
+            self.page_size,
+            &*self.kv.borrow(),
+            &*self.states_kv.borrow(),
+        )
+    }
 }
 
 impl<KV, PH, SH> PagesStorage for AppPages<KV, PH, SH>
@@ -172,11 +469,72 @@ where
 {
     #[must_use]
     fn read_page(&mut self, page_idx: PageIndex) -> Option<Vec<u8>> {
-        match self.pages[page_idx.0 as usize] {
-            PageEntry::NotModified(ph) => self.kv.borrow().get(&ph.0),
-            PageEntry::Modified(..) => panic!("Not allowed to read a dirty page"),
-            PageEntry::Uninitialized => unreachable!(),
+        self.read_pages(&[page_idx]).pop().unwrap()
+    }
+
+    /// The pages not found under the shared cache are read using a single `get_many` of the key-value store.
+    fn read_pages(&mut self, pages: &[PageIndex]) -> Vec<Option<Vec<u8>>> {
+        let mut result = vec![None; pages.len()];
+
+        // the pages to read from the key-value store (position under `pages` and page-hash)
+        let mut to_read = Vec::new();
+
+        for (i, page_idx) in pages.iter().enumerate() {
+            let ph = match self.pages[page_idx.0 as usize] {
+                PageEntry::NotModified(ph) => ph,
+                // zero pages aren't persisted
+                PageEntry::Zero => continue,
+                PageEntry::Modified(..) => panic!("Not allowed to read a dirty page"),
+                PageEntry::Uninitialized => unreachable!(),
+            };
+
+            if self.is_zero_hash(page_idx.0 as usize, &ph) {
+                // zero pages aren't persisted
+                continue;
+            }
+
+            if let Some(cache) = self.shared_cache.as_ref() {
+                if let Some(page) = cache.get(&ph) {
+                    result[i] = Some(page);
+                    continue;
+                }
+            }
+
+            to_read.push((i, ph));
         }
+
+        if to_read.is_empty() {
+            return result;
+        }
+
+        let keys: Vec<&[u8]> = to_read.iter().map(|(_i, ph)| &ph.0[..]).collect();
+        let res = self.kv.borrow().get_many(&keys);
+
+        let values = match res {
+            Ok(values) => values,
+            Err(e) => {
+                self.set_read_error(e);
+                return result;
+            }
+        };
+
+        for ((i, ph), data) in to_read.into_iter().zip(values) {
+            match data
+                .map(|data| codec::decode_page(&data, self.page_size))
+                .transpose()
+            {
+                Ok(page) => {
+                    if let (Some(cache), Some(page)) = (self.shared_cache.as_ref(), page.as_ref()) {
+                        cache.insert(ph, page.clone());
+                    }
+
+                    result[i] = page;
+                }
+                Err(e) => self.set_read_error(e),
+            }
+        }
+
+        result
     }
 
     fn write_page(&mut self, page_idx: PageIndex, page_data: &[u8]) {
@@ -191,47 +549,100 @@ where
         for page in &mut self.pages {
             match page {
                 PageEntry::Modified(ph, ..) => *page = PageEntry::NotModified(*ph),
-                PageEntry::NotModified(..) => (),
+                PageEntry::NotModified(..) | PageEntry::Zero => (),
                 PageEntry::Uninitialized => unreachable!(),
             }
         }
     }
 
-    fn commit(&mut self) {
+    fn commit(&mut self) -> Result<(), KVError> {
+        if let Some(e) = self.read_error.take() {
+            error!("pages-storage won't commit since a read has failed: {}", e);
+
+            return Err(e);
+        }
+
         // We have each page-hash (dirty and non-dirty) under `self.pages`
         // Now, we'll compute the new state of the App pages.
         //
         // ```
-        // new_state = HASH(page1_hash || page2_hash || ... || pageN_hash)
+        // new_state = SH::hash([page1_hash, page2_hash, ..., pageN_hash])
         // ```
+        //
+        // (`DefaultStateHasher` computes the root of a Merkle tree of the pages-hash).
+        // A non-default page size is mixed into the new state as well (see `state::compute_state`).
 
         debug!("about to commit dirty pages to underlying key-value store");
 
         let (new_state, pages_hash, changeset) = self.prepare_changeset();
 
-        let mut entries: Vec<(&[u8], &[u8])> = Vec::with_capacity(1 + changeset.len());
+        let state_entry_val =
+            state::encode_state_entry(&pages_hash, |i| self.is_zero_hash(i, &pages_hash[i]));
+        let state_change: KVChange = (new_state.as_slice(), Some(state_entry_val.as_ref()));
 
-        let state_entry_val: Vec<u8> = pages_hash.iter().flat_map(|ph| ph.0.to_vec()).collect();
-        entries.push((new_state.as_slice(), state_entry_val.as_ref()));
+        // GC bookkeeping (see `gc.rs`). The new state references each of its pages
+        // (re-committing an existing state rewrites the same entries).
+        // The app states history is used later for pruning the app old states.
+        let page_refs = self.page_refs(&new_state, &pages_hash);
 
-        for change in changeset {
-            entries.push(change)
-        }
+        let history = self.push_app_state(&new_state)?;
 
-        // At last, we store under the flat key-value store (`self.kv`) the following new entries:
+        // At last, we store under the flat key-value store (`self.kv`) the following new entries (zero pages excluded):
         // ```
-        // new_state  ---> [page1_hash, page2_hash, ..., pageN_hash]
-        // page1_hash ---> page1_content
-        // page2_hash ---> page2_content
+        // page1_hash ---> encoded page1_content
+        // page2_hash ---> encoded page2_content
+        // ...
         // ...
+        // pageN_hash ---> encoded pageN_content
+        //
+        // page-refs:page1_hash || new_state ---> []
         // ...
-        // pageN_hash ---> pageN_content
         // ```
+        //
+        // and under `self.states_kv` the entries:
+        // ```
+        // new_state  ---> [page1_hash, page2_hash, ..., pageN_hash]  (see `state::encode_state_entry`)
+        // app-states:app_addr || seq ---> new_state  (see `gc::push_app_state`)
+        // app-states-head:app_addr ---> seq + 1
+        // ```
+        //
+        // When both are the same kv-store we write everything using a single `KVStore::write`.
+        // Otherwise, both are stores of the same `UnitOfWork` (see `new_with_unit_of_work`),
+        // buffering the writes until the unit-of-work persists them all as a single batch.
+
+        let mut pages_changes: Vec<KVChange> =
+            Vec::with_capacity(changeset.len() + page_refs.len() + 1 + history.len());
+        pages_changes.extend(changeset.iter().map(|(k, v)| (&k[..], Some(&v[..]))));
+        pages_changes.extend(page_refs.iter().map(|(k, v)| (&k[..], Some(&v[..]))));
+
+        let mut states_changes: Vec<KVChange> = vec![state_change];
+        states_changes.extend(history.iter().map(|(k, v)| (&k[..], Some(&v[..]))));
+
+        if Rc::ptr_eq(&self.kv, &self.states_kv) {
+            pages_changes.extend_from_slice(&states_changes);
+
+            self.kv.borrow_mut().write(&pages_changes)?;
+        } else {
+            self.kv.borrow_mut().write(&pages_changes)?;
+            self.states_kv.borrow_mut().write(&states_changes)?;
+        }
+
+        if let Some(cache) = self.shared_cache.as_ref() {
+            for (page_idx, page) in self.pages.iter().enumerate() {
+                match page {
+                    PageEntry::Modified(ph, data) if !self.is_zero_hash(page_idx, ph) => {
+                        cache.insert(*ph, data.clone())
+                    }
+                    _ => (),
+                }
+            }
+        }
 
-        self.kv.borrow_mut().store(entries.as_slice());
         self.state = new_state;
 
         self.clear();
+
+        Ok(())
     }
 }
 
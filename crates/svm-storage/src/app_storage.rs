@@ -1,10 +1,13 @@
 use crate::{
+    fsck::FsckReport,
+    map::{MapError, MapRegion, StorageMap},
     page::{self, PageIndex, PageOffset, PageSliceLayout},
     traits::PageCache,
 };
 
 use std::collections::HashMap;
 use svm_common::State;
+use svm_kv::KVError;
 
 use log::{debug, trace};
 
@@ -15,12 +18,38 @@ struct PageSlice {
     layout: PageSliceLayout,
 }
 
+/// A savepoint of the page-slices written to an `AppStorage` (see `AppStorage::checkpoint`).
+///
+/// Besides its nesting depth, a checkpoint carries the generation it has been taken at,
+/// so that a handle of an inactive checkpoint never refers to a checkpoint taken later at the same depth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint {
+    depth: usize,
+    generation: u64,
+}
+
+/// The page-slices overwritten since a checkpoint has been taken, along with their value at that time
+/// (`None` for a page-slice that wasn't cached then).
+type UndoLog = HashMap<(PageIndex, PageOffset), Option<PageSlice>>;
+
 /// `AppStorage` is a caching layer on top of the `PageCache`.
 /// While `PageCache` deals with data involving only page units, `AppStorage` has fine-grained
 /// control for various sized of data.
+///
+/// Writes can be partially undone using (nested) checkpoints (see `checkpoint`, `rollback_to` and `release`).
+///
+/// An app storage may dedicate a region of its pages to a key-value map (see `with_map_region` and `map`).
 pub struct AppStorage {
     cached_slices: HashMap<PageIndex, HashMap<PageOffset, PageSlice>>,
 
+    // The generation and undo-log of each active checkpoint (the last one is the most recent)
+    checkpoints: Vec<(u64, UndoLog)>,
+
+    // The generation of the next checkpoint to be taken
+    next_generation: u64,
+
+    map_region: Option<MapRegion>,
+
     page_cache: Box<dyn PageCache>,
 }
 
@@ -34,6 +63,40 @@ impl AppStorage {
         Self {
             page_cache,
             cached_slices: HashMap::new(),
+            checkpoints: Vec::new(),
+            next_generation: 0,
+            map_region: None,
+        }
+    }
+
+    /// Dedicates the `region` pages to the app storage key-value map (see `map`).
+    pub fn with_map_region(mut self, region: MapRegion) -> Self {
+        self.map_region = Some(region);
+        self
+    }
+
+    /// The pages dedicated to the app storage key-value map (if any)
+    #[inline]
+    pub fn map_region(&self) -> Option<MapRegion> {
+        self.map_region
+    }
+
+    /// Whether page `page_idx` is dedicated to the app storage key-value map
+    #[inline]
+    pub fn is_map_page(&self, page_idx: PageIndex) -> bool {
+        match self.map_region {
+            Some(region) => region.contains(page_idx),
+            None => false,
+        }
+    }
+
+    /// Returns the app storage key-value map.
+    ///
+    /// Returns `MapError::NoMapRegion` when the app storage has no map region (see `with_map_region`).
+    pub fn map(&mut self) -> Result<StorageMap<'_>, MapError> {
+        match self.map_region {
+            Some(region) => Ok(StorageMap::new(self, region)),
+            None => Err(MapError::NoMapRegion),
         }
     }
 
@@ -47,6 +110,8 @@ impl AppStorage {
     pub fn read_page_slice(&mut self, layout: &PageSliceLayout) -> Vec<u8> {
         debug!("reading page-slice: {:?}", layout);
 
+        assert!(layout.fits_page(self.page_size()));
+
         let page_idx = layout.page_index();
 
         match self.get_page_slices(page_idx) {
@@ -112,7 +177,7 @@ impl AppStorage {
         trace!("    page-slice data:");
         trace!("    {:?}", data);
 
-        assert!(layout.len() < page::PAGE_SIZE);
+        assert!(layout.fits_page(self.page_size()));
 
         let page_idx = layout.page_index();
 
@@ -128,7 +193,145 @@ impl AppStorage {
 
         let page_slices = self.get_page_slices_mut(page_idx).unwrap();
 
-        page_slices.insert(layout.page_offset(), slice);
+        let prev = page_slices.insert(layout.page_offset(), slice);
+
+        if let Some((_, undo_log)) = self.checkpoints.last_mut() {
+            // copy-on-write: only the first overwrite (since the checkpoint) of a page-slice is recorded
+            undo_log
+                .entry((page_idx, layout.page_offset()))
+                .or_insert(prev);
+        }
+    }
+
+    /// Takes a checkpoint of the page-slices written so far. Checkpoints can be nested.
+    ///
+    /// Taking a checkpoint is cheap. A page-slice is copied only when it's overwritten afterwards
+    /// (and at most once per checkpoint).
+    pub fn checkpoint(&mut self) -> Checkpoint {
+        let checkpoint = Checkpoint {
+            depth: self.checkpoints.len(),
+            generation: self.next_generation,
+        };
+
+        self.next_generation += 1;
+        self.checkpoints
+            .push((checkpoint.generation, UndoLog::new()));
+
+        debug!("taking checkpoint #{}", checkpoint.depth);
+
+        checkpoint
+    }
+
+    /// Undoes the page-slices writes done since `checkpoint` has been taken.
+    /// `checkpoint` and the checkpoints taken after it are no longer active afterwards.
+    ///
+    /// Panics when `checkpoint` isn't active.
+    pub fn rollback_to(&mut self, checkpoint: Checkpoint) {
+        self.ensure_active(checkpoint);
+
+        debug!("rolling back to checkpoint #{}", checkpoint.depth);
+
+        while self.checkpoints.len() > checkpoint.depth {
+            let (_, undo_log) = self.checkpoints.pop().unwrap();
+
+            for ((page_idx, offset), prev) in undo_log {
+                let page_slices = self
+                    .cached_slices
+                    .entry(page_idx)
+                    .or_insert_with(HashMap::new);
+
+                match prev {
+                    Some(slice) => page_slices.insert(offset, slice),
+                    None => page_slices.remove(&offset),
+                };
+            }
+        }
+    }
+
+    /// Keeps the page-slices writes done since `checkpoint` has been taken.
+    /// `checkpoint` and the checkpoints taken after it are no longer active afterwards,
+    /// and their writes are undone only when rolling back to an earlier checkpoint.
+    ///
+    /// Panics when `checkpoint` isn't active.
+    pub fn release(&mut self, checkpoint: Checkpoint) {
+        self.ensure_active(checkpoint);
+
+        debug!("releasing checkpoint #{}", checkpoint.depth);
+
+        let released = self.checkpoints.split_off(checkpoint.depth);
+
+        if let Some((_, undo_log)) = self.checkpoints.last_mut() {
+            // the enclosing checkpoint keeps the earliest recorded value of each page-slice
+            for (_, undo) in released {
+                for (key, prev) in undo {
+                    undo_log.entry(key).or_insert(prev);
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn ensure_active(&self, checkpoint: Checkpoint) {
+        let active = match self.checkpoints.get(checkpoint.depth) {
+            Some((generation, _)) => *generation == checkpoint.generation,
+            None => false,
+        };
+
+        assert!(active, "checkpoint #{} isn't active", checkpoint.depth);
+    }
+
+    /// The size (in bytes) of each page of the underlying `page_cache`
+    #[inline]
+    pub fn page_size(&self) -> u32 {
+        self.page_cache.page_size()
+    }
+
+    /// Reads `len` bytes starting at the logical storage offset `offset` (i.e `page_idx * page_size + page_offset`).
+    ///
+    /// The slice may span pages (or be bigger than a page), it's read as consecutive page-slices (see `page::slice_layouts`).
+    /// As with `read_page_slice`, a slice should be read using the same offset and length it has been written with.
+    pub fn read_slice(&mut self, offset: u32, len: u32) -> Vec<u8> {
+        let mut data = Vec::with_capacity(len as usize);
+
+        for layout in page::slice_layouts(offset, len, self.page_size()) {
+            data.extend_from_slice(&self.read_page_slice(&layout));
+        }
+
+        data
+    }
+
+    /// Writes `data` starting at the logical storage offset `offset` (see `read_slice`).
+    /// The slice may span pages, each of its page-slices is written as by `write_page_slice`.
+    pub fn write_slice(&mut self, offset: u32, data: &[u8]) {
+        let mut start = 0;
+
+        for layout in page::slice_layouts(offset, data.len() as u32, self.page_size()) {
+            let end = start + layout.len() as usize;
+
+            self.write_page_slice(&layout, &data[start..end]);
+
+            start = end;
+        }
+    }
+
+    /// Loads the pages indexed `pages` into the underlying `page_cache` ahead of their reads
+    /// (for example, the pages declared by the access-list of the executed transaction).
+    /// The pages not prefetched are still read on demand.
+    pub fn prefetch(&mut self, pages: &[PageIndex]) {
+        self.page_cache.prefetch(pages)
+    }
+
+    /// Reads page `page_idx` as a whole, directly from the underlying `page_cache`.
+    /// Page-slices written but not committed yet aren't reflected.
+    #[must_use]
+    pub fn read_page(&mut self, page_idx: PageIndex) -> Option<Vec<u8>> {
+        self.page_cache.read_page(page_idx)
+    }
+
+    /// Checks the integrity of the underlying pages-storage current state (see `fsck::check_app_state`).
+    /// Page-slices written but not committed yet aren't reflected.
+    pub fn check_integrity(&self) -> Result<FsckReport, KVError> {
+        self.page_cache.check_integrity()
     }
 
     /// * Clears the `cached_slices`
@@ -139,6 +342,7 @@ impl AppStorage {
         debug!("clearing page-slice cache...");
 
         self.cached_slices.clear();
+        self.checkpoints.clear();
         self.page_cache.clear();
     }
 
@@ -152,12 +356,14 @@ impl AppStorage {
     /// * For each patched cached page we do `page_cache.write_page(..)`
     ///
     /// * We do `page_cache.commit()` and return the new underlying page-storage `State`.
+    ///   An error is returned when the underlying key-value store has failed (nothing is persisted then).
+    ///
+    /// * The active checkpoints are released (there is nothing left to rollback).
     ///
     /// * We don't do a `clear`. In real-life usage, the `svm` will call a `commit()`
     ///   after terimnating execution of the app. The `clear` method is intended to be
     ///   used *only* for `tests`
-    #[must_use]
-    pub fn commit(&mut self) -> State {
+    pub fn commit(&mut self) -> Result<State, KVError> {
         debug!("commiting page-slice cache to underlying pages-storage");
 
         let mut dirty_pages_slices = HashMap::<PageIndex, Vec<PageSlice>>::new();
@@ -177,13 +383,15 @@ impl AppStorage {
             }
         }
 
+        let page_size = self.page_size();
+
         let mut dirty_pages = dirty_pages_indexes
             .iter()
             .map(|&page_idx| {
                 let page_bytes = if let Some(bytes) = self.page_cache.read_page(page_idx) {
                     bytes
                 } else {
-                    page::zero_page()
+                    page::zero_page(page_size)
                 };
 
                 (page_idx, page_bytes)
@@ -203,13 +411,15 @@ impl AppStorage {
             self.page_cache.write_page(page_idx, &page);
         }
 
-        self.page_cache.commit();
+        self.page_cache.commit()?;
         debug!("finished commiting page-slice cache changes...");
 
+        self.checkpoints.clear();
+
         let state = self.page_cache.get_state();
         debug!("new app state: {:?}", state);
 
-        state
+        Ok(state)
     }
 
     /// Applies a slice edit on top of a `page`
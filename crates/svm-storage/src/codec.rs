@@ -0,0 +1,334 @@
+use svm_kv::KVError;
+
+/// A zero-run shorter than that isn't worth a new run header (see `PageCodec::ZeroRun`).
+const MIN_ZERO_RUN: usize = 4;
+
+/// The encoding of a page persisted under the key-value store (see `AppPages::with_codec`).
+///
+/// An encoded page starts with a version byte (the codec tag) followed by the codec output:
+///
+/// ```text
+/// encoded_page = codec_tag || codec_output
+/// ```
+///
+/// That way, a page is always decoded by the codec it was encoded with (see `decode_page`),
+/// and changing the codec doesn't affect the already persisted pages.
+///
+/// Pages persisted before the codecs were introduced are untagged (the raw page, exactly `page_size` bytes).
+/// Hence an encoded page is never exactly `page_size` bytes long (see `encode`).
+///
+/// The codec has nothing to do with the page-hash (and hence with the app `State`),
+/// which is always computed over the uncompressed page.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PageCodec {
+    /// The page is persisted as is
+    NoOp,
+
+    /// The page is compressed using `lz4` (block format, prefixed with the uncompressed size)
+    Lz4,
+
+    /// The page is encoded as a sequence of runs: `zeros_count (u16) || literal_len (u16) || literal`.
+    /// (both lengths are Big-Endian). Cheap and effective for pages consisting mostly of zeros.
+    ZeroRun,
+}
+
+impl Default for PageCodec {
+    fn default() -> Self {
+        PageCodec::NoOp
+    }
+}
+
+impl PageCodec {
+    /// The codec tag (the first byte of each encoded page)
+    pub fn tag(&self) -> u8 {
+        match self {
+            PageCodec::NoOp => 0,
+            PageCodec::Lz4 => 1,
+            PageCodec::ZeroRun => 2,
+        }
+    }
+
+    /// Returns the codec tagged `tag` (`None` for an unknown tag)
+    pub fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            0 => Some(PageCodec::NoOp),
+            1 => Some(PageCodec::Lz4),
+            2 => Some(PageCodec::ZeroRun),
+            _ => None,
+        }
+    }
+
+    /// Encodes `page` (of a storage having pages of `page_size` bytes). The output is prefixed with the codec tag.
+    ///
+    /// When the output happens to be exactly `page_size` bytes long (i.e it would be decoded as an untagged page),
+    /// the page is encoded using `ZeroRun` instead, padded by an empty run if needed.
+    pub fn encode(&self, page: &[u8], page_size: u32) -> Vec<u8> {
+        let page_size = page_size as usize;
+
+        let mut encoded = vec![self.tag()];
+
+        match self {
+            PageCodec::NoOp => encoded.extend_from_slice(page),
+            PageCodec::Lz4 => {
+                encoded.extend_from_slice(&lz4_flex::block::compress_prepend_size(page))
+            }
+            PageCodec::ZeroRun => zero_run_encode(page, &mut encoded),
+        }
+
+        if encoded.len() == page_size && *self != PageCodec::ZeroRun {
+            return PageCodec::ZeroRun.encode(page, page_size as u32);
+        }
+
+        if encoded.len() == page_size {
+            // an empty run (no zeros, no literal)
+            encoded.extend_from_slice(&[0, 0, 0, 0]);
+        }
+
+        encoded
+    }
+}
+
+/// Decodes a page encoded by `PageCodec::encode` (using the codec the page was encoded with),
+/// of a storage having pages of `page_size` bytes.
+///
+/// A `data` of exactly `page_size` bytes is an untagged page (persisted before the codecs were introduced).
+/// Returns `KVError::DataCorruption` when `data` isn't a valid encoded page,
+/// or when the decoded page is larger than `page_size`.
+pub fn decode_page(data: &[u8], page_size: u32) -> Result<Vec<u8>, KVError> {
+    let page_size = page_size as usize;
+
+    if data.len() == page_size {
+        return Ok(data.to_vec());
+    }
+
+    let (tag, payload) = match data.split_first() {
+        Some((tag, payload)) => (*tag, payload),
+        None => return Err(corruption("missing codec tag".to_string())),
+    };
+
+    let page = match PageCodec::from_tag(tag) {
+        Some(PageCodec::NoOp) => payload.to_vec(),
+        Some(PageCodec::Lz4) => lz4_decode(payload, page_size)?,
+        Some(PageCodec::ZeroRun) => zero_run_decode(payload, page_size)?,
+        None => return Err(corruption(format!("unknown codec tag: {}", tag))),
+    };
+
+    if page.len() > page_size {
+        return Err(corruption(format!(
+            "decoded page is {} bytes long (page size: {})",
+            page.len(),
+            page_size
+        )));
+    }
+
+    Ok(page)
+}
+
+/// Decodes a `compress_prepend_size` output. The prepended size (Little-Endian) is capped by `page_size`
+/// (otherwise, a corrupted size would make us allocate up to 4GB).
+fn lz4_decode(payload: &[u8], page_size: usize) -> Result<Vec<u8>, KVError> {
+    if payload.len() < 4 {
+        return Err(corruption("truncated lz4 size".to_string()));
+    }
+
+    let (size, compressed) = payload.split_at(4);
+    let size = u32::from_le_bytes([size[0], size[1], size[2], size[3]]) as usize;
+
+    if size > page_size {
+        return Err(corruption(format!(
+            "lz4 size {} exceeds page size {}",
+            size, page_size
+        )));
+    }
+
+    lz4_flex::block::decompress(compressed, size).or_else(|e| Err(corruption(format!("{}", e))))
+}
+
+fn zero_run_encode(page: &[u8], encoded: &mut Vec<u8>) {
+    let max_run = u16::max_value() as usize;
+    let mut i = 0;
+
+    while i < page.len() {
+        let zeros_start = i;
+
+        while i < page.len() && page[i] == 0 && i - zeros_start < max_run {
+            i += 1;
+        }
+
+        let literal_start = i;
+
+        while i < page.len() && i - literal_start < max_run && !is_zero_run(&page[i..]) {
+            i += 1;
+        }
+
+        let zeros = (literal_start - zeros_start) as u16;
+        let literal = &page[literal_start..i];
+
+        encoded.extend_from_slice(&zeros.to_be_bytes());
+        encoded.extend_from_slice(&(literal.len() as u16).to_be_bytes());
+        encoded.extend_from_slice(literal);
+    }
+}
+
+fn zero_run_decode(mut payload: &[u8], page_size: usize) -> Result<Vec<u8>, KVError> {
+    let mut page = Vec::new();
+
+    while !payload.is_empty() {
+        if payload.len() < 4 {
+            return Err(corruption("truncated zero-run header".to_string()));
+        }
+
+        let zeros = u16::from_be_bytes([payload[0], payload[1]]) as usize;
+        let literal_len = u16::from_be_bytes([payload[2], payload[3]]) as usize;
+        payload = &payload[4..];
+
+        if payload.len() < literal_len {
+            return Err(corruption("truncated zero-run literal".to_string()));
+        }
+
+        if page.len() + zeros + literal_len > page_size {
+            return Err(corruption("zero-run exceeds page size".to_string()));
+        }
+
+        page.resize(page.len() + zeros, 0);
+        page.extend_from_slice(&payload[..literal_len]);
+
+        payload = &payload[literal_len..];
+    }
+
+    Ok(page)
+}
+
+#[inline]
+fn is_zero_run(data: &[u8]) -> bool {
+    data.len() >= MIN_ZERO_RUN && data[..MIN_ZERO_RUN].iter().all(|byte| *byte == 0)
+}
+
+fn corruption(reason: String) -> KVError {
+    KVError::DataCorruption(format!("invalid encoded page: {}", reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::page::{zero_page, PAGE_SIZE};
+
+    const CODECS: [PageCodec; 3] = [PageCodec::NoOp, PageCodec::Lz4, PageCodec::ZeroRun];
+
+    fn sparse_page() -> Vec<u8> {
+        let mut page = zero_page(PAGE_SIZE);
+
+        page[0] = 1;
+        page[1] = 0;
+        page[2] = 2;
+        page[100..110].copy_from_slice(&[10, 20, 30, 0, 0, 0, 0, 40, 50, 60]);
+        page[PAGE_SIZE as usize - 1] = 255;
+
+        page
+    }
+
+    #[test]
+    fn page_codec_encode_then_decode() {
+        let pages = vec![
+            Vec::new(),
+            vec![10, 20, 30],
+            zero_page(PAGE_SIZE),
+            sparse_page(),
+            (0..PAGE_SIZE).map(|i| (i % 251) as u8).collect(),
+        ];
+
+        for codec in CODECS.iter() {
+            for page in pages.iter() {
+                let encoded = codec.encode(page, PAGE_SIZE);
+
+                assert_eq!(codec.tag(), encoded[0]);
+                assert_eq!(*page, decode_page(&encoded, PAGE_SIZE).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn page_codec_from_tag() {
+        for codec in CODECS.iter() {
+            assert_eq!(Some(*codec), PageCodec::from_tag(codec.tag()));
+        }
+
+        assert_eq!(None, PageCodec::from_tag(3));
+    }
+
+    #[test]
+    fn page_codec_compresses_sparse_pages() {
+        let page = sparse_page();
+
+        assert_eq!(
+            1 + page.len(),
+            PageCodec::NoOp.encode(&page, PAGE_SIZE).len()
+        );
+        assert!(PageCodec::Lz4.encode(&page, PAGE_SIZE).len() < 100);
+        assert!(PageCodec::ZeroRun.encode(&page, PAGE_SIZE).len() < 40);
+
+        // a single run of zeros
+        assert_eq!(
+            1 + 4,
+            PageCodec::ZeroRun
+                .encode(&zero_page(PAGE_SIZE), PAGE_SIZE)
+                .len()
+        );
+    }
+
+    #[test]
+    fn page_codec_zero_run_longer_than_u16() {
+        let page = vec![0; 200_000];
+
+        let encoded = PageCodec::ZeroRun.encode(&page, 200_000);
+
+        assert_eq!(page, decode_page(&encoded, 200_000).unwrap());
+    }
+
+    #[test]
+    fn decode_page_rejects_invalid_data() {
+        assert!(decode_page(&[], PAGE_SIZE).is_err());
+        assert!(decode_page(&[3, 10, 20], PAGE_SIZE).is_err());
+
+        // `ZeroRun` truncated header and literal
+        assert!(decode_page(&[2, 0, 0, 0], PAGE_SIZE).is_err());
+        assert!(decode_page(&[2, 0, 0, 0, 3, 10, 20], PAGE_SIZE).is_err());
+
+        // `Lz4` garbage
+        assert!(decode_page(&[1, 10, 0, 0, 0, 255], PAGE_SIZE).is_err());
+    }
+
+    #[test]
+    fn decode_page_untagged_legacy_page() {
+        let page = sparse_page();
+
+        assert_eq!(page, decode_page(&page, PAGE_SIZE).unwrap());
+    }
+
+    #[test]
+    fn page_codec_output_is_never_page_size_long() {
+        let page = vec![7; PAGE_SIZE as usize - 1];
+
+        for codec in CODECS.iter() {
+            let encoded = codec.encode(&page, PAGE_SIZE);
+
+            assert_ne!(PAGE_SIZE as usize, encoded.len());
+            assert_eq!(page, decode_page(&encoded, PAGE_SIZE).unwrap());
+        }
+    }
+
+    #[test]
+    fn decode_page_rejects_pages_larger_than_page_size() {
+        let page = vec![7; 10];
+
+        for codec in CODECS.iter() {
+            let encoded = codec.encode(&page, 100);
+
+            assert!(decode_page(&encoded, 9).is_err());
+        }
+
+        // `Lz4` size prefix of 4GB
+        assert!(decode_page(&[1, 255, 255, 255, 255, 0], PAGE_SIZE).is_err());
+    }
+}
@@ -0,0 +1,219 @@
+use crate::page::{PageHash, PageIndex, PAGE_HASH_LEN};
+use crate::state::StateHash;
+use crate::traits::{MerkleStateHasher, StateHasher};
+use svm_common::{DefaultKeyHasher, KeyHasher};
+
+use std::marker::PhantomData;
+
+/// Leaves and inner nodes are hashed with a different prefix.
+/// That way a leaf can't be interpreted as an inner node (and vice-versa).
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+pub struct MerkleStateHasherImpl<KH> {
+    marker: PhantomData<KH>,
+}
+
+impl<KH> MerkleStateHasherImpl<KH>
+where
+    KH: KeyHasher<Hash = [u8; 32]>,
+{
+    fn leaf_hash(ph: &PageHash) -> [u8; 32] {
+        let mut data = Vec::with_capacity(1 + PAGE_HASH_LEN);
+
+        data.push(LEAF_PREFIX);
+        data.extend_from_slice(&ph.0);
+
+        KH::hash(&data)
+    }
+
+    fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        let mut data = Vec::with_capacity(1 + 2 * 32);
+
+        data.push(NODE_PREFIX);
+        data.extend_from_slice(left);
+        data.extend_from_slice(right);
+
+        KH::hash(&data)
+    }
+
+    /// Computes the next tree level. A trailing odd node is promoted as is.
+    fn next_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+        level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => Self::node_hash(left, right),
+                [single] => *single,
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    fn leaves(pages_hash: &[PageHash]) -> Vec<[u8; 32]> {
+        pages_hash.iter().map(Self::leaf_hash).collect()
+    }
+}
+
+impl<KH> StateHasher for MerkleStateHasherImpl<KH>
+where
+    KH: KeyHasher<Hash = [u8; 32]>,
+{
+    const ID: u8 = 2;
+
+    /// Given a slice of `PageHash`. `StateHash` is the root of the Merkle tree:
+    ///
+    /// leaf = HASH(0 || page_hash)
+    /// node = HASH(1 || left || right)
+    ///
+    /// When a level has an odd number of nodes, its last node is promoted to the next level.
+    /// An empty slice hashes into `HASH()`.
+    fn hash(pages_hash: &[PageHash]) -> StateHash {
+        if pages_hash.is_empty() {
+            return StateHash(KH::hash(&[]));
+        }
+
+        let mut level = Self::leaves(pages_hash);
+
+        while level.len() > 1 {
+            level = Self::next_level(&level);
+        }
+
+        StateHash(level[0])
+    }
+}
+
+impl<KH> MerkleStateHasher for MerkleStateHasherImpl<KH>
+where
+    KH: KeyHasher<Hash = [u8; 32]>,
+{
+    fn prove(pages_hash: &[PageHash], page_idx: PageIndex) -> Vec<[u8; 32]> {
+        let mut idx = page_idx.0 as usize;
+        assert!(idx < pages_hash.len());
+
+        let mut siblings = Vec::new();
+        let mut level = Self::leaves(pages_hash);
+
+        while level.len() > 1 {
+            let sibling_idx = idx ^ 1;
+
+            if sibling_idx < level.len() {
+                siblings.push(level[sibling_idx]);
+            }
+
+            level = Self::next_level(&level);
+            idx /= 2;
+        }
+
+        siblings
+    }
+
+    fn root_from_proof(
+        page_hash: &PageHash,
+        page_idx: PageIndex,
+        page_count: u16,
+        siblings: &[[u8; 32]],
+    ) -> Option<StateHash> {
+        let mut idx = page_idx.0 as usize;
+        let mut count = page_count as usize;
+
+        if idx >= count {
+            return None;
+        }
+
+        let mut siblings = siblings.iter();
+        let mut hash = Self::leaf_hash(page_hash);
+
+        while count > 1 {
+            if idx % 2 == 1 {
+                hash = Self::node_hash(siblings.next()?, &hash);
+            } else if idx + 1 < count {
+                hash = Self::node_hash(&hash, siblings.next()?);
+            }
+
+            idx /= 2;
+            count = (count + 1) / 2;
+        }
+
+        if siblings.next().is_some() {
+            return None;
+        }
+
+        Some(StateHash(hash))
+    }
+}
+
+/// A default implementation for `StateHasher` trait (Merkle-tree based).
+pub type DefaultStateHasher = MerkleStateHasherImpl<DefaultKeyHasher>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pages_hash(page_count: u16) -> Vec<PageHash> {
+        (0..page_count)
+            .map(|i| PageHash(DefaultKeyHasher::hash(&i.to_be_bytes())))
+            .collect()
+    }
+
+    #[test]
+    fn merkle_state_hasher_single_page() {
+        let ph = pages_hash(1);
+
+        let expected = StateHash(DefaultStateHasher::leaf_hash(&ph[0]));
+        let actual = DefaultStateHasher::hash(&ph);
+
+        assert_eq!(expected, actual);
+        assert!(DefaultStateHasher::prove(&ph, PageIndex(0)).is_empty());
+    }
+
+    #[test]
+    fn merkle_state_hasher_three_pages() {
+        let ph = pages_hash(3);
+
+        let l0 = DefaultStateHasher::leaf_hash(&ph[0]);
+        let l1 = DefaultStateHasher::leaf_hash(&ph[1]);
+        let l2 = DefaultStateHasher::leaf_hash(&ph[2]);
+
+        // the 3rd leaf is promoted
+        let expected = StateHash(DefaultStateHasher::node_hash(
+            &DefaultStateHasher::node_hash(&l0, &l1),
+            &l2,
+        ));
+
+        assert_eq!(expected, DefaultStateHasher::hash(&ph));
+    }
+
+    #[test]
+    fn merkle_state_hasher_proofs_round_trip() {
+        for page_count in 1..=17 {
+            let ph = pages_hash(page_count);
+            let root = DefaultStateHasher::hash(&ph);
+
+            for page_idx in 0..page_count {
+                let page_idx = PageIndex(page_idx);
+                let siblings = DefaultStateHasher::prove(&ph, page_idx);
+
+                let actual = DefaultStateHasher::root_from_proof(
+                    &ph[page_idx.0 as usize],
+                    page_idx,
+                    page_count,
+                    &siblings,
+                );
+
+                assert_eq!(Some(root), actual);
+            }
+        }
+    }
+
+    #[test]
+    fn merkle_state_hasher_proof_with_extra_sibling() {
+        let ph = pages_hash(4);
+
+        let mut siblings = DefaultStateHasher::prove(&ph, PageIndex(1));
+        siblings.push([0; 32]);
+
+        let actual = DefaultStateHasher::root_from_proof(&ph[1], PageIndex(1), 4, &siblings);
+
+        assert_eq!(None, actual);
+    }
+}
@@ -10,10 +10,14 @@ mod page_index_hasher;
 /// Default `crate::traits::PagesStorage` implementation.
 mod pages_storage;
 
-/// Default `crates::traits::StateHasher` implementation.
+/// Default `crates::traits::StateHasher` implementation (a Merkle tree).
+mod merkle_state_hasher;
+
+/// A flat `crates::traits::StateHasher` implementation.
 mod state_hasher;
 
+pub use merkle_state_hasher::DefaultStateHasher;
 pub use page_cache::DefaultPageCache;
 pub use page_hasher::DefaultPageHasher;
 pub use page_index_hasher::DefaultPageIndexHasher;
-pub use state_hasher::DefaultStateHasher;
+pub use state_hasher::FlatStateHasher;
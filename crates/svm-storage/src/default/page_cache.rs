@@ -1,6 +1,8 @@
+use crate::fsck::FsckReport;
 use crate::page::{PageHash, PageIndex};
 use crate::traits::{PageCache, PagesStorage, StateAwarePagesStorage};
 use svm_common::State;
+use svm_kv::KVError;
 
 use log::{debug, trace};
 
@@ -29,7 +31,44 @@ pub struct DefaultPageCache<PS: StateAwarePagesStorage> {
     pages_storage: PS,
 }
 
-impl<PS: StateAwarePagesStorage> PageCache for DefaultPageCache<PS> {}
+impl<PS: StateAwarePagesStorage> PageCache for DefaultPageCache<PS> {
+    fn prefetch(&mut self, pages: &[PageIndex]) {
+        let mut missing = Vec::new();
+
+        for page_idx in pages {
+            let idx = page_idx.0 as usize;
+
+            if idx >= self.cached_pages.len() {
+                debug!("skipping prefetch of page #{} (out of range)", page_idx.0);
+                continue;
+            }
+
+            if let CachedPage::NotCached = self.cached_pages[idx] {
+                if self.pages_storage.is_zero_page(*page_idx) {
+                    // a zero page has no content under `pages_storage`, no need to ask for it
+                    self.cached_pages[idx] = CachedPage::CachedEmpty;
+                } else if !missing.contains(page_idx) {
+                    missing.push(*page_idx);
+                }
+            }
+        }
+
+        if missing.is_empty() {
+            return;
+        }
+
+        debug!("prefetching {} pages", missing.len());
+
+        let loaded = self.pages_storage.read_pages(&missing);
+
+        for (page_idx, page) in missing.iter().zip(loaded) {
+            self.cached_pages[page_idx.0 as usize] = match page {
+                Some(page) => CachedPage::Cached(page),
+                None => CachedPage::CachedEmpty,
+            };
+        }
+    }
+}
 
 impl<PS: StateAwarePagesStorage> StateAwarePagesStorage for DefaultPageCache<PS> {
     #[inline]
@@ -42,6 +81,21 @@ impl<PS: StateAwarePagesStorage> StateAwarePagesStorage for DefaultPageCache<PS>
     fn get_page_hash(&self, page_idx: PageIndex) -> PageHash {
         self.pages_storage.get_page_hash(page_idx)
     }
+
+    #[inline]
+    fn page_size(&self) -> u32 {
+        self.pages_storage.page_size()
+    }
+
+    #[inline]
+    fn is_zero_page(&self, page_idx: PageIndex) -> bool {
+        self.pages_storage.is_zero_page(page_idx)
+    }
+
+    #[inline]
+    fn check_integrity(&self) -> Result<FsckReport, KVError> {
+        self.pages_storage.check_integrity()
+    }
 }
 
 /// A `DefaultPageCache` is caching layer on top of a storage pages.
@@ -79,6 +133,14 @@ impl<PS: StateAwarePagesStorage> PagesStorage for DefaultPageCache<PS> {
         let cache_status = &self.cached_pages[page_idx.0 as usize];
 
         match cache_status {
+            CachedPage::NotCached if self.pages_storage.is_zero_page(page_idx) => {
+                // a zero page has no content under `pages_storage`, no need to ask for it
+                debug!("cache miss for page #{}. page is a zero page!", page_idx.0);
+
+                self.cached_pages[page_idx.0 as usize] = CachedPage::CachedEmpty;
+
+                None
+            }
             CachedPage::NotCached => {
                 // page isn't in the cache, so we delegate to `pages_storage`
                 debug!("cache miss for page #{}", page_idx.0);
@@ -166,7 +228,7 @@ impl<PS: StateAwarePagesStorage> PagesStorage for DefaultPageCache<PS> {
     /// * we call `pages_storage.commit` to flush the persist the changes
     ///
     /// since an app is a short-lived program, we don't clear after `commit`
-    fn commit(&mut self) {
+    fn commit(&mut self) -> Result<(), KVError> {
         debug!("page-cache is about to commit dirty pages to underlying pages-storage");
 
         for ((page_idx, dirty), cached_page) in
@@ -191,7 +253,7 @@ impl<PS: StateAwarePagesStorage> PagesStorage for DefaultPageCache<PS> {
             }
         }
 
-        self.pages_storage.commit();
+        self.pages_storage.commit()
     }
 }
 
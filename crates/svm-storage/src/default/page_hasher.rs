@@ -13,6 +13,8 @@ impl<KH> PageHasher for PageHasherImpl<KH>
 where
     KH: KeyHasher<Hash = [u8; 32]>,
 {
+    const ID: u8 = 1;
+
     /// page_addr = addr + page_idx
     /// ph = HASH(page_addr || HASH(page_data))
     fn hash(addr: Address, page_idx: PageIndex, page_data: &[u8]) -> PageHash {
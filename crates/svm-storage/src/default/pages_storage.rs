@@ -9,7 +9,9 @@ use crate::{
 };
 
 use svm_common::Address;
-use svm_kv::traits::KVStore;
+use svm_kv::{traits::KVStore, KVError};
+
+use log::error;
 
 /// `DefaultPagesStorage` is the default implementation for the `PagesStorage` trait.
 /// It serves as a wrapper to a key-value store.
@@ -29,10 +31,13 @@ use svm_kv::traits::KVStore;
 ///   may fail for multiple reasons, and on such occurrence we don't want to change any state.
 ///   Another benefit is that if the underlying key-value store supports a batch write (for example
 ///   database `rocksdb` has this capability), the `commit` implementation can take advantage of it.
+///
+/// * A failed read is returned by the next `commit` (and nothing is persisted then).
 pub struct DefaultPagesStorage<PIH: PageIndexHasher, KV: KVStore> {
     addr: Address,
     kv: Rc<RefCell<KV>>,
     uncommitted: HashMap<Vec<u8>, Vec<u8>>,
+    read_error: Option<KVError>,
     marker: PhantomData<PIH>,
 }
 
@@ -48,6 +53,7 @@ where
             addr,
             kv,
             uncommitted: HashMap::new(),
+            read_error: None,
             marker: PhantomData,
         }
     }
@@ -68,7 +74,17 @@ where
     fn read_page(&mut self, page_idx: PageIndex) -> Option<Vec<u8>> {
         let ph = self.compute_page_hash(page_idx);
 
-        self.kv.borrow().get(&ph)
+        let res = self.kv.borrow().get(&ph);
+
+        match res {
+            Ok(page) => page,
+            Err(e) => {
+                error!("pages-storage read failed: {}", e);
+
+                self.read_error.get_or_insert(e);
+                None
+            }
+        }
     }
 
     /// Pushes a new pending change (persistence *only* upon `commit`)
@@ -84,15 +100,21 @@ where
     }
 
     /// Commits pending changes to the underlying key-value store
-    fn commit(&mut self) {
+    fn commit(&mut self) -> Result<(), KVError> {
+        if let Some(e) = self.read_error.take() {
+            return Err(e);
+        }
+
         let changes: Vec<(&[u8], &[u8])> = self
             .uncommitted
             .iter()
             .map(|(key, page)| (key.as_ref(), page.as_ref()))
             .collect();
 
-        self.kv.borrow_mut().store(changes.as_slice());
+        self.kv.borrow_mut().store(changes.as_slice())?;
 
         self.clear();
+
+        Ok(())
     }
 }
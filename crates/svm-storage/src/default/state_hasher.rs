@@ -5,14 +5,16 @@ use svm_common::{DefaultKeyHasher, KeyHasher};
 
 use std::marker::PhantomData;
 
-pub struct StateHasherImpl<SH> {
+pub struct FlatStateHasherImpl<SH> {
     marker: PhantomData<SH>,
 }
 
-impl<KH> StateHasher for StateHasherImpl<KH>
+impl<KH> StateHasher for FlatStateHasherImpl<KH>
 where
     KH: KeyHasher<Hash = [u8; 32]>,
 {
+    const ID: u8 = 1;
+
     /// Given a slice of `PageHash`. `StateHash` is derived by:
     ///
     /// HASH(page1_hash || page2_hash || ... || pageN_hash)
@@ -31,8 +33,8 @@ where
     }
 }
 
-/// A default implementation for `StateHasher` trait.
-pub type DefaultStateHasher = StateHasherImpl<DefaultKeyHasher>;
+/// A `StateHasher` implementation hashing the concatenation of all pages-hash.
+pub type FlatStateHasher = FlatStateHasherImpl<DefaultKeyHasher>;
 
 #[cfg(test)]
 mod tests {
@@ -43,7 +45,7 @@ mod tests {
     use svm_common::{Address, DefaultKeyHasher, KeyHasher};
 
     #[test]
-    fn default_state_hasher_sanity() {
+    fn flat_state_hasher_sanity() {
         let page1: Vec<u8> = vec![10, 20, 30];
         let page2: Vec<u8> = vec![40, 50, 60];
 
@@ -58,7 +60,7 @@ mod tests {
         let expected = StateHash(DefaultKeyHasher::hash(&joined_pages_hash));
 
         let pages_hash = vec![page1_hash, page2_hash];
-        let actual = DefaultStateHasher::hash(&pages_hash);
+        let actual = FlatStateHasher::hash(&pages_hash);
 
         assert_eq!(expected, actual);
     }
@@ -0,0 +1,243 @@
+use crate::codec;
+use crate::page::{self, PageHash, PageIndex};
+use crate::state;
+use crate::traits::PageHasher;
+
+use svm_common::{Address, State};
+use svm_kv::{traits::KVStore, KVError};
+
+use serde::{Deserialize, Serialize};
+
+use log::debug;
+
+/// A run of consecutive differing bytes within a page.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ByteRangeDiff {
+    /// The page offset where the run starts
+    pub offset: u32,
+
+    /// The bytes under the old state
+    pub old: Vec<u8>,
+
+    /// The bytes under the new state (same length as `old`)
+    pub new: Vec<u8>,
+}
+
+/// A page whose content differs between two states.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageDiff {
+    /// The changed page index
+    pub page_idx: PageIndex,
+
+    /// The page-hash under the old state
+    pub old_hash: PageHash,
+
+    /// The page-hash under the new state
+    pub new_hash: PageHash,
+
+    /// The differing byte ranges (ordered by offset)
+    pub ranges: Vec<ByteRangeDiff>,
+}
+
+/// The changes of an app storage between two of its states (see `diff_states`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateDiff {
+    /// The app address
+    pub addr: Address,
+
+    /// The state diffed from
+    pub old_state: State,
+
+    /// The state diffed to
+    pub new_state: State,
+
+    /// The changed pages (ordered by page index)
+    pub pages: Vec<PageDiff>,
+}
+
+impl StateDiff {
+    /// Returns whether both states have the same content
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+}
+
+/// Returns the pages (of app `addr` having `page_count` pages of `page_size` bytes) that differ between states `old_state` and `new_state`.
+///
+/// * `kv`        - The pages key-value store.
+/// * `states_kv` - The states key-value store (may be `kv` itself).
+///
+/// Only the pages whose page-hash differs (as listed under both state entries) are loaded.
+/// A page shorter than another is compared as if padded with zeros.
+///
+/// `State::empty()` stands for the app initial state (all pages are zero pages).
+/// A missing state entry or page is reported as `KVError::DataCorruption`.
+pub fn diff_states<KV, PH>(
+    addr: &Address,
+    old_state: &State,
+    new_state: &State,
+    page_count: u16,
+    page_size: u32,
+    kv: &KV,
+    states_kv: &KV,
+) -> Result<StateDiff, KVError>
+where
+    KV: KVStore,
+    PH: PageHasher,
+{
+    debug!(
+        "diffing app {:?} states: {:?} ---> {:?}",
+        addr, old_state, new_state
+    );
+
+    let old_hashes = load_pages_hash::<KV, PH>(addr, old_state, page_count, page_size, states_kv)?;
+    let new_hashes = load_pages_hash::<KV, PH>(addr, new_state, page_count, page_size, states_kv)?;
+
+    let mut pages = Vec::new();
+
+    for (i, (old_hash, new_hash)) in old_hashes.iter().zip(new_hashes.iter()).enumerate() {
+        if old_hash == new_hash {
+            continue;
+        }
+
+        let page_idx = PageIndex(i as u16);
+
+        let old_page = load_page::<KV, PH>(addr, page_idx, old_hash, page_size, kv)?;
+        let new_page = load_page::<KV, PH>(addr, page_idx, new_hash, page_size, kv)?;
+
+        pages.push(PageDiff {
+            page_idx,
+            old_hash: *old_hash,
+            new_hash: *new_hash,
+            ranges: diff_bytes(&old_page, &new_page),
+        });
+    }
+
+    Ok(StateDiff {
+        addr: addr.clone(),
+        old_state: old_state.clone(),
+        new_state: new_state.clone(),
+        pages,
+    })
+}
+
+fn load_pages_hash<KV, PH>(
+    addr: &Address,
+    state: &State,
+    page_count: u16,
+    page_size: u32,
+    states_kv: &KV,
+) -> Result<Vec<PageHash>, KVError>
+where
+    KV: KVStore,
+    PH: PageHasher,
+{
+    if *state == State::empty() {
+        let zero_page = page::zero_page(page_size);
+
+        let pages_hash = (0..page_count)
+            .map(|page_idx| PH::hash(addr.clone(), PageIndex(page_idx), &zero_page))
+            .collect();
+
+        return Ok(pages_hash);
+    }
+
+    match states_kv.get(state.as_slice())? {
+        Some(entry) => state::expand_state_entry::<PH>(addr, &entry, page_count, page_size),
+        None => Err(KVError::DataCorruption(format!(
+            "Didn't find state: {:?}",
+            state
+        ))),
+    }
+}
+
+fn load_page<KV, PH>(
+    addr: &Address,
+    page_idx: PageIndex,
+    ph: &PageHash,
+    page_size: u32,
+    kv: &KV,
+) -> Result<Vec<u8>, KVError>
+where
+    KV: KVStore,
+    PH: PageHasher,
+{
+    let zero_page = page::zero_page(page_size);
+
+    if *ph == PH::hash(addr.clone(), page_idx, &zero_page) {
+        // zero pages aren't persisted
+        return Ok(zero_page);
+    }
+
+    match kv.get(&ph.0)? {
+        Some(data) => codec::decode_page(&data, page_size),
+        None => Err(KVError::DataCorruption(format!(
+            "Didn't find page #{} (page-hash: {:?})",
+            page_idx.0, ph
+        ))),
+    }
+}
+
+/// Returns the maximal runs of differing bytes between `old` and `new`.
+/// The shorter of the two is treated as padded with zeros.
+fn diff_bytes(old: &[u8], new: &[u8]) -> Vec<ByteRangeDiff> {
+    let len = old.len().max(new.len());
+    let byte_at = |bytes: &[u8], i: usize| bytes.get(i).copied().unwrap_or(0);
+
+    let mut ranges = Vec::new();
+    let mut i = 0;
+
+    while i < len {
+        if byte_at(old, i) == byte_at(new, i) {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+
+        while i < len && byte_at(old, i) != byte_at(new, i) {
+            i += 1;
+        }
+
+        ranges.push(ByteRangeDiff {
+            offset: start as u32,
+            old: (start..i).map(|j| byte_at(old, j)).collect(),
+            new: (start..i).map(|j| byte_at(new, j)).collect(),
+        });
+    }
+
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_bytes_same_content() {
+        assert!(diff_bytes(&[1, 2, 3], &[1, 2, 3]).is_empty());
+        assert!(diff_bytes(&[1, 2, 3], &[1, 2, 3, 0, 0]).is_empty());
+    }
+
+    #[test]
+    fn diff_bytes_maximal_runs() {
+        let old = [1, 2, 3, 4, 5, 6];
+        let new = [1, 0, 0, 4, 5, 7, 8];
+
+        let expected = vec![
+            ByteRangeDiff {
+                offset: 1,
+                old: vec![2, 3],
+                new: vec![0, 0],
+            },
+            ByteRangeDiff {
+                offset: 5,
+                old: vec![6, 0],
+                new: vec![7, 8],
+            },
+        ];
+
+        assert_eq!(expected, diff_bytes(&old, &new));
+    }
+}
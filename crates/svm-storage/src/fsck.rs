@@ -0,0 +1,227 @@
+use crate::codec;
+use crate::page::{self, PageHash, PageIndex};
+use crate::state;
+use crate::traits::{PageHasher, StateHasher};
+
+use svm_common::{Address, State};
+use svm_kv::{traits::KVStore, KVError};
+
+use std::fmt;
+
+use log::{debug, error};
+
+/// A problem found while checking an app state (see `check_app_state`).
+#[derive(Debug, Clone, PartialEq)]
+pub enum FsckIssue {
+    /// The state entry doesn't exist
+    MissingState,
+
+    /// The state entry is malformed (or doesn't fit the app page count)
+    InvalidStateEntry(String),
+
+    /// The pages-hash listed under the state entry hash to `actual` (instead of the checked state)
+    StateMismatch {
+        /// The state computed from the listed pages-hash
+        actual: State,
+    },
+
+    /// A (non-zero) page listed under the state entry has no content
+    MissingPage {
+        /// The page index
+        page_idx: PageIndex,
+
+        /// The listed page-hash
+        ph: PageHash,
+    },
+
+    /// A page content can't be decoded or doesn't hash to its listed page-hash
+    CorruptPage {
+        /// The page index
+        page_idx: PageIndex,
+
+        /// The listed page-hash
+        ph: PageHash,
+
+        /// What's wrong with the page content
+        reason: String,
+    },
+}
+
+impl fmt::Display for FsckIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FsckIssue::MissingState => write!(f, "state entry is missing"),
+            FsckIssue::InvalidStateEntry(reason) => write!(f, "invalid state entry: {}", reason),
+            FsckIssue::StateMismatch { actual } => write!(
+                f,
+                "pages-hash of the state entry hash to another state: {:?}",
+                actual
+            ),
+            FsckIssue::MissingPage { page_idx, ph } => {
+                write!(f, "page #{} is missing (page-hash: {:?})", page_idx.0, ph)
+            }
+            FsckIssue::CorruptPage {
+                page_idx,
+                ph,
+                reason,
+            } => write!(
+                f,
+                "page #{} is corrupt (page-hash: {:?}): {}",
+                page_idx.0, ph, reason
+            ),
+        }
+    }
+}
+
+/// The outcome of checking an app state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FsckReport {
+    /// The checked app
+    pub addr: Address,
+
+    /// The checked state
+    pub state: State,
+
+    /// Number of pages whose content has been read and verified (zero pages aren't persisted, hence not read)
+    pub pages_checked: usize,
+
+    /// The problems found (empty when the state is intact)
+    pub issues: Vec<FsckIssue>,
+}
+
+impl FsckReport {
+    /// Returns whether the state is intact
+    #[inline]
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Checks the integrity of state `state` of app `addr` (having `page_count` pages of `page_size` bytes):
+///
+/// * Loads the state entry and checks that its pages-hash hash (using `SH`, see `state::compute_state`) to `state`.
+/// * Re-reads each (non-zero) page and checks that its content hashes (using `PH`) to its listed page-hash.
+///
+/// * `kv`        - The pages key-value store.
+/// * `states_kv` - The states key-value store (may be `kv` itself).
+///
+/// Missing or corrupt data is reported under the returned `FsckReport`.
+/// An error is returned only when reading from the key-value stores has failed.
+pub fn check_app_state<KV, PH, SH>(
+    addr: &Address,
+    state: &State,
+    page_count: u16,
+    page_size: u32,
+    kv: &KV,
+    states_kv: &KV,
+) -> Result<FsckReport, KVError>
+where
+    KV: KVStore,
+    PH: PageHasher,
+    SH: StateHasher,
+{
+    debug!("checking state {:?} of app {:?}", state, addr);
+
+    let mut report = FsckReport {
+        addr: addr.clone(),
+        state: state.clone(),
+        pages_checked: 0,
+        issues: Vec::new(),
+    };
+
+    if *state == State::empty() {
+        // the initial state of an app (all pages are zero pages), it has no state entry
+        return Ok(report);
+    }
+
+    let pages_hash = match load_pages_hash::<KV, PH>(addr, state, page_count, page_size, states_kv)?
+    {
+        Ok(pages_hash) => pages_hash,
+        Err(issue) => {
+            error!("app {:?} state {:?}: {}", addr, state, issue);
+            report.issues.push(issue);
+
+            return Ok(report);
+        }
+    };
+
+    let actual = state::compute_state::<SH>(&pages_hash, page_size);
+
+    if actual != *state {
+        report.issues.push(FsckIssue::StateMismatch { actual });
+    }
+
+    let zero_page = page::zero_page(page_size);
+
+    for (i, ph) in pages_hash.iter().enumerate() {
+        let page_idx = PageIndex(i as u16);
+
+        if *ph == PH::hash(addr.clone(), page_idx, &zero_page) {
+            // zero pages aren't persisted
+            continue;
+        }
+
+        report.pages_checked += 1;
+
+        let issue = match kv.get(&ph.0)? {
+            None => Some(FsckIssue::MissingPage { page_idx, ph: *ph }),
+            Some(data) => check_page::<PH>(addr, page_idx, ph, &data, page_size).err(),
+        };
+
+        report.issues.extend(issue);
+    }
+
+    for issue in report.issues.iter() {
+        error!("app {:?} state {:?}: {}", addr, state, issue);
+    }
+
+    Ok(report)
+}
+
+/// Loads the pages-hash of `state` (including the zero pages omitted from the state entry).
+/// The inner error is the reason `state` has no valid state entry.
+fn load_pages_hash<KV, PH>(
+    addr: &Address,
+    state: &State,
+    page_count: u16,
+    page_size: u32,
+    states_kv: &KV,
+) -> Result<Result<Vec<PageHash>, FsckIssue>, KVError>
+where
+    KV: KVStore,
+    PH: PageHasher,
+{
+    let entry = match states_kv.get(state.as_slice())? {
+        Some(entry) => entry,
+        None => return Ok(Err(FsckIssue::MissingState)),
+    };
+
+    match state::expand_state_entry::<PH>(addr, &entry, page_count, page_size) {
+        Ok(pages_hash) => Ok(Ok(pages_hash)),
+        Err(e) => Ok(Err(FsckIssue::InvalidStateEntry(format!("{}", e)))),
+    }
+}
+
+fn check_page<PH: PageHasher>(
+    addr: &Address,
+    page_idx: PageIndex,
+    ph: &PageHash,
+    data: &[u8],
+    page_size: u32,
+) -> Result<(), FsckIssue> {
+    let corrupt = |reason: String| FsckIssue::CorruptPage {
+        page_idx,
+        ph: *ph,
+        reason,
+    };
+
+    let page = codec::decode_page(data, page_size).or_else(|e| Err(corrupt(format!("{}", e))))?;
+
+    if PH::hash(addr.clone(), page_idx, &page) != *ph {
+        return Err(corrupt(
+            "page content doesn't match its page-hash".to_string(),
+        ));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,287 @@
+use crate::page::{PageHash, PAGE_HASH_LEN};
+use crate::state;
+
+use svm_common::{Address, State};
+use svm_kv::{
+    traits::{KVEntry, KVStore},
+    KVError,
+};
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use log::debug;
+
+/// Prefix of a page reference entry key (stored under the pages key-value store).
+/// Each state referencing a (non-zero) page has its own entry:
+/// `page-refs:page_hash || state ---> []`
+///
+/// That way committing a state only adds entries (no read is required),
+/// and a page is unreferenced once it has no reference entries left.
+const PAGE_REFS_PREFIX: &[u8] = b"page-refs:";
+
+/// Prefix of an app states history entry key (stored under the states key-value store).
+/// Each committed state has its own entry, keyed by the commit sequence number (big-endian):
+/// `app-states:app_addr || seq ---> state`
+///
+/// That way committing a state only adds an entry (see `push_app_state`).
+/// Re-committing an existing state adds another entry, the earlier entries of that state
+/// are ignored by `app_states` (and deleted by `prune_app`).
+const APP_STATES_PREFIX: &[u8] = b"app-states:";
+
+/// Prefix of an app states history head key (stored under the states key-value store):
+/// `app-states-head:app_addr ---> next_seq`
+const APP_STATES_HEAD_PREFIX: &[u8] = b"app-states-head:";
+
+/// An app states history entry key, along with its state.
+type HistoryEntry = (Vec<u8>, State);
+
+/// Determines which app states survive pruning (see `prune_app`).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Retain {
+    /// Keeps the most recently committed `K` states
+    Last(usize),
+
+    /// Keeps the given (live) states. The app current (most recently committed) state is always kept.
+    States(Vec<State>),
+}
+
+/// The outcome of pruning an app.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PruneStats {
+    /// Number of deleted state entries
+    pub states_deleted: usize,
+
+    /// Number of deleted pages (pages no longer referenced by any state)
+    pub pages_deleted: usize,
+}
+
+/// Returns the states of app `addr` (as committed by `AppPages::commit`), the oldest first.
+/// A re-committed state appears once (as of its most recent commit).
+pub fn app_states<KV: KVStore>(states_kv: &KV, addr: &Address) -> Result<Vec<State>, KVError> {
+    let (history, _stale_keys) = app_states_history(states_kv, addr)?;
+
+    Ok(history.into_iter().map(|(_key, state)| state).collect())
+}
+
+/// Returns the entries to store for appending `state` to app `addr` states history
+/// (the new history entry and the updated history head).
+///
+/// Only the history head is read, so the cost doesn't depend on the history length.
+pub(crate) fn push_app_state<KV: KVStore>(
+    states_kv: &KV,
+    addr: &Address,
+    state: &State,
+) -> Result<Vec<KVEntry>, KVError> {
+    let head_key = app_states_head_key(addr);
+
+    let seq = match states_kv.get(&head_key)? {
+        Some(v) => decode_seq(&v)?,
+        None => 0,
+    };
+
+    let entry = (app_state_key(addr, seq), state.bytes().to_vec());
+    let head = (head_key, (seq + 1).to_be_bytes().to_vec());
+
+    Ok(vec![entry, head])
+}
+
+/// Deletes the states of app `addr` that aren't retained by `retain`,
+/// and the pages that are no longer referenced by any state.
+///
+/// * `kv`        - The pages key-value store.
+/// * `states_kv` - The states key-value store (may be `kv` itself).
+///
+/// The deleted state entries are removed first. So a crash in the middle may leak
+/// unreferenced pages, but a state will never point to a deleted page.
+///
+/// Only the app pages and states are collected. The `GlobalState` tree nodes are never deleted,
+/// since they are shared between the global-state roots (see `GlobalState`).
+pub fn prune_app<KV: KVStore>(
+    addr: &Address,
+    kv: &Rc<RefCell<KV>>,
+    states_kv: &Rc<RefCell<KV>>,
+    retain: &Retain,
+) -> Result<PruneStats, KVError> {
+    let (history, stale_keys) = app_states_history(&*states_kv.borrow(), addr)?;
+
+    let current = history.last().map(|(_key, s)| s.clone());
+
+    let (kept, deleted): (Vec<HistoryEntry>, Vec<HistoryEntry>) = match retain {
+        Retain::Last(k) => {
+            let split = history.len().saturating_sub(*k);
+            let (deleted, kept) = history.split_at(split);
+
+            (kept.to_vec(), deleted.to_vec())
+        }
+        Retain::States(live) => history
+            .into_iter()
+            .partition(|(_key, s)| live.contains(s) || Some(s) == current.as_ref()),
+    };
+
+    debug!(
+        "pruning app {:?}: keeping {} states, deleting {} states",
+        addr,
+        kept.len(),
+        deleted.len()
+    );
+
+    if deleted.is_empty() && stale_keys.is_empty() {
+        return Ok(PruneStats::default());
+    }
+
+    // 1) Deleting the states entries (and their app states history entries)
+    let mut refs_keys = Vec::new();
+
+    for (_key, state) in deleted.iter() {
+        if let Some(v) = states_kv.borrow().get(state.as_slice())? {
+            let (listed, _zero_tail) = state::decode_state_entry(&v)?;
+
+            refs_keys.extend(listed.iter().map(|ph| (*ph, page_ref_key(ph, state))));
+        }
+    }
+
+    // the superseded history entries (of both the kept and the deleted states) are deleted as well
+    let mut state_keys: Vec<&[u8]> = Vec::with_capacity(2 * deleted.len() + stale_keys.len());
+
+    for (history_key, state) in deleted.iter() {
+        state_keys.push(state.as_slice());
+        state_keys.push(&history_key[..]);
+    }
+    state_keys.extend(stale_keys.iter().map(|k| &k[..]));
+
+    states_kv.borrow_mut().delete(&state_keys)?;
+
+    // 2) Deleting the deleted states references. Deleting the pages left unreferenced.
+    // A listed page having no reference entry is a zero page (zero pages aren't persisted).
+    let keys: Vec<&[u8]> = refs_keys.iter().map(|(_ph, k)| &k[..]).collect();
+    let found = kv.borrow().get_many(&keys)?;
+
+    kv.borrow_mut().delete(&keys)?;
+
+    let mut candidates: Vec<PageHash> = refs_keys
+        .iter()
+        .zip(found)
+        .filter(|(_, v)| v.is_some())
+        .map(|((ph, _k), _v)| *ph)
+        .collect();
+    candidates.sort_by_key(|ph| ph.0);
+    candidates.dedup();
+
+    let mut delete_keys: Vec<&[u8]> = Vec::new();
+
+    {
+        let kv = kv.borrow();
+
+        for ph in candidates.iter() {
+            if kv.iter_prefix(&page_refs_prefix(ph))?.is_empty() {
+                delete_keys.push(&ph.0);
+            }
+        }
+    }
+
+    let pages_deleted = delete_keys.len();
+
+    kv.borrow_mut().delete(&delete_keys)?;
+
+    Ok(PruneStats {
+        states_deleted: deleted.len(),
+        pages_deleted,
+    })
+}
+
+/// The prefix of page `ph` reference entries.
+fn page_refs_prefix(ph: &PageHash) -> Vec<u8> {
+    let mut prefix = Vec::with_capacity(PAGE_REFS_PREFIX.len() + PAGE_HASH_LEN);
+
+    prefix.extend_from_slice(PAGE_REFS_PREFIX);
+    prefix.extend_from_slice(&ph.0);
+
+    prefix
+}
+
+/// The key of the entry recording that `state` references page `ph`.
+pub(crate) fn page_ref_key(ph: &PageHash, state: &State) -> Vec<u8> {
+    let mut key = page_refs_prefix(ph);
+
+    key.extend_from_slice(state.as_slice());
+
+    key
+}
+
+/// Returns app `addr` states history entries (the oldest first, each state as of its most recent commit),
+/// and the keys of the history entries superseded by a later commit of the same state.
+fn app_states_history<KV: KVStore>(
+    states_kv: &KV,
+    addr: &Address,
+) -> Result<(Vec<HistoryEntry>, Vec<Vec<u8>>), KVError> {
+    let entries = states_kv.iter_prefix(&app_states_prefix(addr))?;
+
+    let mut history = Vec::with_capacity(entries.len());
+    let mut stale_keys = Vec::new();
+    let mut seen = HashSet::new();
+
+    // the entries are sorted by their sequence number, so the most recent entry of a state is visited first
+    for (key, v) in entries.into_iter().rev() {
+        if v.len() != State::len() {
+            return Err(KVError::DataCorruption(format!(
+                "invalid app states history entry length: {}",
+                v.len()
+            )));
+        }
+
+        if seen.insert(v.clone()) {
+            history.push((key, State::from(&v[..])));
+        } else {
+            stale_keys.push(key);
+        }
+    }
+
+    history.reverse();
+
+    Ok((history, stale_keys))
+}
+
+/// The prefix of app `addr` states history entries.
+fn app_states_prefix(addr: &Address) -> Vec<u8> {
+    let mut prefix = Vec::with_capacity(APP_STATES_PREFIX.len() + Address::len());
+
+    prefix.extend_from_slice(APP_STATES_PREFIX);
+    prefix.extend_from_slice(addr.as_slice());
+
+    prefix
+}
+
+/// The key of app `addr` states history entry #`seq`.
+fn app_state_key(addr: &Address, seq: u64) -> Vec<u8> {
+    let mut key = app_states_prefix(addr);
+
+    key.extend_from_slice(&seq.to_be_bytes());
+
+    key
+}
+
+/// The key of app `addr` states history head (holding the next sequence number).
+fn app_states_head_key(addr: &Address) -> Vec<u8> {
+    let mut key = Vec::with_capacity(APP_STATES_HEAD_PREFIX.len() + Address::len());
+
+    key.extend_from_slice(APP_STATES_HEAD_PREFIX);
+    key.extend_from_slice(addr.as_slice());
+
+    key
+}
+
+fn decode_seq(v: &[u8]) -> Result<u64, KVError> {
+    if v.len() != 8 {
+        return Err(KVError::DataCorruption(format!(
+            "invalid app states history head length: {}",
+            v.len()
+        )));
+    }
+
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(v);
+
+    Ok(u64::from_be_bytes(bytes))
+}
@@ -0,0 +1,392 @@
+use crate::proof::{AppStateProof, ProofError};
+use crate::traits::GlobalStateStorage;
+
+use svm_common::{Address, KeyHasher, State};
+use svm_kv::{traits::KVStore, KVError};
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+use log::debug;
+
+/// The depth of the global state tree (one level per `Address` bit, an `Address` is 20 bytes).
+pub(crate) const TREE_DEPTH: usize = 20 * 8;
+
+/// Leaves and inner nodes are hashed with a different prefix.
+/// That way a leaf can't be interpreted as an inner node (and vice-versa).
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+/// The key under which the most recently committed root is stored.
+const LATEST_ROOT_KEY: &[u8] = b"global-state-latest-root";
+
+/// `GlobalState` maintains a Merkle map `app Address -> app State` over all apps.
+/// Its root is a single commitment to the current state of every app.
+///
+/// The map is a sparse Merkle tree of depth 160 (each `Address` bit determines a direction).
+/// Only non-empty nodes are persisted under the key-value store:
+///
+/// ```text
+/// leaf_hash = HASH(0 || app_addr || app_state)    ---> app_addr || app_state
+/// node_hash = HASH(1 || left_hash || right_hash)  ---> left_hash || right_hash
+/// ```
+///
+/// An empty leaf is `000...0` and an empty node is `HASH(1 || empty_child || empty_child)`.
+///
+/// Nodes are never deleted (`gc::prune_app` collects only app pages and states).
+/// A node is shared by every root whose tree contains it, and proofs against past roots depend on it.
+/// Collecting nodes would require reference-counting them per root.
+///
+/// `KV` - stands for `KVStore`
+/// `KH` - stands for `KeyHasher`
+pub struct GlobalState<KV, KH>
+where
+    KV: KVStore,
+    KH: KeyHasher<Hash = [u8; 32]>,
+{
+    kv: Rc<RefCell<KV>>,
+    root: [u8; 32],
+    pending: HashMap<Address, State>,
+    empty_hashes: Vec<[u8; 32]>,
+    marker: PhantomData<KH>,
+}
+
+impl<KV, KH> GlobalState<KV, KH>
+where
+    KV: KVStore,
+    KH: KeyHasher<Hash = [u8; 32]>,
+{
+    /// Opens the global-state having root `root` under key-value store `kv`.
+    /// `State::empty()` stands for a global-state with no apps.
+    pub fn new(kv: Rc<RefCell<KV>>, root: State) -> Self {
+        Self {
+            kv,
+            root: normalize_root::<KH>(&root),
+            pending: HashMap::new(),
+            empty_hashes: empty_hashes::<KH>(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Opens the most recently committed global-state under `kv`.
+    /// Returns an empty global-state when nothing has been committed yet.
+    pub fn open_latest(kv: Rc<RefCell<KV>>) -> Result<Self, KVError> {
+        let root = latest_root(&*kv.borrow())?;
+
+        Ok(Self::new(kv, root))
+    }
+
+    fn load_node(&self, depth: usize, node: &[u8; 32]) -> Result<([u8; 32], [u8; 32]), KVError> {
+        if *node == self.empty_hashes[depth] {
+            let empty_child = self.empty_hashes[depth + 1];
+
+            return Ok((empty_child, empty_child));
+        }
+
+        match self.kv.borrow().get(node)? {
+            Some(ref v) if v.len() == 64 => {
+                let mut left = [0; 32];
+                let mut right = [0; 32];
+                left.copy_from_slice(&v[0..32]);
+                right.copy_from_slice(&v[32..64]);
+
+                Ok((left, right))
+            }
+            _ => Err(KVError::DataCorruption(format!(
+                "Didn't find global-state node: {:?}",
+                node
+            ))),
+        }
+    }
+
+    fn load_leaf(&self, leaf: &[u8; 32]) -> Result<Option<(Address, State)>, KVError> {
+        if *leaf == self.empty_hashes[TREE_DEPTH] {
+            return Ok(None);
+        }
+
+        match self.kv.borrow().get(leaf)? {
+            Some(ref v) if v.len() == Address::len() + State::len() => {
+                let (addr, state) = v.split_at(Address::len());
+
+                Ok(Some((Address::from(addr), State::from(state))))
+            }
+            _ => Err(KVError::DataCorruption(format!(
+                "Didn't find global-state leaf: {:?}",
+                leaf
+            ))),
+        }
+    }
+
+    /// Walks from the root down to `addr` leaf. Returns the leaf and the siblings along the path.
+    fn walk(&self, addr: &Address) -> Result<([u8; 32], Vec<[u8; 32]>), KVError> {
+        let mut node = self.root;
+        let mut siblings = Vec::with_capacity(TREE_DEPTH);
+
+        for depth in 0..TREE_DEPTH {
+            if node == self.empty_hashes[depth] {
+                // the remaining path is empty
+                siblings.extend_from_slice(&self.empty_hashes[depth + 1..]);
+
+                return Ok((self.empty_hashes[TREE_DEPTH], siblings));
+            }
+
+            let (left, right) = self.load_node(depth, &node)?;
+
+            if addr_bit(addr, depth) {
+                siblings.push(left);
+                node = right;
+            } else {
+                siblings.push(right);
+                node = left;
+            }
+        }
+
+        Ok((node, siblings))
+    }
+
+    /// Applies `updates` (sorted by address) to the subtree `node` at depth `depth`.
+    /// The new non-empty nodes are appended to `changes`.
+    fn update_subtree(
+        &self,
+        depth: usize,
+        node: [u8; 32],
+        updates: &[(&Address, &State)],
+        changes: &mut Vec<([u8; 32], Vec<u8>)>,
+    ) -> Result<[u8; 32], KVError> {
+        if updates.is_empty() {
+            return Ok(node);
+        }
+
+        if depth == TREE_DEPTH {
+            // addresses are unique, hence there is exactly one update for a leaf
+            let (addr, state) = updates[0];
+
+            let mut value = Vec::with_capacity(Address::len() + State::len());
+            value.extend_from_slice(addr.as_slice());
+            value.extend_from_slice(state.as_slice());
+
+            let leaf = leaf_hash::<KH>(addr, state);
+            changes.push((leaf, value));
+
+            return Ok(leaf);
+        }
+
+        let (left, right) = self.load_node(depth, &node)?;
+
+        let split = updates
+            .iter()
+            .position(|(addr, _)| addr_bit(addr, depth))
+            .unwrap_or_else(|| updates.len());
+
+        let left = self.update_subtree(depth + 1, left, &updates[..split], changes)?;
+        let right = self.update_subtree(depth + 1, right, &updates[split..], changes)?;
+
+        let empty_child = self.empty_hashes[depth + 1];
+
+        if left == empty_child && right == empty_child {
+            return Ok(self.empty_hashes[depth]);
+        }
+
+        let mut value = Vec::with_capacity(64);
+        value.extend_from_slice(&left);
+        value.extend_from_slice(&right);
+
+        let node = node_hash::<KH>(&left, &right);
+        changes.push((node, value));
+
+        Ok(node)
+    }
+}
+
+impl<KV, KH> GlobalStateStorage for GlobalState<KV, KH>
+where
+    KV: KVStore,
+    KH: KeyHasher<Hash = [u8; 32]>,
+{
+    fn get_app_state(&self, addr: &Address) -> Result<Option<State>, KVError> {
+        if let Some(state) = self.pending.get(addr) {
+            return Ok(Some(state.clone()));
+        }
+
+        let (leaf, _siblings) = self.walk(addr)?;
+        let leaf = self.load_leaf(&leaf)?;
+
+        Ok(leaf.map(|(_addr, state)| state))
+    }
+
+    fn set_app_state(&mut self, addr: &Address, state: &State) {
+        self.pending.insert(addr.clone(), state.clone());
+    }
+
+    #[inline]
+    fn get_root(&self) -> State {
+        State::from(&self.root[..])
+    }
+
+    fn commit(&mut self) -> Result<State, KVError> {
+        debug!(
+            "committing {} app-states into the global-state",
+            self.pending.len()
+        );
+
+        // the pending app-states are dropped even when the commit fails.
+        // (otherwise, a later commit would include them)
+        let pending = std::mem::take(&mut self.pending);
+
+        let mut updates: Vec<(&Address, &State)> = pending.iter().collect();
+        updates.sort_by(|(a, _), (b, _)| a.as_slice().cmp(b.as_slice()));
+
+        let mut changes = Vec::new();
+        let root = self.update_subtree(0, self.root, &updates, &mut changes)?;
+
+        let mut entries: Vec<(&[u8], &[u8])> =
+            changes.iter().map(|(k, v)| (&k[..], &v[..])).collect();
+        entries.push((LATEST_ROOT_KEY, &root[..]));
+
+        self.kv.borrow_mut().store(&entries)?;
+
+        self.root = root;
+
+        Ok(self.get_root())
+    }
+
+    fn reload(&mut self) -> Result<(), KVError> {
+        self.pending.clear();
+
+        let root = latest_root(&*self.kv.borrow())?;
+
+        debug!("reloading global-state (latest root: {:?})", root);
+
+        self.root = normalize_root::<KH>(&root);
+
+        Ok(())
+    }
+
+    fn prove_app_state(&self, addr: &Address) -> Result<AppStateProof, ProofError> {
+        if !self.pending.is_empty() {
+            return Err(ProofError::PendingChanges);
+        }
+
+        let (leaf, siblings) = self.walk(addr)?;
+        let state = self.load_leaf(&leaf)?.map(|(_addr, state)| state);
+
+        let siblings = siblings
+            .iter()
+            .enumerate()
+            .map(|(i, sibling)| {
+                if *sibling == self.empty_hashes[i + 1] {
+                    None
+                } else {
+                    Some(*sibling)
+                }
+            })
+            .collect();
+
+        Ok(AppStateProof {
+            addr: addr.clone(),
+            state,
+            siblings,
+        })
+    }
+}
+
+/// Re-computes the global-state root out of `proof`.
+/// Returns `None` when the proof is malformed.
+pub(crate) fn root_from_proof<KH>(proof: &AppStateProof) -> Option<[u8; 32]>
+where
+    KH: KeyHasher<Hash = [u8; 32]>,
+{
+    if proof.siblings.len() != TREE_DEPTH {
+        return None;
+    }
+
+    let empty_hashes = empty_hashes::<KH>();
+
+    let mut node = match proof.state {
+        Some(ref state) => leaf_hash::<KH>(&proof.addr, state),
+        None => empty_hashes[TREE_DEPTH],
+    };
+
+    for depth in (0..TREE_DEPTH).rev() {
+        let empty_child = empty_hashes[depth + 1];
+        let sibling = proof.siblings[depth].unwrap_or(empty_child);
+
+        let (left, right) = if addr_bit(&proof.addr, depth) {
+            (sibling, node)
+        } else {
+            (node, sibling)
+        };
+
+        node = if left == empty_child && right == empty_child {
+            empty_hashes[depth]
+        } else {
+            node_hash::<KH>(&left, &right)
+        };
+    }
+
+    Some(node)
+}
+
+/// Returns the most recently committed root under `kv` (`State::empty()` when there is none).
+fn latest_root<KV: KVStore>(kv: &KV) -> Result<State, KVError> {
+    let root = match kv.get(LATEST_ROOT_KEY)? {
+        Some(root) => State::from(&root[..]),
+        None => State::empty(),
+    };
+
+    Ok(root)
+}
+
+/// Normalizes `root`. `State::empty()` stands for the root of an empty global-state.
+pub(crate) fn normalize_root<KH>(root: &State) -> [u8; 32]
+where
+    KH: KeyHasher<Hash = [u8; 32]>,
+{
+    if *root == State::empty() {
+        empty_hashes::<KH>()[0]
+    } else {
+        root.bytes()
+    }
+}
+
+/// Returns the `depth`-th bit of `addr` (most significant bit first). `true` means "go right".
+#[inline]
+fn addr_bit(addr: &Address, depth: usize) -> bool {
+    let byte = addr.as_slice()[depth / 8];
+
+    (byte >> (7 - depth % 8)) & 1 == 1
+}
+
+fn leaf_hash<KH: KeyHasher<Hash = [u8; 32]>>(addr: &Address, state: &State) -> [u8; 32] {
+    let mut data = Vec::with_capacity(1 + Address::len() + State::len());
+
+    data.push(LEAF_PREFIX);
+    data.extend_from_slice(addr.as_slice());
+    data.extend_from_slice(state.as_slice());
+
+    KH::hash(&data)
+}
+
+fn node_hash<KH: KeyHasher<Hash = [u8; 32]>>(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut data = Vec::with_capacity(1 + 2 * 32);
+
+    data.push(NODE_PREFIX);
+    data.extend_from_slice(left);
+    data.extend_from_slice(right);
+
+    KH::hash(&data)
+}
+
+/// The `ith` item is the hash of an empty subtree rooted at depth `i`.
+fn empty_hashes<KH: KeyHasher<Hash = [u8; 32]>>() -> Vec<[u8; 32]> {
+    let mut hashes = vec![[0; 32]; TREE_DEPTH + 1];
+
+    for depth in (0..TREE_DEPTH).rev() {
+        let child = hashes[depth + 1];
+        hashes[depth] = node_hash::<KH>(&child, &child);
+    }
+
+    hashes
+}
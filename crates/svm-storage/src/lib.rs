@@ -9,6 +9,8 @@ pub mod default;
 
 mod app_pages;
 mod app_storage;
+mod global_state;
+mod shared_page_cache;
 
 /// Contains definitions of `Page` related structures. For example: `Page, PageIndex` etc
 pub mod page;
@@ -16,8 +18,34 @@ pub mod page;
 /// Contains definitions `State`-related.
 pub mod state;
 
+/// Garbage collection. Pruning app old states and their no longer referenced pages.
+pub mod gc;
+
+/// Page codecs (encoding a page before being persisted under the key-value store).
+pub mod codec;
+
+/// State diffs (the pages, and the bytes within, changed between two app states).
+pub mod diff;
+
+/// Storage integrity checks (verifying app states and their pages against the hashers).
+pub mod fsck;
+
+/// Storage inclusion proofs (a page content under an app `State`, an app `State` under the global-state).
+pub mod proof;
+
+/// App storage snapshots (exporting an app storage at a `State` into a self-describing stream, and importing it back).
+pub mod snapshot;
+
+/// App storage key-value map (hashing keys into a dedicated region of the app pages).
+pub mod map;
+
 pub use crate::app_pages::AppPages;
-pub use crate::app_storage::AppStorage;
+pub use crate::app_storage::{AppStorage, Checkpoint};
+pub use crate::global_state::GlobalState;
+pub use crate::shared_page_cache::{PageCacheMetrics, SharedPageCache};
+
+/// Storage implementations backed by an overlay (buffering writes until being committed) on top of a kv-store
+pub mod overlay;
 
 /// Storage related traits
 pub mod traits;
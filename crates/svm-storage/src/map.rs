@@ -0,0 +1,272 @@
+use std::fmt;
+
+use crate::{
+    page::{PageIndex, PageOffset, PageSliceLayout},
+    AppStorage,
+};
+
+use svm_common::{DefaultKeyHasher, KeyHasher};
+
+use log::debug;
+
+/// The size (in bytes) of each slot of a storage map.
+///
+/// A slot is laid out as: `tag (1 byte) || key hash (32 bytes) || value length (1 byte) || value (zero-padded)`.
+/// Since page sizes are powers of two (of at least 256 bytes), a slot never spans pages.
+pub const MAP_SLOT_SIZE: u32 = 64;
+
+/// The maximum size (in bytes) of a storage map value.
+pub const MAP_MAX_VALUE_SIZE: usize = MAP_SLOT_SIZE as usize - 34;
+
+const SLOT_EMPTY: u8 = 0;
+const SLOT_OCCUPIED: u8 = 1;
+const SLOT_REMOVED: u8 = 2;
+
+/// The pages of an app storage dedicated to its key-value map.
+/// (the map pages are part of the app pages, so the map is part of the app `State`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MapRegion {
+    /// The first page of the region
+    pub first_page: PageIndex,
+
+    /// Number of pages of the region
+    pub page_count: u16,
+}
+
+impl MapRegion {
+    /// Whether page `page_idx` belongs to the region
+    #[inline]
+    pub fn contains(&self, page_idx: PageIndex) -> bool {
+        let first = self.first_page.0 as u32;
+        let page = page_idx.0 as u32;
+
+        page >= first && page < first + self.page_count as u32
+    }
+}
+
+/// Storage map errors
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapError {
+    EmptyValue,
+    ValueTooLarge(usize),
+    MapFull,
+    NoMapRegion,
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MapError::EmptyValue => write!(f, "storage map values can't be empty"),
+            MapError::ValueTooLarge(len) => write!(
+                f,
+                "storage map value of {} bytes exceeds the maximum of {} bytes",
+                len, MAP_MAX_VALUE_SIZE
+            ),
+            MapError::MapFull => write!(f, "storage map has no vacant slots"),
+            MapError::NoMapRegion => write!(f, "app storage has no map region"),
+        }
+    }
+}
+
+enum Probe {
+    Found(u32),
+    Vacant(u32),
+    Full,
+}
+
+/// A key-value map laid out over the `MapRegion` pages of an `AppStorage`.
+///
+/// Keys are hashed (using `DefaultKeyHasher`) into the region slots, and collisions are resolved
+/// by linear probing. Removed entries leave a tombstone, so the entries following them stay reachable.
+///
+/// The map reads and writes whole slots via the `AppStorage` page-slices cache. Hence its changes
+/// are persisted only on `AppStorage::commit` (and can be undone using `AppStorage` checkpoints).
+pub struct StorageMap<'a> {
+    storage: &'a mut AppStorage,
+    region: MapRegion,
+    slots_per_page: u32,
+}
+
+impl<'a> StorageMap<'a> {
+    /// Creates a map over the `region` pages of `storage`
+    pub fn new(storage: &'a mut AppStorage, region: MapRegion) -> Self {
+        assert!(
+            region.page_count > 0,
+            "a storage map requires at least one page"
+        );
+
+        let slots_per_page = storage.page_size() / MAP_SLOT_SIZE;
+
+        Self {
+            storage,
+            region,
+            slots_per_page,
+        }
+    }
+
+    /// The number of entries the map can hold
+    #[inline]
+    pub fn slot_count(&self) -> u32 {
+        self.region.page_count as u32 * self.slots_per_page
+    }
+
+    /// Returns the value of `key` (`None` when `key` has no value).
+    pub fn get(&mut self, key: &[u8]) -> Option<Vec<u8>> {
+        let key_hash = DefaultKeyHasher::hash(key);
+
+        match self.probe(&key_hash) {
+            Probe::Found(slot) => {
+                let data = self.read_slot(slot);
+                let len = data[33] as usize;
+
+                Some(data[34..34 + len].to_vec())
+            }
+            _ => None,
+        }
+    }
+
+    /// Sets the value of `key` to `value` (overriding its previous value, if any).
+    pub fn set(&mut self, key: &[u8], value: &[u8]) -> Result<(), MapError> {
+        if value.is_empty() {
+            return Err(MapError::EmptyValue);
+        }
+
+        if value.len() > MAP_MAX_VALUE_SIZE {
+            return Err(MapError::ValueTooLarge(value.len()));
+        }
+
+        let key_hash = DefaultKeyHasher::hash(key);
+
+        let slot = match self.probe(&key_hash) {
+            Probe::Found(slot) | Probe::Vacant(slot) => slot,
+            Probe::Full => return Err(MapError::MapFull),
+        };
+
+        debug!("storage map: setting slot #{}", slot);
+
+        let mut data = vec![0; MAP_SLOT_SIZE as usize];
+        data[0] = SLOT_OCCUPIED;
+        data[1..33].copy_from_slice(&key_hash);
+        data[33] = value.len() as u8;
+        data[34..34 + value.len()].copy_from_slice(value);
+
+        self.write_slot(slot, &data);
+
+        Ok(())
+    }
+
+    /// Removes the value of `key`. Returns whether `key` had a value.
+    pub fn remove(&mut self, key: &[u8]) -> bool {
+        let key_hash = DefaultKeyHasher::hash(key);
+
+        match self.probe(&key_hash) {
+            Probe::Found(slot) => {
+                debug!("storage map: removing slot #{}", slot);
+
+                let mut data = vec![0; MAP_SLOT_SIZE as usize];
+                data[0] = SLOT_REMOVED;
+
+                self.write_slot(slot, &data);
+
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Looks for the slot of `key_hash`, starting at its home slot.
+    /// When there is none, returns the slot `key_hash` should be inserted to (the first tombstone, if any, along the way).
+    fn probe(&mut self, key_hash: &[u8; 32]) -> Probe {
+        let slot_count = self.slot_count();
+
+        let mut home = [0; 4];
+        home.copy_from_slice(&key_hash[0..4]);
+        let home = u32::from_be_bytes(home) % slot_count;
+
+        let mut removed = None;
+
+        for i in 0..slot_count {
+            let slot = (home + i) % slot_count;
+            let data = self.read_slot(slot);
+
+            match data[0] {
+                SLOT_EMPTY => return Probe::Vacant(removed.unwrap_or(slot)),
+                SLOT_REMOVED => {
+                    if removed.is_none() {
+                        removed = Some(slot);
+                    }
+                }
+                _ => {
+                    if &data[1..33] == &key_hash[..] {
+                        return Probe::Found(slot);
+                    }
+                }
+            }
+        }
+
+        match removed {
+            Some(slot) => Probe::Vacant(slot),
+            None => Probe::Full,
+        }
+    }
+
+    fn read_slot(&mut self, slot: u32) -> Vec<u8> {
+        let layout = self.slot_layout(slot);
+
+        self.storage.read_page_slice(&layout)
+    }
+
+    fn write_slot(&mut self, slot: u32, data: &[u8]) {
+        let layout = self.slot_layout(slot);
+
+        self.storage.write_page_slice(&layout, data);
+    }
+
+    fn slot_layout(&self, slot: u32) -> PageSliceLayout {
+        let page = self.region.first_page.0 as u32 + slot / self.slots_per_page;
+        let offset = (slot % self.slots_per_page) * MAP_SLOT_SIZE;
+
+        PageSliceLayout::new(PageIndex(page as u16), PageOffset(offset), MAP_SLOT_SIZE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{default::DefaultPageCache, testing};
+
+    fn map_storage(page_count: u16, page_size: u32) -> AppStorage {
+        let (_addr, _kv, pages) =
+            testing::app_pages_init_with_page_size("my-app", page_count, page_size);
+
+        AppStorage::new(Box::new(DefaultPageCache::new(pages, page_count)))
+    }
+
+    #[test]
+    fn map_slots_are_laid_out_over_the_region_pages() {
+        let mut storage = map_storage(3, 256);
+
+        let region = MapRegion {
+            first_page: PageIndex(1),
+            page_count: 2,
+        };
+        let map = StorageMap::new(&mut storage, region);
+
+        assert_eq!(8, map.slot_count());
+
+        assert_eq!(
+            PageSliceLayout::new(PageIndex(1), PageOffset(0), MAP_SLOT_SIZE),
+            map.slot_layout(0)
+        );
+        assert_eq!(
+            PageSliceLayout::new(PageIndex(1), PageOffset(192), MAP_SLOT_SIZE),
+            map.slot_layout(3)
+        );
+        assert_eq!(
+            PageSliceLayout::new(PageIndex(2), PageOffset(64), MAP_SLOT_SIZE),
+            map.slot_layout(5)
+        );
+    }
+}
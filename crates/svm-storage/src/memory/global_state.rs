@@ -0,0 +1,7 @@
+use crate::global_state::GlobalState;
+
+use svm_common::DefaultKeyHasher;
+use svm_kv::memory::MemKVStore;
+
+/// A `GlobalState` implementation backed by `MemKVStore` kv-store.
+pub type MemGlobalState = GlobalState<MemKVStore, DefaultKeyHasher>;
@@ -1,5 +1,7 @@
 mod app_page_cache;
 mod app_pages;
+mod global_state;
 
 pub use app_page_cache::MemAppPageCache;
 pub use app_pages::MemAppPages;
+pub use global_state::MemGlobalState;
@@ -0,0 +1,5 @@
+use crate::default::DefaultPageCache;
+use crate::overlay::OverlayAppPages;
+
+/// `DefaultPageCache` implementation backed by `OverlayAppPages` pages-storage.
+pub type OverlayAppPageCache<KV> = DefaultPageCache<OverlayAppPages<KV>>;
@@ -0,0 +1,9 @@
+use crate::{
+    app_pages::AppPages,
+    default::{DefaultPageHasher, DefaultStateHasher},
+};
+
+use svm_kv::overlay::OverlayKV;
+
+/// A `AppPages` implementation backed by an `OverlayKV` on top of kv-store `KV`.
+pub type OverlayAppPages<KV> = AppPages<OverlayKV<KV>, DefaultPageHasher, DefaultStateHasher>;
@@ -0,0 +1,7 @@
+use crate::global_state::GlobalState;
+
+use svm_common::DefaultKeyHasher;
+use svm_kv::overlay::OverlayKV;
+
+/// A `GlobalState` implementation backed by an `OverlayKV` on top of kv-store `KV`.
+pub type OverlayGlobalState<KV> = GlobalState<OverlayKV<KV>, DefaultKeyHasher>;
@@ -0,0 +1,7 @@
+mod app_page_cache;
+mod app_pages;
+mod global_state;
+
+pub use app_page_cache::OverlayAppPageCache;
+pub use app_pages::OverlayAppPages;
+pub use global_state::OverlayGlobalState;
@@ -1,8 +1,24 @@
-/// A page is `4096 bytes`
-pub const PAGE_SIZE: u32 = 4_096;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+pub use svm_common::{is_valid_page_size, MAX_PAGE_SIZE, MIN_PAGE_SIZE};
+
+/// The default page size is `4096 bytes` (see `AppPages::new_with_page_size`)
+pub const PAGE_SIZE: u32 = svm_common::DEFAULT_PAGE_SIZE;
+
+/// A page size which isn't allowed (see `is_valid_page_size`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InvalidPageSize(pub u32);
+
+impl fmt::Display for InvalidPageSize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid page size: {}", self.0)
+    }
+}
 
 /// A `PageIndex` represents a page-index (non-negative integer)
-#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
 #[repr(transparent)]
 pub struct PageIndex(pub u16);
 
@@ -15,7 +31,7 @@ pub struct PageOffset(pub u32);
 pub const PAGE_HASH_LEN: usize = 32;
 
 /// A `PageHash` is a one-dimensional tuple of `([u8; PAGE_HASH_LEN])` representing hash of the page-content.
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PageHash(pub [u8; PAGE_HASH_LEN]);
 
 impl AsRef<[u8]> for PageHash {
@@ -63,10 +79,11 @@ pub struct PageSliceLayout {
 }
 
 impl PageSliceLayout {
-    /// New page-slice layout
+    /// New page-slice layout. The slice must reside within the page.
+    /// (a page is at most `MAX_PAGE_SIZE` bytes, the actual page size is checked upon reading and writing the slice).
     pub fn new(page_idx: PageIndex, offset: PageOffset, len: u32) -> Self {
-        assert!(offset.0 < PAGE_SIZE);
-        assert!(len < PAGE_SIZE);
+        assert!(offset.0 < MAX_PAGE_SIZE);
+        assert!(len <= MAX_PAGE_SIZE - offset.0);
 
         Self {
             page_idx,
@@ -92,18 +109,100 @@ impl PageSliceLayout {
     pub fn len(&self) -> u32 {
         self.len
     }
+
+    /// Returns whether the slice resides within a page of `page_size` bytes
+    #[inline]
+    pub fn fits_page(&self, page_size: u32) -> bool {
+        self.offset.0 as u64 + self.len as u64 <= page_size as u64
+    }
 }
 
-/// Allocates a new page (`Vec<u8>`) consisting of only of zeros
+/// Splits the storage slice of `len` bytes starting at the logical storage offset `offset`
+/// (i.e `page_idx * page_size + page_offset`) into consecutive page-slices (ordered by page index).
+///
+/// A slice residing within a single page is split into exactly one page-slice.
+/// An empty slice is split into no page-slices.
+pub fn slice_layouts(offset: u32, len: u32, page_size: u32) -> Vec<PageSliceLayout> {
+    let page_size = page_size as u64;
+    let end = offset as u64 + len as u64;
+
+    assert!(
+        end <= page_size * (u16::max_value() as u64 + 1),
+        "slice exceeds the storage bounds"
+    );
+
+    let mut layouts = Vec::new();
+    let mut start = offset as u64;
+
+    while start < end {
+        let page_idx = (start / page_size) as u16;
+        let page_offset = (start % page_size) as u32;
+        let slice_len = (end - start).min(page_size - page_offset as u64) as u32;
+
+        layouts.push(PageSliceLayout::new(
+            PageIndex(page_idx),
+            PageOffset(page_offset),
+            slice_len,
+        ));
+
+        start += slice_len as u64;
+    }
+
+    layouts
+}
+
+/// Allocates a new page (`Vec<u8>`) of `page_size` bytes consisting of only of zeros
 #[inline]
-pub fn zero_page() -> Vec<u8> {
-    vec![0; PAGE_SIZE as usize]
+pub fn zero_page(page_size: u32) -> Vec<u8> {
+    vec![0; page_size as usize]
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn layout(page_idx: u16, offset: u32, len: u32) -> PageSliceLayout {
+        PageSliceLayout::new(PageIndex(page_idx), PageOffset(offset), len)
+    }
+
+    #[test]
+    fn slice_layouts_within_a_page() {
+        assert_eq!(
+            vec![layout(1, 100, 3)],
+            slice_layouts(4096 + 100, 3, PAGE_SIZE)
+        );
+        assert_eq!(vec![layout(0, 0, 4096)], slice_layouts(0, 4096, PAGE_SIZE));
+        assert!(slice_layouts(100, 0, PAGE_SIZE).is_empty());
+    }
+
+    #[test]
+    fn slice_layouts_spanning_pages() {
+        let expected = vec![layout(0, 4090, 6), layout(1, 0, 4096), layout(2, 0, 4)];
+
+        assert_eq!(expected, slice_layouts(4090, 6 + 4096 + 4, PAGE_SIZE));
+
+        let expected = vec![layout(1, 200, 56), layout(2, 0, 44)];
+
+        assert_eq!(expected, slice_layouts(256 + 200, 100, 256));
+    }
+
+    #[test]
+    fn page_size_validity() {
+        assert!(is_valid_page_size(PAGE_SIZE));
+        assert!(is_valid_page_size(MIN_PAGE_SIZE));
+        assert!(is_valid_page_size(MAX_PAGE_SIZE));
+
+        assert!(!is_valid_page_size(128));
+        assert!(!is_valid_page_size(3000));
+        assert!(!is_valid_page_size(32_768));
+    }
+
+    #[test]
+    #[should_panic(expected = "slice exceeds the storage bounds")]
+    fn slice_layouts_exceeding_the_storage() {
+        slice_layouts(u32::max_value(), 2, PAGE_SIZE);
+    }
+
     #[test]
     #[should_panic(expected = "`PageHash::from` expects exactly 32 bytes input")]
     fn page_hash_expects_exactly_32_bytes_input() {
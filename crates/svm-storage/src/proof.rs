@@ -0,0 +1,148 @@
+use crate::global_state;
+use crate::page::{PageIndex, PageSliceLayout};
+use crate::state;
+use crate::traits::{MerkleStateHasher, PageHasher};
+
+use svm_common::{Address, KeyHasher, State};
+use svm_kv::KVError;
+
+use std::fmt;
+
+/// Producing a proof failure.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProofError {
+    /// The page index isn't within the app pages range
+    PageOutOfRange {
+        /// The requested page index
+        page_idx: PageIndex,
+
+        /// The number of pages of the app storage
+        page_count: u16,
+    },
+
+    /// There are uncommitted changes (dirty app pages or uncommitted app-states), so the proof would be against a stale root
+    PendingChanges,
+
+    /// The key-value store has failed
+    Store(KVError),
+}
+
+impl fmt::Display for ProofError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProofError::PageOutOfRange {
+                page_idx,
+                page_count,
+            } => write!(
+                f,
+                "page #{} is out of range (page count: {})",
+                page_idx.0, page_count
+            ),
+            ProofError::PendingChanges => write!(f, "can't prove while having uncommitted changes"),
+            ProofError::Store(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<KVError> for ProofError {
+    fn from(e: KVError) -> Self {
+        ProofError::Store(e)
+    }
+}
+
+/// A proof that page `page_idx` of an app storage has content `page` under some app `State`.
+/// (see `AppPages::prove_page`)
+///
+/// The verifier only needs the app `Address` and `State` (and the hashers in use).
+/// The proof consists of `O(log(page_count))` hashes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageProof {
+    /// The proven page index
+    pub page_idx: PageIndex,
+
+    /// The number of pages of the app storage
+    pub page_count: u16,
+
+    /// The size (in bytes) of each page of the app storage (it's part of the app `State`)
+    pub page_size: u32,
+
+    /// The page content
+    pub page: Vec<u8>,
+
+    /// The page Merkle path (siblings hashes, from the leaves up)
+    pub siblings: Vec<[u8; 32]>,
+}
+
+impl PageProof {
+    /// Returns whether the proof shows that page `self.page_idx` has content `self.page`
+    /// under app `addr` state `state`.
+    pub fn verify<PH, SH>(&self, addr: &Address, state: &State) -> bool
+    where
+        PH: PageHasher,
+        SH: MerkleStateHasher,
+    {
+        let ph = PH::hash(addr.clone(), self.page_idx, &self.page);
+
+        let root = SH::root_from_proof(&ph, self.page_idx, self.page_count, &self.siblings);
+
+        match root {
+            Some(root) => state::state_from_root::<SH>(root, self.page_size) == *state,
+            None => false,
+        }
+    }
+
+    /// Returns whether the proof shows that page-slice `layout` has content `slice`
+    /// under app `addr` state `state`.
+    pub fn verify_slice<PH, SH>(
+        &self,
+        addr: &Address,
+        state: &State,
+        layout: &PageSliceLayout,
+        slice: &[u8],
+    ) -> bool
+    where
+        PH: PageHasher,
+        SH: MerkleStateHasher,
+    {
+        if layout.page_index() != self.page_idx {
+            return false;
+        }
+
+        let start = layout.page_offset().0 as usize;
+        let end = start + layout.len() as usize;
+
+        match self.page.get(start..end) {
+            Some(actual) if actual == slice => self.verify::<PH, SH>(addr, state),
+            _ => false,
+        }
+    }
+}
+
+/// A proof that app `addr` has state `state` under a global-state root.
+/// (see `GlobalStateStorage::prove_app_state`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppStateProof {
+    /// The app address
+    pub addr: Address,
+
+    /// The app state (`None` for an app not under the global-state)
+    pub state: Option<State>,
+
+    /// The siblings hashes along the app leaf path, from the root down.
+    /// `None` stands for an empty subtree.
+    pub siblings: Vec<Option<[u8; 32]>>,
+}
+
+impl AppStateProof {
+    /// Returns whether the proof shows that app `self.addr` has state `self.state`
+    /// under global-state root `root`.
+    pub fn verify<KH>(&self, root: &State) -> bool
+    where
+        KH: KeyHasher<Hash = [u8; 32]>,
+    {
+        match global_state::root_from_proof::<KH>(self) {
+            Some(actual) => actual == global_state::normalize_root::<KH>(root),
+            None => false,
+        }
+    }
+}
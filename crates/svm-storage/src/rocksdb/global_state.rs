@@ -0,0 +1,7 @@
+use crate::global_state::GlobalState;
+
+use svm_common::DefaultKeyHasher;
+use svm_kv::rocksdb::Rocksdb;
+
+/// A `GlobalState` implementation backed by `Rocksdb` kv-store.
+pub type RocksdbGlobalState = GlobalState<Rocksdb, DefaultKeyHasher>;
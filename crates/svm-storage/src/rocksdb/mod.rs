@@ -1,5 +1,7 @@
 mod app_page_cache;
 mod app_pages;
+mod global_state;
 
 pub use app_page_cache::RocksdbAppPageCache;
 pub use app_pages::RocksdbAppPages;
+pub use global_state::RocksdbGlobalState;
@@ -0,0 +1,258 @@
+use crate::page::PageHash;
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use log::{debug, trace};
+
+/// Counters of a `SharedPageCache`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PageCacheMetrics {
+    /// Number of lookups found in the cache
+    pub hits: u64,
+
+    /// Number of lookups not found in the cache
+    pub misses: u64,
+
+    /// Number of pages evicted (for staying within the memory budget)
+    pub evictions: u64,
+
+    /// Number of cached pages
+    pub entries: usize,
+
+    /// Total size (in bytes) of the cached pages
+    pub used_bytes: usize,
+}
+
+struct CachedEntry {
+    page: Vec<u8>,
+    last_used: u64,
+}
+
+struct LruPages {
+    budget: usize,
+    entries: HashMap<PageHash, CachedEntry>,
+
+    /// `last_used` tick ---> page-hash. The first entry is the least recently used page.
+    lru: BTreeMap<u64, PageHash>,
+
+    tick: u64,
+    metrics: PageCacheMetrics,
+}
+
+/// A least-recently-used cache of `PageHash -> page bytes`, shared by many `AppPages` (see `AppPages::with_shared_cache`).
+///
+/// Unlike `DefaultPageCache` (living for a single app-transaction), a `SharedPageCache` lives
+/// as long as the process (runtime) does. Since pages are content-addressed (a page-hash is derived
+/// from the page content), a cached page never goes stale.
+///
+/// The total size of the cached pages never exceeds `budget` bytes.
+/// Cloning a `SharedPageCache` returns another handle to the same cache.
+/// The handles may be sent to (and used by) other threads.
+#[derive(Clone)]
+pub struct SharedPageCache {
+    inner: Arc<Mutex<LruPages>>,
+}
+
+impl SharedPageCache {
+    /// Creates a new empty cache, holding up to `budget` bytes of pages.
+    pub fn new(budget: usize) -> Self {
+        debug!("creating a shared page-cache (budget = {} bytes)", budget);
+
+        let inner = LruPages {
+            budget,
+            entries: HashMap::new(),
+            lru: BTreeMap::new(),
+            tick: 0,
+            metrics: PageCacheMetrics::default(),
+        };
+
+        Self {
+            inner: Arc::new(Mutex::new(inner)),
+        }
+    }
+
+    /// Locks the cache. A thread panicking while holding the lock can't leave a stale page behind
+    /// (pages are content-addressed), so a poisoned lock is still usable.
+    fn lock(&self) -> MutexGuard<'_, LruPages> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the page whose hash is `ph` (and marks it as the most recently used).
+    /// `None` is returned when the page isn't cached.
+    pub fn get(&self, ph: &PageHash) -> Option<Vec<u8>> {
+        let mut inner = self.lock();
+
+        inner.tick += 1;
+        let tick = inner.tick;
+
+        let LruPages {
+            entries,
+            lru,
+            metrics,
+            ..
+        } = &mut *inner;
+
+        match entries.get_mut(ph) {
+            Some(entry) => {
+                lru.remove(&entry.last_used);
+                lru.insert(tick, *ph);
+                entry.last_used = tick;
+
+                metrics.hits += 1;
+                trace!("shared page-cache hit: {:?}", ph);
+
+                Some(entry.page.clone())
+            }
+            None => {
+                metrics.misses += 1;
+                trace!("shared page-cache miss: {:?}", ph);
+
+                None
+            }
+        }
+    }
+
+    /// Caches `page` under `ph`. The least recently used pages are evicted when exceeding the budget.
+    /// A page larger than the whole budget isn't cached.
+    pub fn insert(&self, ph: PageHash, page: Vec<u8>) {
+        let mut inner = self.lock();
+
+        if page.len() > inner.budget {
+            return;
+        }
+
+        inner.tick += 1;
+        let tick = inner.tick;
+
+        let entry = CachedEntry {
+            page,
+            last_used: tick,
+        };
+
+        inner.metrics.used_bytes += entry.page.len();
+
+        if let Some(old) = inner.entries.insert(ph, entry) {
+            inner.lru.remove(&old.last_used);
+            inner.metrics.used_bytes -= old.page.len();
+        }
+
+        inner.lru.insert(tick, ph);
+
+        inner.evict();
+    }
+
+    /// Returns the cache counters.
+    pub fn metrics(&self) -> PageCacheMetrics {
+        let inner = self.lock();
+
+        PageCacheMetrics {
+            entries: inner.entries.len(),
+            ..inner.metrics.clone()
+        }
+    }
+
+    /// Removes all the cached pages (the counters are kept).
+    pub fn clear(&self) {
+        let mut inner = self.lock();
+
+        inner.entries.clear();
+        inner.lru.clear();
+        inner.metrics.used_bytes = 0;
+    }
+}
+
+impl LruPages {
+    fn evict(&mut self) {
+        while self.metrics.used_bytes > self.budget {
+            let (&tick, &ph) = self.lru.iter().next().unwrap();
+
+            self.lru.remove(&tick);
+
+            let entry = self.entries.remove(&ph).unwrap();
+            self.metrics.used_bytes -= entry.page.len();
+            self.metrics.evictions += 1;
+
+            trace!("shared page-cache evicted: {:?}", ph);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ph(n: u8) -> PageHash {
+        PageHash([n; 32])
+    }
+
+    #[test]
+    fn shared_page_cache_hits_and_misses() {
+        let cache = SharedPageCache::new(100);
+
+        assert_eq!(None, cache.get(&ph(1)));
+
+        cache.insert(ph(1), vec![10, 20, 30]);
+        assert_eq!(Some(vec![10, 20, 30]), cache.get(&ph(1)));
+
+        let metrics = cache.metrics();
+        assert_eq!(1, metrics.hits);
+        assert_eq!(1, metrics.misses);
+        assert_eq!(1, metrics.entries);
+        assert_eq!(3, metrics.used_bytes);
+    }
+
+    #[test]
+    fn shared_page_cache_evicts_least_recently_used() {
+        let cache = SharedPageCache::new(10);
+
+        cache.insert(ph(1), vec![1; 4]);
+        cache.insert(ph(2), vec![2; 4]);
+
+        // `ph(1)` is now the most recently used
+        assert!(cache.get(&ph(1)).is_some());
+
+        cache.insert(ph(3), vec![3; 4]);
+
+        assert!(cache.get(&ph(2)).is_none());
+        assert!(cache.get(&ph(1)).is_some());
+        assert!(cache.get(&ph(3)).is_some());
+
+        let metrics = cache.metrics();
+        assert_eq!(1, metrics.evictions);
+        assert_eq!(2, metrics.entries);
+        assert_eq!(8, metrics.used_bytes);
+    }
+
+    #[test]
+    fn shared_page_cache_shared_across_threads() {
+        let cache = SharedPageCache::new(100);
+
+        let handles: Vec<_> = (0..4)
+            .map(|n| {
+                let cache = cache.clone();
+
+                std::thread::spawn(move || cache.insert(ph(n), vec![n; 4]))
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        for n in 0..4 {
+            assert_eq!(Some(vec![n; 4]), cache.get(&ph(n)));
+        }
+        assert_eq!(16, cache.metrics().used_bytes);
+    }
+
+    #[test]
+    fn shared_page_cache_skips_pages_larger_than_budget() {
+        let cache = SharedPageCache::new(2);
+
+        cache.insert(ph(1), vec![1; 3]);
+
+        assert!(cache.get(&ph(1)).is_none());
+        assert_eq!(0, cache.metrics().used_bytes);
+    }
+}
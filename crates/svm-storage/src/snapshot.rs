@@ -0,0 +1,398 @@
+use crate::app_pages::AppPages;
+use crate::codec::PageCodec;
+use crate::page::{self, PageHash, PageIndex};
+use crate::state;
+use crate::traits::{PageHasher, PagesStorage, StateAwarePagesStorage, StateHasher};
+
+use svm_common::{Address, State};
+use svm_kv::{traits::KVStore, KVError};
+
+use std::cell::RefCell;
+use std::fmt;
+use std::io::{Read, Write};
+use std::rc::Rc;
+
+use log::{debug, error};
+
+/// The first bytes of each snapshot
+pub const SNAPSHOT_MAGIC: &[u8; 4] = b"SVMS";
+
+/// The current snapshot format version
+pub const SNAPSHOT_VERSION: u8 = 1;
+
+/// Exporting or importing a snapshot failure.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnapshotError {
+    /// Reading or writing the snapshot has failed
+    Io(String),
+
+    /// The snapshot is malformed
+    InvalidFormat(String),
+
+    /// The snapshot has been produced using other hashers
+    HasherMismatch {
+        /// The snapshot `(page_hasher_id, state_hasher_id)`
+        snapshot: (u8, u8),
+
+        /// The expected `(page_hasher_id, state_hasher_id)`
+        expected: (u8, u8),
+    },
+
+    /// A page content doesn't hash to its listed page-hash
+    PageMismatch(PageIndex),
+
+    /// The pages-hash hash to `actual` (instead of the snapshot state)
+    StateMismatch {
+        /// The snapshot state
+        expected: State,
+
+        /// The state computed from the pages-hash
+        actual: State,
+    },
+
+    /// A non-zero page has no content under the exported storage
+    MissingPage(PageIndex),
+
+    /// The key-value store has failed
+    Store(KVError),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SnapshotError::Io(reason) => write!(f, "snapshot I/O failed: {}", reason),
+            SnapshotError::InvalidFormat(reason) => write!(f, "invalid snapshot: {}", reason),
+            SnapshotError::HasherMismatch { snapshot, expected } => write!(
+                f,
+                "snapshot hashers ids {:?} differ from the expected {:?}",
+                snapshot, expected
+            ),
+            SnapshotError::PageMismatch(page_idx) => write!(
+                f,
+                "page #{} content doesn't match its page-hash",
+                page_idx.0
+            ),
+            SnapshotError::StateMismatch { expected, actual } => write!(
+                f,
+                "snapshot pages hash to state {:?} (expected: {:?})",
+                actual, expected
+            ),
+            SnapshotError::MissingPage(page_idx) => write!(f, "page #{} is missing", page_idx.0),
+            SnapshotError::Store(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<KVError> for SnapshotError {
+    fn from(e: KVError) -> Self {
+        SnapshotError::Store(e)
+    }
+}
+
+/// The header of a snapshot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotHeader {
+    /// The app address
+    pub addr: Address,
+
+    /// The app state the snapshot has been taken at
+    pub state: State,
+
+    /// The app page count
+    pub page_count: u16,
+
+    /// The app page size (in bytes)
+    pub page_size: u32,
+
+    /// The `PageHasher::ID` the pages-hash have been computed with
+    pub page_hasher_id: u8,
+
+    /// The `StateHasher::ID` the state has been computed with
+    pub state_hasher_id: u8,
+}
+
+/// Streams the app storage `storage` (of app `addr`, having `page_count` pages) at its current state into `out`.
+/// The page size is the one of `storage`.
+///
+/// A snapshot is laid out as:
+///
+/// ```text
+/// magic (4 bytes) || version (1 byte)
+/// addr (20 bytes) || state (32 bytes) || page_count (u16) || page_size (u32) || page_hasher_id (u8) || state_hasher_id (u8)
+/// page_hash #0 || page_hash #1 || ... || page_hash #(page_count - 1)   (32 bytes each)
+/// non_zero_pages_count (u16)
+/// [page_idx (u16) || page_len (u32) || page content] for each non-zero page (ordered by page index)
+/// ```
+///
+/// (all integers are Big-Endian). Pages are written uncompressed, since the page-hash is computed over the raw content.
+///
+/// `storage` must have no uncommitted changes.
+pub fn export_snapshot<PH, SH, S, W>(
+    addr: &Address,
+    page_count: u16,
+    storage: &mut S,
+    out: &mut W,
+) -> Result<(), SnapshotError>
+where
+    PH: PageHasher,
+    SH: StateHasher,
+    S: StateAwarePagesStorage,
+    W: Write,
+{
+    let state = storage.get_state();
+    let page_size = storage.page_size();
+
+    debug!("exporting snapshot of app {:?} at state {:?}", addr, state);
+
+    let pages_hash: Vec<PageHash> = (0..page_count)
+        .map(|page_idx| storage.get_page_hash(PageIndex(page_idx)))
+        .collect();
+
+    ensure_state::<SH>(&state, &pages_hash, page_size)?;
+
+    let non_zero: Vec<PageIndex> = (0..page_count)
+        .map(PageIndex)
+        .filter(|page_idx| !storage.is_zero_page(*page_idx))
+        .collect();
+
+    let mut header = Vec::new();
+    header.extend_from_slice(SNAPSHOT_MAGIC);
+    header.push(SNAPSHOT_VERSION);
+    header.extend_from_slice(addr.as_slice());
+    header.extend_from_slice(state.as_slice());
+    header.extend_from_slice(&page_count.to_be_bytes());
+    header.extend_from_slice(&page_size.to_be_bytes());
+    header.push(PH::ID);
+    header.push(SH::ID);
+
+    for ph in pages_hash.iter() {
+        header.extend_from_slice(&ph.0);
+    }
+
+    header.extend_from_slice(&(non_zero.len() as u16).to_be_bytes());
+
+    write_all(out, &header)?;
+
+    for page_idx in non_zero {
+        let page = match storage.read_page(page_idx) {
+            Some(page) => page,
+            None => return Err(SnapshotError::MissingPage(page_idx)),
+        };
+
+        write_all(out, &page_idx.0.to_be_bytes())?;
+        write_all(out, &(page.len() as u32).to_be_bytes())?;
+        write_all(out, &page)?;
+    }
+
+    out.flush()
+        .or_else(|e| Err(SnapshotError::Io(format!("{}", e))))
+}
+
+/// Reads a snapshot (see `export_snapshot`) from `input` and commits it as a new state of the snapshot app.
+///
+/// The whole snapshot is verified before anything is committed:
+/// the hashers ids must be `PH::ID` and `SH::ID`, each page content must hash to its listed page-hash,
+/// and the pages-hash must hash to the snapshot state.
+///
+/// The pages (encoded using `codec`) and the state entry are persisted under `kv`
+/// (exactly as `AppPages::commit` does). Returns the snapshot header.
+pub fn import_snapshot<KV, PH, SH, R>(
+    input: &mut R,
+    kv: &Rc<RefCell<KV>>,
+    codec: PageCodec,
+) -> Result<SnapshotHeader, SnapshotError>
+where
+    KV: KVStore,
+    PH: PageHasher,
+    SH: StateHasher,
+    R: Read,
+{
+    let header = read_header(input)?;
+
+    debug!(
+        "importing snapshot of app {:?} at state {:?}",
+        header.addr, header.state
+    );
+
+    let expected = (PH::ID, SH::ID);
+    let snapshot = (header.page_hasher_id, header.state_hasher_id);
+
+    if snapshot != expected {
+        return Err(SnapshotError::HasherMismatch { snapshot, expected });
+    }
+
+    let page_count = header.page_count as usize;
+
+    let mut pages_hash = Vec::with_capacity(page_count);
+
+    for _ in 0..page_count {
+        let mut ph = [0; 32];
+        read_exact(input, &mut ph)?;
+
+        pages_hash.push(PageHash(ph));
+    }
+
+    ensure_state::<SH>(&header.state, &pages_hash, header.page_size)?;
+
+    let zero_page = page::zero_page(header.page_size);
+    let mut pages: Vec<Option<Vec<u8>>> = vec![None; page_count];
+
+    let non_zero_count = read_u16(input)?;
+
+    for _ in 0..non_zero_count {
+        let page_idx = read_u16(input)?;
+        let page_len = read_u32(input)? as usize;
+
+        if page_idx as usize >= page_count {
+            return Err(invalid(format!("page index out of range: {}", page_idx)));
+        }
+
+        if page_len > header.page_size as usize {
+            return Err(invalid(format!("page #{} is too long", page_idx)));
+        }
+
+        if pages[page_idx as usize].is_some() {
+            return Err(invalid(format!("page #{} is duplicated", page_idx)));
+        }
+
+        let mut page = vec![0; page_len];
+        read_exact(input, &mut page)?;
+
+        pages[page_idx as usize] = Some(page);
+    }
+
+    for (i, (ph, page)) in pages_hash.iter().zip(pages.iter()).enumerate() {
+        let page_idx = PageIndex(i as u16);
+        let content = page.as_ref().unwrap_or(&zero_page);
+
+        if PH::hash(header.addr.clone(), page_idx, content) != *ph {
+            error!("snapshot page #{} doesn't match its page-hash", i);
+
+            return Err(SnapshotError::PageMismatch(page_idx));
+        }
+    }
+
+    if header.state == State::empty() {
+        if pages.iter().any(Option::is_some) {
+            return Err(invalid("the initial state has non-zero pages".to_string()));
+        }
+
+        // there is nothing to commit
+        return Ok(header);
+    }
+
+    // the snapshot is valid. now it's committed on top of the app initial state (all zero pages)
+    let mut app_pages = AppPages::<KV, PH, SH>::new_with_page_size(
+        header.addr.clone(),
+        Rc::clone(kv),
+        State::empty(),
+        header.page_count,
+        header.page_size,
+    )
+    .or_else(|e| Err(invalid(format!("{}", e))))?
+    .with_codec(codec);
+
+    for (i, page) in pages.iter().enumerate() {
+        if let Some(page) = page {
+            app_pages.write_page(PageIndex(i as u16), page);
+        }
+    }
+
+    app_pages.commit()?;
+
+    debug_assert_eq!(header.state, app_pages.get_state());
+
+    Ok(header)
+}
+
+fn read_header<R: Read>(input: &mut R) -> Result<SnapshotHeader, SnapshotError> {
+    let mut magic = [0; 4];
+    read_exact(input, &mut magic)?;
+
+    if magic != *SNAPSHOT_MAGIC {
+        return Err(invalid("bad magic".to_string()));
+    }
+
+    let mut version = [0; 1];
+    read_exact(input, &mut version)?;
+
+    if version[0] != SNAPSHOT_VERSION {
+        return Err(invalid(format!("unsupported version: {}", version[0])));
+    }
+
+    let mut addr = [0; 20];
+    read_exact(input, &mut addr)?;
+
+    let mut state = [0; 32];
+    read_exact(input, &mut state)?;
+
+    let page_count = read_u16(input)?;
+    let page_size = read_u32(input)?;
+
+    if !page::is_valid_page_size(page_size) {
+        return Err(invalid(format!("invalid page size: {}", page_size)));
+    }
+
+    let mut hashers = [0; 2];
+    read_exact(input, &mut hashers)?;
+
+    Ok(SnapshotHeader {
+        addr: Address::from(&addr[..]),
+        state: State::from(&state[..]),
+        page_count,
+        page_size,
+        page_hasher_id: hashers[0],
+        state_hasher_id: hashers[1],
+    })
+}
+
+fn ensure_state<SH: StateHasher>(
+    expected: &State,
+    pages_hash: &[PageHash],
+    page_size: u32,
+) -> Result<(), SnapshotError> {
+    if *expected == State::empty() {
+        // the app initial state (it has no state entry). its pages are verified to be zero pages
+        return Ok(());
+    }
+
+    let actual = state::compute_state::<SH>(pages_hash, page_size);
+
+    if actual != *expected {
+        return Err(SnapshotError::StateMismatch {
+            expected: expected.clone(),
+            actual,
+        });
+    }
+
+    Ok(())
+}
+
+fn read_u16<R: Read>(input: &mut R) -> Result<u16, SnapshotError> {
+    let mut buf = [0; 2];
+    read_exact(input, &mut buf)?;
+
+    Ok(u16::from_be_bytes(buf))
+}
+
+fn read_u32<R: Read>(input: &mut R) -> Result<u32, SnapshotError> {
+    let mut buf = [0; 4];
+    read_exact(input, &mut buf)?;
+
+    Ok(u32::from_be_bytes(buf))
+}
+
+fn read_exact<R: Read>(input: &mut R, buf: &mut [u8]) -> Result<(), SnapshotError> {
+    input
+        .read_exact(buf)
+        .or_else(|e| Err(SnapshotError::Io(format!("{}", e))))
+}
+
+fn write_all<W: Write>(out: &mut W, buf: &[u8]) -> Result<(), SnapshotError> {
+    out.write_all(buf)
+        .or_else(|e| Err(SnapshotError::Io(format!("{}", e))))
+}
+
+fn invalid(reason: String) -> SnapshotError {
+    SnapshotError::InvalidFormat(reason)
+}
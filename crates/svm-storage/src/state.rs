@@ -1,3 +1,9 @@
+use crate::page::{self, PageHash, PageIndex, PAGE_HASH_LEN, PAGE_SIZE};
+use crate::traits::{PageHasher, StateHasher};
+
+use svm_common::{Address, State};
+use svm_kv::KVError;
+
 /// An App's state Hash length is 32 bytes.
 pub const STATE_HASH_LEN: usize = 32;
 
@@ -10,3 +16,186 @@ impl AsRef<[u8]> for StateHash {
         self.0.as_ref()
     }
 }
+
+/// Computes the `State` of an app storage having pages-hash `pages_hash` and pages of `page_size` bytes.
+///
+/// The page size is part of the state. A non-default page size (see `PAGE_SIZE`) is mixed into the state as:
+///
+/// ```text
+/// State = SH::hash([SH::hash(pages_hash), page_size_hash])
+/// ```
+///
+/// where `page_size_hash` is the page size (Big-Endian) padded with zeros.
+/// An app storage of the default page size has `State = SH::hash(pages_hash)`.
+pub fn compute_state<SH: StateHasher>(pages_hash: &[PageHash], page_size: u32) -> State {
+    state_from_root::<SH>(SH::hash(pages_hash), page_size)
+}
+
+/// Computes the `State` given `root`, the state-hash of the pages-hash (see `compute_state`).
+pub(crate) fn state_from_root<SH: StateHasher>(root: StateHash, page_size: u32) -> State {
+    if page_size == PAGE_SIZE {
+        return State::from(root.as_ref());
+    }
+
+    let mut page_size_hash = [0; PAGE_HASH_LEN];
+    page_size_hash[..4].copy_from_slice(&page_size.to_be_bytes());
+
+    let state_hash = SH::hash(&[PageHash(root.0), PageHash(page_size_hash)]);
+
+    State::from(state_hash.as_ref())
+}
+
+/// Terminates a state entry whose last pages are all zero pages (see `encode_state_entry`).
+const ZERO_TAIL_MARKER: u8 = 0;
+
+/// Encodes the value of a state entry:
+///
+/// ```text
+/// state ---> [page1_hash || page2_hash || ... || pageN_hash]
+/// ```
+///
+/// When the last pages are zero pages (i.e `is_zero(page_idx)` returns `true`), their hashes are omitted.
+/// A single `ZERO_TAIL_MARKER` byte terminates the entry instead:
+///
+/// ```text
+/// state ---> [page1_hash || ... || pageK_hash || ZERO_TAIL_MARKER]    (pages `K+1..N` are zero pages)
+/// ```
+pub(crate) fn encode_state_entry<F>(pages_hash: &[PageHash], is_zero: F) -> Vec<u8>
+where
+    F: Fn(usize) -> bool,
+{
+    let listed = (0..pages_hash.len())
+        .rev()
+        .find(|page_idx| !is_zero(*page_idx))
+        .map_or(0, |page_idx| page_idx + 1);
+
+    let mut entry = Vec::with_capacity(listed * PAGE_HASH_LEN + 1);
+
+    for ph in pages_hash[..listed].iter() {
+        entry.extend_from_slice(&ph.0);
+    }
+
+    if listed < pages_hash.len() {
+        entry.push(ZERO_TAIL_MARKER);
+    }
+
+    entry
+}
+
+/// Decodes the value of a state entry (see `encode_state_entry`).
+/// Returns the listed pages-hash, and whether the pages following them are zero pages.
+pub(crate) fn decode_state_entry(entry: &[u8]) -> Result<(Vec<PageHash>, bool), KVError> {
+    let (hashes, zero_tail) = match entry.len() % PAGE_HASH_LEN {
+        0 => (entry, false),
+        1 if entry[entry.len() - 1] == ZERO_TAIL_MARKER => (&entry[..entry.len() - 1], true),
+        _ => {
+            return Err(KVError::DataCorruption(format!(
+                "invalid state entry length: {}",
+                entry.len()
+            )))
+        }
+    };
+
+    let pages_hash = hashes
+        .chunks_exact(PAGE_HASH_LEN)
+        .map(PageHash::from)
+        .collect();
+
+    Ok((pages_hash, zero_tail))
+}
+
+/// Decodes the state entry of app `addr` (having `page_count` pages of `page_size` bytes) and returns all its pages-hash.
+/// (the zero pages omitted from the state entry included).
+pub(crate) fn expand_state_entry<PH: PageHasher>(
+    addr: &Address,
+    entry: &[u8],
+    page_count: u16,
+    page_size: u32,
+) -> Result<Vec<PageHash>, KVError> {
+    let (mut pages_hash, zero_tail) = decode_state_entry(entry)?;
+
+    let listed = pages_hash.len();
+    let page_count = page_count as usize;
+
+    if listed > page_count || (!zero_tail && listed < page_count) {
+        return Err(KVError::DataCorruption(format!(
+            "state entry lists {} pages-hash (page count: {})",
+            listed, page_count
+        )));
+    }
+
+    let zero_page = page::zero_page(page_size);
+
+    for page_idx in listed..page_count {
+        let ph = PH::hash(addr.clone(), PageIndex(page_idx as u16), &zero_page);
+
+        pages_hash.push(ph);
+    }
+
+    Ok(pages_hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ph(n: u8) -> PageHash {
+        PageHash([n; PAGE_HASH_LEN])
+    }
+
+    #[test]
+    fn state_entry_without_zero_pages() {
+        let pages_hash = vec![ph(1), ph(2)];
+
+        let entry = encode_state_entry(&pages_hash, |_| false);
+        assert_eq!(2 * PAGE_HASH_LEN, entry.len());
+
+        assert_eq!((pages_hash, false), decode_state_entry(&entry).unwrap());
+    }
+
+    #[test]
+    fn state_entry_omits_trailing_zero_pages() {
+        // page #1 is a zero page too, but it isn't trailing
+        let pages_hash = vec![ph(1), ph(0), ph(2), ph(0), ph(0)];
+
+        let entry = encode_state_entry(&pages_hash, |i| pages_hash[i] == ph(0));
+        assert_eq!(3 * PAGE_HASH_LEN + 1, entry.len());
+
+        let expected = vec![ph(1), ph(0), ph(2)];
+        assert_eq!((expected, true), decode_state_entry(&entry).unwrap());
+    }
+
+    #[test]
+    fn state_entry_of_zero_pages_only() {
+        let pages_hash = vec![ph(0); 1000];
+
+        let entry = encode_state_entry(&pages_hash, |_| true);
+        assert_eq!(vec![ZERO_TAIL_MARKER], entry);
+
+        assert_eq!((Vec::new(), true), decode_state_entry(&entry).unwrap());
+    }
+
+    #[test]
+    fn compute_state_mixes_non_default_page_size() {
+        use crate::default::DefaultStateHasher;
+
+        let pages_hash = vec![ph(1), ph(2)];
+
+        let default_state = compute_state::<DefaultStateHasher>(&pages_hash, PAGE_SIZE);
+        let expected = State::from(DefaultStateHasher::hash(&pages_hash).as_ref());
+        assert_eq!(expected, default_state);
+
+        let state_1k = compute_state::<DefaultStateHasher>(&pages_hash, 1024);
+        let state_2k = compute_state::<DefaultStateHasher>(&pages_hash, 2048);
+
+        assert_ne!(default_state, state_1k);
+        assert_ne!(default_state, state_2k);
+        assert_ne!(state_1k, state_2k);
+    }
+
+    #[test]
+    fn state_entry_invalid_length() {
+        assert!(decode_state_entry(&[1; PAGE_HASH_LEN + 2]).is_err());
+        assert!(decode_state_entry(&[1; PAGE_HASH_LEN + 1]).is_err());
+    }
+}
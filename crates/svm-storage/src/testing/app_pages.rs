@@ -28,3 +28,35 @@ pub fn app_pages_open(
 ) -> MemAppPages {
     MemAppPages::new(addr.clone(), Rc::clone(&kv), state.clone(), page_count)
 }
+
+/// Same as `app_pages_init`, where each page is of `page_size` bytes.
+pub fn app_pages_init_with_page_size(
+    addr: &str,
+    page_count: u16,
+    page_size: u32,
+) -> (Address, Rc<RefCell<MemKVStore>>, MemAppPages) {
+    let addr = Address::of(addr);
+    let kv = Rc::new(RefCell::new(MemKVStore::new()));
+
+    let pages = app_pages_open_with_page_size(&addr, &State::empty(), &kv, page_count, page_size);
+
+    (addr, kv, pages)
+}
+
+/// Same as `app_pages_open`, where each page is of `page_size` bytes.
+pub fn app_pages_open_with_page_size(
+    addr: &Address,
+    state: &State,
+    kv: &Rc<RefCell<MemKVStore>>,
+    page_count: u16,
+    page_size: u32,
+) -> MemAppPages {
+    MemAppPages::new_with_page_size(
+        addr.clone(),
+        Rc::clone(&kv),
+        state.clone(),
+        page_count,
+        page_size,
+    )
+    .unwrap()
+}
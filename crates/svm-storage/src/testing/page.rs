@@ -1,8 +1,8 @@
-use svm_common::{Address, DefaultKeyHasher, KeyHasher, State};
+use svm_common::{Address, State};
 
-use crate::default::{DefaultPageHasher, DefaultPageIndexHasher};
+use crate::default::{DefaultPageHasher, DefaultPageIndexHasher, DefaultStateHasher};
 use crate::page::{PageHash, PageIndex};
-use crate::traits::{PageHasher, PageIndexHasher};
+use crate::traits::{PageHasher, PageIndexHasher, StateHasher};
 
 /// An helper for computing a page default hash using `DefaultPageIndexHasher`
 pub fn default_page_hash(addr: &Address, page_idx: u16, data: &[u8]) -> PageHash {
@@ -34,16 +34,18 @@ pub fn concat_pages_hash(pages_hash: &[PageHash]) -> Vec<u8> {
     res
 }
 
+/// Concatenates pages-hash into one vector of bytes, followed by the zero-tail marker.
+/// That's how a state entry looks when the pages following `pages_hash` are zero pages.
+pub fn concat_pages_hash_with_zero_tail(pages_hash: &[PageHash]) -> Vec<u8> {
+    let mut res = concat_pages_hash(pages_hash);
+    res.push(0);
+
+    res
+}
+
 /// Derives the app new `State` by its pages-hash.
 pub fn compute_pages_state(pages_hash: &[PageHash]) -> State {
-    let concat_ph = concat_pages_hash(pages_hash);
-
-    let state = Some(concat_ph.as_slice())
-        .map(|jph| {
-            let h = DefaultKeyHasher::hash(jph);
-            State::from(h.as_ref())
-        })
-        .unwrap();
+    let state_hash = DefaultStateHasher::hash(pages_hash);
 
-    state
+    State::from(state_hash.as_ref())
 }
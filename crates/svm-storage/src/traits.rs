@@ -1,6 +1,9 @@
+use crate::fsck::FsckReport;
 use crate::page::{PageHash, PageIndex};
+use crate::proof::{AppStateProof, ProofError};
 use crate::state::StateHash;
 use svm_common::{Address, State};
+use svm_kv::KVError;
 
 /// `PagesStorage` is the most low-level trait for dealing with a app's storage.
 /// For performance concerns, we work on pages units (a page is 4096 bytes)
@@ -11,6 +14,18 @@ pub trait PagesStorage {
     #[must_use]
     fn read_page(&mut self, page_idx: PageIndex) -> Option<Vec<u8>>;
 
+    /// Retrieves the content of the pages indexed `pages` (in the same order).
+    ///
+    /// The default implementation reads the pages one after the other.
+    /// Pages storages able to read many pages at once should override it.
+    #[must_use]
+    fn read_pages(&mut self, pages: &[PageIndex]) -> Vec<Option<Vec<u8>>> {
+        pages
+            .iter()
+            .map(|page_idx| self.read_page(*page_idx))
+            .collect()
+    }
+
     /// Overrides the page indexed `page_idx` with the content of `data` (and marking it as `dirty`)
     /// Important: does NOT persist new page version yet (see: `commit`)
     fn write_page(&mut self, page_idx: PageIndex, data: &[u8]);
@@ -19,7 +34,8 @@ pub trait PagesStorage {
     fn clear(&mut self);
 
     /// Persist the pending in-memory dirty pages into the backed database
-    fn commit(&mut self);
+    /// Returns an error when the backed database has failed (then, nothing is persisted).
+    fn commit(&mut self) -> Result<(), KVError>;
 }
 
 /// `PageIndexHasher` is a trait defining that a app storage-page hash must be determined by
@@ -40,6 +56,9 @@ pub trait PageIndexHasher {
 /// Implementors are in-charge of calculating a page hash.
 /// The page hash is derived from 3 components: `app address` + `page-index` + `page-data`
 pub trait PageHasher {
+    /// Identifies the page hashing scheme (recorded under snapshots headers, see `snapshot`)
+    const ID: u8;
+
     /// `address`  - The app account address
     /// `page_idx` - The page index we want to calculate its hash
     /// `page_data - The raw content of the page
@@ -50,11 +69,34 @@ pub trait PageHasher {
 /// Implementors are in-charge of calculating a page hash.
 /// The page hash isderived from 3 components: `app address` + `page-index` + `page-data`
 pub trait StateHasher {
+    /// Identifies the state hashing scheme (recorded under snapshots headers, see `snapshot`)
+    const ID: u8;
+
     /// `pages_hash` - a slice of `PageHash`
     #[must_use]
     fn hash(pages_hash: &[PageHash]) -> StateHash;
 }
 
+/// A `StateHasher` deriving the `StateHash` as the root of a Merkle tree whose leaves are the pages-hash.
+/// Such a state allows proving a single page content using only `O(log(#pages))` hashes.
+pub trait MerkleStateHasher: StateHasher {
+    /// Returns the Merkle path (the siblings hashes, from the leaves up) of page indexed `page_idx`.
+    #[must_use]
+    fn prove(pages_hash: &[PageHash], page_idx: PageIndex) -> Vec<[u8; 32]>;
+
+    /// Re-computes the `StateHash` given page indexed `page_idx` hash and its Merkle path `siblings`.
+    /// `page_count` is the number of tree leaves.
+    ///
+    /// Returns `None` when `siblings` doesn't fit a tree of `page_count` leaves.
+    #[must_use]
+    fn root_from_proof(
+        page_hash: &PageHash,
+        page_idx: PageIndex,
+        page_count: u16,
+        siblings: &[[u8; 32]],
+    ) -> Option<StateHash>;
+}
+
 /// This trait should be implemented by state-oriented pages storage.
 /// Since an app must have a state (like a source control revision) we need to have this
 /// capability implemented for real-usage app storage.
@@ -66,8 +108,51 @@ pub trait StateAwarePagesStorage: PagesStorage {
     /// Returns the page-hash of a given page indexed by `page_idx`
     #[must_use]
     fn get_page_hash(&self, page_idx: PageIndex) -> PageHash;
+
+    /// Returns the size (in bytes) of each page (see `page::is_valid_page_size`)
+    #[must_use]
+    fn page_size(&self) -> u32;
+
+    /// Returns whether page indexed `page_idx` is a zero page (i.e its page-hash is the zero-page hash).
+    /// Zero pages aren't persisted, so reading one should return `None` without hitting the backed database.
+    #[must_use]
+    fn is_zero_page(&self, page_idx: PageIndex) -> bool;
+
+    /// Checks the integrity of the current (committed) state and its persisted pages (see `fsck::check_app_state`).
+    /// Returns an error only when reading from the backed database has failed.
+    fn check_integrity(&self) -> Result<FsckReport, KVError>;
 }
 
-/// `PageCache` is a marker trait intended for subclassing the  StateAwarePagesStorage` trait.
+/// `PageCache` is a trait intended for subclassing the  StateAwarePagesStorage` trait.
 /// It's intended to mark a  StateAwarePagesStorage` as having a caching layer on top of the backed pages-storage.
-pub trait PageCache: StateAwarePagesStorage {}
+pub trait PageCache: StateAwarePagesStorage {
+    /// Loads the pages indexed `pages` into the cache ahead of their reads (using a single `read_pages`).
+    /// Pages already cached, and indexes out of the pages range, are skipped.
+    fn prefetch(&mut self, pages: &[PageIndex]);
+}
+
+/// Maintains the global-state: a single commitment (root) over the current `State` of all apps.
+pub trait GlobalStateStorage {
+    /// Returns the current state of app `addr` (including uncommitted changes).
+    /// Returns `None` when the app isn't under the global-state.
+    fn get_app_state(&self, addr: &Address) -> Result<Option<State>, KVError>;
+
+    /// Sets the current state of app `addr`.
+    /// Important: the root isn't updated until calling `commit`.
+    fn set_app_state(&mut self, addr: &Address, state: &State);
+
+    /// Returns the global-state root (as of the last `commit`)
+    #[must_use]
+    fn get_root(&self) -> State;
+
+    /// Persists the pending app-states changes and returns the new root.
+    fn commit(&mut self) -> Result<State, KVError>;
+
+    /// Discards the pending app-states changes and re-opens the latest committed root under the kv-store.
+    /// (for example, after the writes buffered by an underlying overlay have been discarded).
+    fn reload(&mut self) -> Result<(), KVError>;
+
+    /// Returns a proof of app `addr` current state under the current root. (see `AppStateProof::verify`)
+    /// Returns `ProofError::PendingChanges` when there are uncommitted changes.
+    fn prove_app_state(&self, addr: &Address) -> Result<AppStateProof, ProofError>;
+}
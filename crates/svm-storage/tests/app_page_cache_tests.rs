@@ -1,6 +1,11 @@
-use svm_storage::page::PageIndex;
-use svm_storage::testing::{app_page_cache_init, default_page_index_hash};
-use svm_storage::traits::PagesStorage;
+use svm_kv::traits::KVStore;
+
+use svm_storage::page::{zero_page, PageIndex, PAGE_SIZE};
+use svm_storage::testing::{
+    app_page_cache_init, app_page_cache_open, app_pages_init, default_page_hash,
+    default_page_index_hash,
+};
+use svm_storage::traits::{PageCache, PagesStorage, StateAwarePagesStorage};
 
 mod asserts;
 
@@ -54,8 +59,51 @@ fn page_cache_commit_persists_each_dirty_page() {
     let ph = default_page_index_hash("my-app", 0);
     assert_no_key!(kv, ph);
 
-    cache.commit();
+    cache.commit().unwrap();
 
     // `cache.commit` persists the page
-    assert_key_value!(kv, ph, [10, 20, 30]);
+    assert_page_value!(kv, ph, [10, 20, 30]);
+}
+
+#[test]
+fn page_cache_serves_zero_pages_without_hitting_kv() {
+    let addr = "my-app";
+    let page_count = 10;
+
+    let (addr, kv, mut cache) = app_page_cache_init(addr, page_count);
+
+    // a zero page is never stored, so such an entry must not be read
+    let ph = default_page_hash(&addr, 0, &zero_page(PAGE_SIZE));
+    kv.borrow_mut().store(&[(&ph.0, &[10, 20, 30])]).unwrap();
+
+    assert!(cache.is_zero_page(PageIndex(0)));
+    assert_eq!(None, cache.read_page(PageIndex(0)));
+}
+
+#[test]
+fn page_cache_prefetch_loads_pages_into_the_cache() {
+    let addr = "my-app";
+    let page_count = 3;
+
+    let (addr, kv, mut pages) = app_pages_init(addr, page_count);
+
+    pages.write_page(PageIndex(0), &[10, 20, 30]);
+    pages.write_page(PageIndex(1), &[40, 50, 60]);
+    pages.commit().unwrap();
+    let state = pages.get_state();
+
+    let mut cache = app_page_cache_open(&addr, &state, &kv, page_count);
+
+    // page #2 is a zero page, page #10 is out of range
+    cache.prefetch(&[PageIndex(0), PageIndex(2), PageIndex(10), PageIndex(0)]);
+
+    // removing the pages content from the key-value store.
+    // page #0 is served by the cache while page #1 (not prefetched) is read on demand.
+    let ph0 = default_page_hash(&addr, 0, &[10, 20, 30]);
+    let ph1 = default_page_hash(&addr, 1, &[40, 50, 60]);
+    kv.borrow_mut().delete(&[&ph0.0, &ph1.0]).unwrap();
+
+    assert_eq!(Some(vec![10, 20, 30]), cache.read_page(PageIndex(0)));
+    assert_eq!(None, cache.read_page(PageIndex(1)));
+    assert_eq!(None, cache.read_page(PageIndex(2)));
 }
@@ -1,12 +1,27 @@
 extern crate svm_storage;
 
-use svm_common::State;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use svm_common::{Address, State};
+use svm_kv::{
+    faulty::FaultyKV,
+    memory::MemKVStore,
+    traits::{KVStore, Overlay},
+    unit_of_work::UnitOfWork,
+};
 
-use svm_storage::page::{zero_page, PageIndex};
+use svm_storage::codec::PageCodec;
+use svm_storage::default::{DefaultPageHasher, DefaultStateHasher};
+use svm_storage::memory::MemAppPages;
+use svm_storage::overlay::OverlayAppPages;
+use svm_storage::page::{zero_page, InvalidPageSize, PageHash, PageIndex, PAGE_SIZE};
 use svm_storage::testing::{
-    app_pages_init, app_pages_open, compute_pages_state, concat_pages_hash, default_page_hash,
+    app_pages_init, app_pages_open, app_pages_open_with_page_size, compute_pages_state,
+    concat_pages_hash, concat_pages_hash_with_zero_tail, default_page_hash,
 };
-use svm_storage::traits::{PagesStorage, StateAwarePagesStorage};
+use svm_storage::traits::{PageHasher, PagesStorage, StateAwarePagesStorage};
+use svm_storage::{state, AppPages, SharedPageCache};
 
 mod asserts;
 
@@ -30,11 +45,11 @@ fn app_pages_first_time_run_with_no_modifications_then_commit() {
     let (addr, kv, mut pages) = app_pages_init(addr, page_count);
     assert_eq!(0, pages.dirty_page_count());
 
-    pages.commit();
+    pages.commit().unwrap();
 
-    let ph0 = default_page_hash(&addr, 0, &zero_page());
-    let ph1 = default_page_hash(&addr, 1, &zero_page());
-    let ph2 = default_page_hash(&addr, 2, &zero_page());
+    let ph0 = default_page_hash(&addr, 0, &zero_page(PAGE_SIZE));
+    let ph1 = default_page_hash(&addr, 1, &zero_page(PAGE_SIZE));
+    let ph2 = default_page_hash(&addr, 2, &zero_page(PAGE_SIZE));
 
     let expected_state = compute_pages_state(&[ph0, ph1, ph2]);
     let actual_state = pages.get_state();
@@ -63,11 +78,11 @@ fn app_pages_first_time_run_with_one_modified_page() {
 
     pages.write_page(PageIndex(0), &[10, 20, 30]);
     assert_eq!(1, pages.dirty_page_count());
-    pages.commit();
+    pages.commit().unwrap();
 
     let ph0 = default_page_hash(&addr, 0, &[10, 20, 30]);
-    let ph1 = default_page_hash(&addr, 1, &zero_page());
-    let ph2 = default_page_hash(&addr, 2, &zero_page());
+    let ph1 = default_page_hash(&addr, 1, &zero_page(PAGE_SIZE));
+    let ph2 = default_page_hash(&addr, 2, &zero_page(PAGE_SIZE));
 
     let expected_state = compute_pages_state(&[ph0, ph1, ph2]);
     let actual_state = pages.get_state();
@@ -78,9 +93,9 @@ fn app_pages_first_time_run_with_one_modified_page() {
     assert_key_value!(
         kv,
         actual_state.bytes(),
-        concat_pages_hash(&[ph0, ph1, ph2])
+        concat_pages_hash_with_zero_tail(&[ph0])
     );
-    assert_key_value!(kv, ph0.0, [10, 20, 30]);
+    assert_page_value!(kv, ph0.0, [10, 20, 30]);
 
     assert_page_content!(pages, 0, Some(vec![10, 20, 30]));
     assert_page_content!(pages, 1, None);
@@ -98,7 +113,7 @@ fn app_pages_first_time_run_with_two_modified_pages() {
     pages.write_page(PageIndex(0), &[10, 20, 30]);
     pages.write_page(PageIndex(1), &[40, 50, 60]);
     assert_eq!(2, pages.dirty_page_count());
-    pages.commit();
+    pages.commit().unwrap();
 
     let ph0 = default_page_hash(&addr, 0, &[10, 20, 30]);
     let ph1 = default_page_hash(&addr, 1, &[40, 50, 60]);
@@ -109,8 +124,8 @@ fn app_pages_first_time_run_with_two_modified_pages() {
 
     assert_same_keys!(vec![actual_state.bytes(), ph0.0, ph1.0], kv_keys_vec!(kv));
     assert_key_value!(kv, actual_state.bytes(), concat_pages_hash(&[ph0, ph1]));
-    assert_key_value!(kv, ph0.0, [10, 20, 30]);
-    assert_key_value!(kv, ph1.0, [40, 50, 60]);
+    assert_page_value!(kv, ph0.0, [10, 20, 30]);
+    assert_page_value!(kv, ph1.0, [40, 50, 60]);
     assert_page_content!(pages, 0, Some(vec![10, 20, 30]));
     assert_page_content!(pages, 1, Some(vec![40, 50, 60]));
     assert_eq!(0, pages.dirty_page_count());
@@ -123,19 +138,19 @@ fn app_pages_second_run_after_first_run_with_no_modifications() {
     let addr = "my-app";
 
     let (addr, kv, mut pages) = app_pages_init(addr, page_count);
-    pages.commit();
+    pages.commit().unwrap();
     let old_state = pages.get_state();
 
     // 2nd run
     let mut pages = app_pages_open(&addr, &old_state, &kv, page_count);
     pages.write_page(PageIndex(0), &[10, 20, 30]);
     pages.write_page(PageIndex(1), &[40, 50, 60]);
-    pages.commit();
+    pages.commit().unwrap();
 
     // modifying pages `0` and `1`
     let ph0 = default_page_hash(&addr, 0, &[10, 20, 30]);
     let ph1 = default_page_hash(&addr, 1, &[40, 50, 60]);
-    let ph2 = default_page_hash(&addr, 2, &zero_page());
+    let ph2 = default_page_hash(&addr, 2, &zero_page(PAGE_SIZE));
 
     let expected_state = compute_pages_state(&[ph0, ph1, ph2]);
     let new_state = pages.get_state();
@@ -146,13 +161,42 @@ fn app_pages_second_run_after_first_run_with_no_modifications() {
         kv_keys_vec!(kv)
     );
 
-    assert_key_value!(kv, new_state.bytes(), concat_pages_hash(&[ph0, ph1, ph2]));
-    assert_key_value!(kv, ph0.0, [10, 20, 30]);
-    assert_key_value!(kv, ph0.0, [10, 20, 30]);
-    assert_key_value!(kv, ph1.0, [40, 50, 60]);
+    assert_key_value!(
+        kv,
+        new_state.bytes(),
+        concat_pages_hash_with_zero_tail(&[ph0, ph1])
+    );
+    assert_page_value!(kv, ph0.0, [10, 20, 30]);
+    assert_page_value!(kv, ph0.0, [10, 20, 30]);
+    assert_page_value!(kv, ph1.0, [40, 50, 60]);
     assert_no_key!(kv, ph2.0);
 }
 
+#[test]
+fn app_pages_read_pages() {
+    // 1st run
+    let page_count = 3;
+    let addr = "my-app";
+
+    let (addr, kv, mut pages) = app_pages_init(addr, page_count);
+
+    pages.write_page(PageIndex(0), &[10, 20, 30]);
+    pages.write_page(PageIndex(2), &[40, 50, 60]);
+    pages.commit().unwrap();
+    let state = pages.get_state();
+
+    // 2nd run
+    let mut pages = app_pages_open(&addr, &state, &kv, page_count);
+
+    let actual = pages.read_pages(&[PageIndex(2), PageIndex(1), PageIndex(0)]);
+
+    let expected = vec![Some(vec![40, 50, 60]), None, Some(vec![10, 20, 30])];
+    assert_eq!(expected, actual);
+
+    assert!(pages.read_pages(&[]).is_empty());
+    assert!(pages.commit().is_ok());
+}
+
 #[test]
 fn app_pages_second_run_after_first_run_with_modifications() {
     // 1st run
@@ -162,20 +206,20 @@ fn app_pages_second_run_after_first_run_with_modifications() {
     let (addr, kv, mut pages) = app_pages_init(addr, page_count);
 
     pages.write_page(PageIndex(0), &[11, 22, 33]);
-    pages.commit();
+    pages.commit().unwrap();
     let old_state = pages.get_state();
 
     // 2nd run
     let mut pages = app_pages_open(&addr, &old_state, &kv, page_count);
     pages.write_page(PageIndex(0), &[10, 20, 30]);
     pages.write_page(PageIndex(1), &[40, 50, 60]);
-    pages.commit();
+    pages.commit().unwrap();
 
     // modifying pages `0` and `1`
     let ph0_old = default_page_hash(&addr, 0, &[11, 22, 33]);
     let ph0 = default_page_hash(&addr, 0, &[10, 20, 30]);
     let ph1 = default_page_hash(&addr, 1, &[40, 50, 60]);
-    let ph2 = default_page_hash(&addr, 2, &zero_page());
+    let ph2 = default_page_hash(&addr, 2, &zero_page(PAGE_SIZE));
 
     let expected_state = compute_pages_state(&[ph0, ph1, ph2]);
     let new_state = pages.get_state();
@@ -192,9 +236,13 @@ fn app_pages_second_run_after_first_run_with_modifications() {
         kv_keys_vec!(kv)
     );
 
-    assert_key_value!(kv, new_state.bytes(), concat_pages_hash(&[ph0, ph1, ph2]));
-    assert_key_value!(kv, ph0.0, [10, 20, 30]);
-    assert_key_value!(kv, ph1.0, [40, 50, 60]);
+    assert_key_value!(
+        kv,
+        new_state.bytes(),
+        concat_pages_hash_with_zero_tail(&[ph0, ph1])
+    );
+    assert_page_value!(kv, ph0.0, [10, 20, 30]);
+    assert_page_value!(kv, ph1.0, [40, 50, 60]);
     assert_no_key!(kv, ph2.0);
 }
 
@@ -207,24 +255,24 @@ fn app_pages_third_run_rollbacks_to_after_first_run() {
     let (addr, kv, mut pages) = app_pages_init(addr, page_count);
 
     pages.write_page(PageIndex(0), &[11, 22, 33]);
-    pages.commit();
+    pages.commit().unwrap();
     let state_1 = pages.get_state();
 
     let ph0_1 = default_page_hash(&addr, 0, &[11, 22, 33]);
-    let ph1_1 = default_page_hash(&addr, 1, &zero_page());
-    let ph2_1 = default_page_hash(&addr, 2, &zero_page());
+    let ph1_1 = default_page_hash(&addr, 1, &zero_page(PAGE_SIZE));
+    let ph2_1 = default_page_hash(&addr, 2, &zero_page(PAGE_SIZE));
 
     // 2nd run
     let mut pages = app_pages_open(&addr, &state_1, &kv, page_count);
     pages.write_page(PageIndex(0), &[10, 20, 30]);
     pages.write_page(PageIndex(1), &[40, 50, 60]);
-    pages.commit();
+    pages.commit().unwrap();
     let state_2 = pages.get_state();
 
     // modifying pages `0` and `1`
     let ph0_2 = default_page_hash(&addr, 0, &[10, 20, 30]);
     let ph1_2 = default_page_hash(&addr, 1, &[40, 50, 60]);
-    let ph2_2 = default_page_hash(&addr, 2, &zero_page());
+    let ph2_2 = default_page_hash(&addr, 2, &zero_page(PAGE_SIZE));
 
     // 3rd run (rollbacks to `state_1` initial state)
     let pages = app_pages_open(&addr, &state_1, &kv, page_count);
@@ -240,7 +288,7 @@ fn app_pages_third_run_rollbacks_to_after_first_run() {
     assert_key_value!(
         kv,
         state_1.bytes(),
-        concat_pages_hash(&[ph0_1, ph1_1, ph2_1])
+        concat_pages_hash_with_zero_tail(&[ph0_1])
     );
 
     // 4th run (rollbacks to `state_2` state)
@@ -254,8 +302,321 @@ fn app_pages_third_run_rollbacks_to_after_first_run() {
     assert_key_value!(
         kv,
         state_2.bytes(),
-        concat_pages_hash(&[ph0_2, ph1_2, ph2_2])
+        concat_pages_hash_with_zero_tail(&[ph0_2, ph1_2])
     );
 
     assert_eq!(state_2, pages.get_state());
+    assert_eq!(compute_pages_state(&[ph0_2, ph1_2, ph2_2]), state_2);
+}
+
+#[test]
+fn app_pages_with_unit_of_work() {
+    let page_count = 3;
+    let addr = Address::of("my-app");
+
+    let backend = Rc::new(RefCell::new(FaultyKV::new(MemKVStore::new())));
+    let mut uow = UnitOfWork::new(Rc::clone(&backend));
+
+    let mut pages = OverlayAppPages::new_with_unit_of_work(
+        addr.clone(),
+        &mut uow,
+        "pages",
+        "app-states",
+        State::empty(),
+        page_count,
+        PAGE_SIZE,
+    )
+    .unwrap();
+
+    pages.write_page(PageIndex(0), &[10, 20, 30]);
+    pages.commit().unwrap();
+
+    let ph0 = default_page_hash(&addr, 0, &[10, 20, 30]);
+    let ph1 = default_page_hash(&addr, 1, &zero_page(PAGE_SIZE));
+    let ph2 = default_page_hash(&addr, 2, &zero_page(PAGE_SIZE));
+
+    let state = pages.get_state();
+    assert_eq!(compute_pages_state(&[ph0, ph1, ph2]), state);
+
+    // the pages go to the `pages` store while the state entry goes to the `app-states` store
+    let pages_kv = uow.open_store("pages");
+    let states_kv = uow.open_store("app-states");
+
+    assert_no_key!(pages_kv, state.bytes());
+    assert_no_key!(states_kv, ph0.0);
+    assert_key_value!(
+        states_kv,
+        state.bytes(),
+        concat_pages_hash_with_zero_tail(&[ph0])
+    );
+
+    // nothing is persisted until the unit-of-work is committed (using a single write)
+    assert_eq!(0, backend.borrow().writes_count());
+
+    uow.commit().unwrap();
+
+    assert_eq!(1, backend.borrow().writes_count());
+
+    // reopening
+    let mut pages = OverlayAppPages::new_with_unit_of_work(
+        addr,
+        &mut uow,
+        "pages",
+        "app-states",
+        state,
+        page_count,
+        PAGE_SIZE,
+    )
+    .unwrap();
+
+    assert_page_content!(pages, 0, Some(vec![10, 20, 30]));
+    assert_page_content!(pages, 1, None);
+}
+
+#[test]
+fn app_pages_read_through_shared_cache() {
+    let page_count = 2;
+    let cache = SharedPageCache::new(10 * 4096);
+
+    let (addr, kv, pages) = app_pages_init("my-app", page_count);
+    let mut pages = pages.with_shared_cache(cache.clone());
+
+    pages.write_page(PageIndex(0), &[10, 20, 30]);
+    pages.commit().unwrap();
+
+    let state = pages.get_state();
+    let ph = pages.get_page_hash(PageIndex(0));
+
+    // the committed page is cached, so reading it (under a later transaction) doesn't touch `kv`
+    kv.borrow_mut().delete(&[&ph.0]).unwrap();
+
+    let mut pages = app_pages_open(&addr, &state, &kv, page_count).with_shared_cache(cache.clone());
+    assert_eq!(Some(vec![10, 20, 30]), pages.read_page(PageIndex(0)));
+
+    // a zero page (never stored) isn't looked up at all
+    assert_eq!(None, pages.read_page(PageIndex(1)));
+
+    let metrics = cache.metrics();
+    assert_eq!(1, metrics.hits);
+    assert_eq!(0, metrics.misses);
+    assert_eq!(1, metrics.entries);
+    assert_eq!(3, metrics.used_bytes);
+}
+
+#[test]
+fn app_pages_encoded_by_different_codecs() {
+    let page_count = 3;
+
+    let (addr, kv, pages) = app_pages_init("my-app", page_count);
+    let mut pages = pages.with_codec(PageCodec::ZeroRun);
+
+    let mut page0 = zero_page(PAGE_SIZE);
+    page0[100..103].copy_from_slice(&[10, 20, 30]);
+
+    pages.write_page(PageIndex(0), &page0);
+    pages.commit().unwrap();
+
+    let ph0 = default_page_hash(&addr, 0, &page0);
+    let ph1 = default_page_hash(&addr, 1, &zero_page(PAGE_SIZE));
+    let ph2 = default_page_hash(&addr, 2, &zero_page(PAGE_SIZE));
+
+    // the page-hash (and hence the state) is computed over the uncompressed page
+    let state = pages.get_state();
+    assert_eq!(compute_pages_state(&[ph0, ph1, ph2]), state);
+
+    let data = kv.borrow().get(&ph0.0).unwrap().unwrap();
+    assert_eq!(PageCodec::ZeroRun.tag(), data[0]);
+    assert!(data.len() < 20);
+    assert_page_value!(kv, ph0.0, &page0[..]);
+
+    // changing the codec, the page persisted by the previous codec is still readable
+    let pages = app_pages_open(&addr, &state, &kv, page_count);
+    let mut pages = pages.with_codec(PageCodec::Lz4);
+
+    assert_page_content!(pages, 0, Some(page0.clone()));
+
+    let page1 = vec![40; 4096];
+    pages.write_page(PageIndex(1), &page1);
+    pages.commit().unwrap();
+
+    let ph1 = default_page_hash(&addr, 1, &page1);
+    assert_eq!(
+        PageCodec::Lz4.tag(),
+        kv.borrow().get(&ph1.0).unwrap().unwrap()[0]
+    );
+
+    let state = pages.get_state();
+    assert_eq!(compute_pages_state(&[ph0, ph1, ph2]), state);
+
+    let mut pages = app_pages_open(&addr, &state, &kv, page_count);
+
+    assert_page_content!(pages, 0, Some(page0));
+    assert_page_content!(pages, 1, Some(page1));
+}
+
+#[test]
+fn app_pages_zero_pages_are_not_stored() {
+    let page_count = 3;
+    let addr = "my-app";
+
+    let (addr, kv, mut pages) = app_pages_init(addr, page_count);
+
+    pages.write_page(PageIndex(0), &zero_page(PAGE_SIZE));
+    pages.write_page(PageIndex(1), &[10, 20, 30]);
+    pages.commit().unwrap();
+
+    let ph0 = default_page_hash(&addr, 0, &zero_page(PAGE_SIZE));
+    let ph1 = default_page_hash(&addr, 1, &[10, 20, 30]);
+    let ph2 = default_page_hash(&addr, 2, &zero_page(PAGE_SIZE));
+
+    let state = pages.get_state();
+    assert_eq!(compute_pages_state(&[ph0, ph1, ph2]), state);
+
+    // a zero page (not trailing) is still listed under the state entry, but its content isn't stored
+    assert_same_keys!(vec![state.bytes(), ph1.0], kv_keys_vec!(kv));
+    assert_key_value!(
+        kv,
+        state.bytes(),
+        concat_pages_hash_with_zero_tail(&[ph0, ph1])
+    );
+
+    // a zero page is read without hitting `kv`
+    kv.borrow_mut().store(&[(&ph0.0, &[1, 2, 3])]).unwrap();
+
+    let mut pages = app_pages_open(&addr, &state, &kv, page_count);
+
+    assert!(pages.is_zero_page(PageIndex(0)));
+    assert!(!pages.is_zero_page(PageIndex(1)));
+    assert_page_content!(pages, 0, None);
+    assert_page_content!(pages, 1, Some(vec![10, 20, 30]));
+    assert_page_content!(pages, 2, None);
+}
+
+#[test]
+fn app_pages_state_entry_of_zero_pages_only() {
+    let page_count = 1000;
+    let addr = "my-app";
+
+    let (addr, kv, mut pages) = app_pages_init(addr, page_count);
+    pages.commit().unwrap();
+
+    // the state entry consists of the zero-tail marker only (instead of `1000 * 32` bytes)
+    let state = pages.get_state();
+    assert_same_keys!(vec![state.bytes()], kv_keys_vec!(kv));
+    assert_key_value!(kv, state.bytes(), concat_pages_hash_with_zero_tail(&[]));
+
+    let pages = app_pages_open(&addr, &state, &kv, page_count);
+
+    assert_eq!(state, pages.get_state());
+    assert_eq!(
+        default_page_hash(&addr, 999, &zero_page(PAGE_SIZE)),
+        pages.get_page_hash(PageIndex(999))
+    );
+}
+
+#[test]
+fn app_pages_of_non_default_page_size() {
+    let page_count = 3;
+    let page_size = 1024;
+
+    let kv = Rc::new(RefCell::new(MemKVStore::new()));
+    let addr = Address::of("my-app");
+
+    let open =
+        |state: &State| app_pages_open_with_page_size(&addr, state, &kv, page_count, page_size);
+
+    let mut page0 = zero_page(page_size);
+    page0[..3].copy_from_slice(&[10, 20, 30]);
+
+    let mut pages = open(&State::empty());
+    assert_eq!(page_size, pages.page_size());
+
+    pages.write_page(PageIndex(0), &page0);
+    pages.commit().unwrap();
+
+    let ph0 = default_page_hash(&addr, 0, &page0);
+    let ph1 = default_page_hash(&addr, 1, &zero_page(page_size));
+    let ph2 = default_page_hash(&addr, 2, &zero_page(page_size));
+
+    // the page size is part of the state
+    let state = pages.get_state();
+
+    assert_eq!(
+        state::compute_state::<DefaultStateHasher>(&[ph0, ph1, ph2], page_size),
+        state
+    );
+    assert_ne!(compute_pages_state(&[ph0, ph1, ph2]), state);
+
+    let mut pages = open(&state);
+
+    assert!(pages.is_zero_page(PageIndex(1)));
+    assert!(pages.check_integrity().unwrap().is_ok());
+    assert_page_content!(pages, 0, Some(page0.clone()));
+    assert_page_content!(pages, 1, None);
+
+    // the same content under the default page size
+    let (_addr, _kv, mut default_pages) = app_pages_init("my-app", page_count);
+
+    default_pages.write_page(PageIndex(0), &page0);
+    default_pages.commit().unwrap();
+
+    assert_ne!(state, default_pages.get_state());
+}
+
+#[test]
+fn app_pages_invalid_page_size() {
+    let addr = Address::of("my-app");
+    let kv = Rc::new(RefCell::new(MemKVStore::new()));
+
+    let res = MemAppPages::new_with_page_size(addr, kv, State::empty(), 3, 1000);
+
+    assert_eq!(Some(InvalidPageSize(1000)), res.err());
+}
+
+thread_local! {
+    static PAGES_HASHED: Cell<usize> = Cell::new(0);
+}
+
+/// A `DefaultPageHasher` counting the hashed pages (see `PAGES_HASHED`).
+struct CountingPageHasher;
+
+impl PageHasher for CountingPageHasher {
+    const ID: u8 = DefaultPageHasher::ID;
+
+    fn hash(address: Address, page_idx: PageIndex, page_data: &[u8]) -> PageHash {
+        PAGES_HASHED.with(|count| count.set(count.get() + 1));
+
+        DefaultPageHasher::hash(address, page_idx, page_data)
+    }
+}
+
+#[test]
+fn app_pages_zero_pages_hashes_computed_on_demand() {
+    let page_count = 1000;
+    let addr = Address::of("my-app");
+    let kv = Rc::new(RefCell::new(MemKVStore::new()));
+
+    let open = |state: State| {
+        AppPages::<MemKVStore, CountingPageHasher, DefaultStateHasher>::new(
+            addr.clone(),
+            Rc::clone(&kv),
+            state,
+            page_count,
+        )
+    };
+
+    let mut pages = open(State::empty());
+    pages.write_page(PageIndex(0), &[10, 20, 30]);
+    pages.commit().unwrap();
+    let state = pages.get_state();
+
+    PAGES_HASHED.with(|count| count.set(0));
+
+    let mut pages = open(state);
+    assert_eq!(0, PAGES_HASHED.with(|count| count.get()));
+
+    // only page #0 zero-page hash is computed (page #999 is known to be a zero page)
+    assert_eq!(Some(vec![10, 20, 30]), pages.read_page(PageIndex(0)));
+    assert_eq!(None, pages.read_page(PageIndex(999)));
+    assert_eq!(1, PAGES_HASHED.with(|count| count.get()));
 }
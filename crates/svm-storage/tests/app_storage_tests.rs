@@ -1,7 +1,12 @@
 use svm_kv::traits::KVStore;
 
-use svm_storage::page::{zero_page, PageIndex, PageOffset, PageSliceLayout};
-use svm_storage::testing::{app_storage_init, app_storage_open, default_page_hash, fill_page};
+use svm_storage::default::DefaultPageCache;
+use svm_storage::page::{zero_page, PageIndex, PageOffset, PageSliceLayout, PAGE_SIZE};
+use svm_storage::testing::{
+    app_pages_init_with_page_size, app_pages_open_with_page_size, app_storage_init,
+    app_storage_open, default_page_hash, fill_page,
+};
+use svm_storage::AppStorage;
 
 mod asserts;
 
@@ -48,19 +53,19 @@ fn app_storage_write_slice_without_loading_it_first_and_commit() {
     let layout = PageSliceLayout::new(PageIndex(1), PageOffset(100), 3);
 
     storage.write_page_slice(&layout, &[10, 20, 30]);
-    let new_state = storage.commit();
+    let new_state = storage.commit().unwrap();
 
     // asserting persisted data. when viewed in the context of `new_state`.
     app_storage_open(&addr, &new_state, &kv, page_count);
 
     assert_eq!(vec![10, 20, 30], storage.read_page_slice(&layout));
 
-    let mut expected_page = zero_page();
+    let mut expected_page = zero_page(PAGE_SIZE);
     fill_page(&mut expected_page, &[(100, 10), (101, 20), (102, 30)]);
 
     let ph = default_page_hash(&addr, 1, &expected_page);
 
-    assert_key_value!(kv, ph.0, expected_page);
+    assert_page_value!(kv, ph.0, expected_page);
 }
 
 #[test]
@@ -73,30 +78,30 @@ fn app_storage_read_an_existing_slice_then_overriding_it_and_commit() {
     let layout = PageSliceLayout::new(PageIndex(1), PageOffset(100), 3);
 
     storage.write_page_slice(&layout, &vec![10, 20, 30]);
-    let _ = storage.commit();
+    let _ = storage.commit().unwrap();
 
-    let mut expected_page = zero_page();
+    let mut expected_page = zero_page(PAGE_SIZE);
     fill_page(&mut expected_page, &[(100, 10), (101, 20), (102, 30)]);
     let ph1 = default_page_hash(&addr, 1, &expected_page);
     fill_page(&mut expected_page, &[(100, 40), (101, 50), (102, 60)]);
     let ph2 = default_page_hash(&addr, 1, &expected_page);
 
-    let page = kv.borrow().get(&ph1.0).unwrap();
+    let page = kv_page!(kv, ph1.0);
     assert_eq!(vec![10, 20, 30], &page[100..103]);
     storage.write_page_slice(&layout, &vec![40, 50, 60]);
 
     // new page is on the page-storage, but not persisted yet
     assert_eq!(vec![40, 50, 60], storage.read_page_slice(&layout));
 
-    let page = kv.borrow().get(&ph1.0).unwrap();
+    let page = kv_page!(kv, ph1.0);
     assert_eq!(vec![10, 20, 30], &page[100..103]);
 
-    assert_eq!(None, kv.borrow().get(&ph2.0));
+    assert_eq!(None, kv.borrow().get(&ph2.0).unwrap());
 
     // now we also persist the new page version
-    let _ = storage.commit();
+    let _ = storage.commit().unwrap();
 
-    let page = kv.borrow().get(&ph2.0).unwrap();
+    let page = kv_page!(kv, ph2.0);
     assert_eq!(vec![40, 50, 60], &page[100..103]);
 }
 
@@ -109,7 +114,7 @@ fn app_storage_write_slice_and_commit_then_load_it_override_it_and_commit() {
 
     let layout = PageSliceLayout::new(PageIndex(1), PageOffset(100), 3);
 
-    let mut expected_page = zero_page();
+    let mut expected_page = zero_page(PAGE_SIZE);
     fill_page(&mut expected_page, &[(100, 10), (101, 20), (102, 30)]);
     let ph1 = default_page_hash(&addr, 1, &expected_page);
     fill_page(&mut expected_page, &[(100, 40), (101, 50), (102, 60)]);
@@ -119,7 +124,7 @@ fn app_storage_write_slice_and_commit_then_load_it_override_it_and_commit() {
     storage.write_page_slice(&layout, &vec![10, 20, 30]);
 
     // 2) commit
-    let state = storage.commit();
+    let state = storage.commit().unwrap();
 
     // 3) re-load persisted page (we do a `clear` first to make sure we load from the pages-storage)
     let mut storage = app_storage_open(&addr, &state, &kv, page_count);
@@ -130,12 +135,12 @@ fn app_storage_write_slice_and_commit_then_load_it_override_it_and_commit() {
     assert_eq!(vec![40, 50, 60], storage.read_page_slice(&layout));
 
     // 5) commit again
-    let page = kv.borrow().get(&ph1.0).unwrap();
+    let page = kv_page!(kv, ph1.0);
     assert_eq!(vec![10, 20, 30], &page[100..103]);
 
-    let _ = storage.commit();
+    let _ = storage.commit().unwrap();
 
-    let page = kv.borrow().get(&ph2.0).unwrap();
+    let page = kv_page!(kv, ph2.0);
     assert_eq!(vec![40, 50, 60], &page[100..103]);
 }
 
@@ -149,7 +154,7 @@ fn app_storage_write_two_slices_under_same_page_and_commit() {
     let layout1 = PageSliceLayout::new(PageIndex(1), PageOffset(100), 3);
     let layout2 = PageSliceLayout::new(PageIndex(1), PageOffset(200), 2);
 
-    let mut expected_page = zero_page();
+    let mut expected_page = zero_page(PAGE_SIZE);
     fill_page(
         &mut expected_page,
         &[(100, 10), (101, 20), (102, 30), (200, 40), (201, 50)],
@@ -166,7 +171,7 @@ fn app_storage_write_two_slices_under_same_page_and_commit() {
     // commiting two slices under the same page
     assert_no_key!(kv, ph.0);
 
-    let state = storage.commit();
+    let state = storage.commit().unwrap();
 
     // asserting persisted data. when viewing in the context of `new_state`.
     let mut storage = app_storage_open(&addr, &state, &kv, page_count);
@@ -175,7 +180,221 @@ fn app_storage_write_two_slices_under_same_page_and_commit() {
     assert_eq!(vec![40, 50], storage.read_page_slice(&layout2));
 
     // querying the key-value store directly
-    let page = kv.borrow().get(&ph.0).unwrap();
+    let page = kv_page!(kv, ph.0);
     assert_eq!(vec![10, 20, 30], &page[100..103]);
     assert_eq!(vec![40, 50], &page[200..202]);
 }
+
+#[test]
+fn app_storage_write_slice_spanning_pages_and_commit() {
+    let addr = "my-app";
+    let page_count = 4;
+
+    let (addr, kv, mut storage) = app_storage_init(addr, page_count);
+
+    // starts at page #0 offset `4094`, covers page #1 entirely and ends at page #2 offset `1`
+    let offset = 4094;
+    let data: Vec<u8> = (0..4096 + 4).map(|i| (i % 251) as u8 + 1).collect();
+
+    storage.write_slice(offset, &data);
+    assert_eq!(data, storage.read_slice(offset, data.len() as u32));
+
+    let state = storage.commit().unwrap();
+
+    // reading the slice in the context of `state`
+    let mut storage = app_storage_open(&addr, &state, &kv, page_count);
+    assert_eq!(data, storage.read_slice(offset, data.len() as u32));
+
+    // the slice spans 3 pages (page #3 remains a zero page)
+    let mut page0 = zero_page(PAGE_SIZE);
+    page0[4094..].copy_from_slice(&data[..2]);
+    let page1 = data[2..4098].to_vec();
+    let mut page2 = zero_page(PAGE_SIZE);
+    page2[..2].copy_from_slice(&data[4098..]);
+
+    assert_page_value!(kv, default_page_hash(&addr, 0, &page0).0, page0);
+    assert_page_value!(kv, default_page_hash(&addr, 1, &page1).0, page1);
+    assert_page_value!(kv, default_page_hash(&addr, 2, &page2).0, page2);
+    assert_eq!(None, storage.read_page(PageIndex(3)));
+}
+
+#[test]
+fn app_storage_read_slice_within_a_page_equals_read_page_slice() {
+    let addr = "my-app";
+    let page_count = 2;
+
+    let (_addr, _kv, mut storage) = app_storage_init(addr, page_count);
+
+    let layout = PageSliceLayout::new(PageIndex(1), PageOffset(100), 3);
+    storage.write_page_slice(&layout, &[10, 20, 30]);
+
+    assert_eq!(vec![10, 20, 30], storage.read_slice(4096 + 100, 3));
+    assert!(storage.read_slice(4096 + 100, 0).is_empty());
+}
+
+#[test]
+fn app_storage_of_non_default_page_size() {
+    let page_count = 4;
+    let page_size = 256;
+
+    let (addr, kv, pages) = app_pages_init_with_page_size("my-app", page_count, page_size);
+    let mut storage = AppStorage::new(Box::new(DefaultPageCache::new(pages, page_count)));
+
+    assert_eq!(page_size, storage.page_size());
+
+    // starts at page #1 offset `200` and ends at page #2 offset `43`
+    let data: Vec<u8> = (1..=100).collect();
+    storage.write_slice(page_size + 200, &data);
+
+    let state = storage.commit().unwrap();
+
+    let pages = app_pages_open_with_page_size(&addr, &state, &kv, page_count, page_size);
+    let mut storage = AppStorage::new(Box::new(DefaultPageCache::new(pages, page_count)));
+
+    assert_eq!(data, storage.read_slice(page_size + 200, 100));
+
+    let mut page1 = zero_page(page_size);
+    page1[200..].copy_from_slice(&data[..56]);
+
+    assert_eq!(Some(page1), storage.read_page(PageIndex(1)));
+}
+
+#[test]
+#[should_panic]
+fn app_storage_page_slice_exceeding_the_page_size() {
+    let (_addr, _kv, pages) = app_pages_init_with_page_size("my-app", 2, 256);
+    let mut storage = AppStorage::new(Box::new(DefaultPageCache::new(pages, 2)));
+
+    let layout = PageSliceLayout::new(PageIndex(0), PageOffset(250), 10);
+
+    storage.write_page_slice(&layout, &[0; 10]);
+}
+
+#[test]
+fn app_storage_rollback_to_checkpoint() {
+    let (addr, kv, mut storage) = app_storage_init("my-app", 2);
+
+    let layout1 = PageSliceLayout::new(PageIndex(0), PageOffset(100), 3);
+    let layout2 = PageSliceLayout::new(PageIndex(1), PageOffset(200), 2);
+
+    storage.write_page_slice(&layout1, &[10, 20, 30]);
+
+    let checkpoint = storage.checkpoint();
+
+    storage.write_page_slice(&layout1, &[40, 50, 60]);
+    storage.write_page_slice(&layout1, &[70, 80, 90]);
+    storage.write_page_slice(&layout2, &[1, 2]);
+
+    assert_eq!(vec![70, 80, 90], storage.read_page_slice(&layout1));
+    assert_eq!(vec![1, 2], storage.read_page_slice(&layout2));
+
+    storage.rollback_to(checkpoint);
+
+    // the writes prior to the checkpoint are kept
+    assert_eq!(vec![10, 20, 30], storage.read_page_slice(&layout1));
+    assert_eq!(vec![0, 0], storage.read_page_slice(&layout2));
+
+    let state = storage.commit().unwrap();
+
+    let mut expected_page = zero_page(PAGE_SIZE);
+    fill_page(&mut expected_page, &[(100, 10), (101, 20), (102, 30)]);
+    let ph = default_page_hash(&addr, 0, &expected_page);
+
+    assert_page_value!(kv, ph.0, expected_page);
+
+    let mut storage = app_storage_open(&addr, &state, &kv, 2);
+    assert_eq!(None, storage.read_page(PageIndex(1)));
+}
+
+#[test]
+fn app_storage_nested_checkpoints() {
+    let (_addr, _kv, mut storage) = app_storage_init("my-app", 2);
+
+    let layout = PageSliceLayout::new(PageIndex(1), PageOffset(100), 3);
+
+    let outer = storage.checkpoint();
+    storage.write_page_slice(&layout, &[10, 20, 30]);
+
+    let inner = storage.checkpoint();
+    storage.write_page_slice(&layout, &[40, 50, 60]);
+
+    // undoing a failed sub-call
+    storage.rollback_to(inner);
+    assert_eq!(vec![10, 20, 30], storage.read_page_slice(&layout));
+
+    let inner = storage.checkpoint();
+    storage.write_page_slice(&layout, &[70, 80, 90]);
+
+    // keeping a succeeded sub-call
+    storage.release(inner);
+    assert_eq!(vec![70, 80, 90], storage.read_page_slice(&layout));
+
+    // the released writes are still undone by rolling back to the outer checkpoint
+    storage.rollback_to(outer);
+    assert_eq!(vec![0, 0, 0], storage.read_page_slice(&layout));
+}
+
+#[test]
+fn app_storage_rollback_to_outer_checkpoint_discards_inner_ones() {
+    let (_addr, _kv, mut storage) = app_storage_init("my-app", 2);
+
+    let layout = PageSliceLayout::new(PageIndex(0), PageOffset(0), 2);
+    storage.write_page_slice(&layout, &[1, 1]);
+
+    let outer = storage.checkpoint();
+    storage.write_page_slice(&layout, &[2, 2]);
+
+    let _inner = storage.checkpoint();
+    storage.write_page_slice(&layout, &[3, 3]);
+
+    storage.rollback_to(outer);
+    assert_eq!(vec![1, 1], storage.read_page_slice(&layout));
+
+    // there are no active checkpoints left, the writes are final
+    storage.write_page_slice(&layout, &[4, 4]);
+    assert_eq!(vec![4, 4], storage.read_page_slice(&layout));
+}
+
+#[test]
+#[should_panic(expected = "checkpoint #1 isn't active")]
+fn app_storage_rollback_to_released_checkpoint() {
+    let (_addr, _kv, mut storage) = app_storage_init("my-app", 2);
+
+    let _outer = storage.checkpoint();
+    let inner = storage.checkpoint();
+
+    storage.release(inner);
+    storage.rollback_to(inner);
+}
+
+#[test]
+#[should_panic(expected = "checkpoint #1 isn't active")]
+fn app_storage_rollback_to_stale_checkpoint() {
+    let (_addr, _kv, mut storage) = app_storage_init("my-app", 2);
+
+    let layout = PageSliceLayout::new(PageIndex(0), PageOffset(0), 2);
+
+    let _outer = storage.checkpoint();
+    let stale = storage.checkpoint();
+    storage.rollback_to(stale);
+
+    // the new checkpoint is taken at the same depth as `stale`
+    let _inner = storage.checkpoint();
+    storage.write_page_slice(&layout, &[1, 1]);
+
+    storage.rollback_to(stale);
+}
+
+#[test]
+#[should_panic(expected = "checkpoint #0 isn't active")]
+fn app_storage_commit_releases_checkpoints() {
+    let (_addr, _kv, mut storage) = app_storage_init("my-app", 2);
+
+    let layout = PageSliceLayout::new(PageIndex(0), PageOffset(0), 2);
+
+    let checkpoint = storage.checkpoint();
+    storage.write_page_slice(&layout, &[1, 1]);
+    storage.commit().unwrap();
+
+    storage.rollback_to(checkpoint);
+}
@@ -3,7 +3,7 @@ macro_rules! assert_no_key {
     ($kv: expr, $key: expr) => {{
         use svm_kv::traits::KVStore;
 
-        assert!($kv.borrow().get(&$key).is_none());
+        assert!($kv.borrow().get(&$key).unwrap().is_none());
     }};
 }
 
@@ -12,7 +12,26 @@ macro_rules! assert_key_value {
     ($kv: expr, $key: expr, $expected: expr) => {{
         use svm_kv::traits::KVStore;
 
-        let actual = $kv.borrow().get(&$key).unwrap();
+        let actual = $kv.borrow().get(&$key).unwrap().unwrap();
+        assert_eq!($expected, &actual[..]);
+    }};
+}
+
+#[macro_export]
+macro_rules! kv_page {
+    ($kv: expr, $key: expr) => {{
+        use svm_kv::traits::KVStore;
+
+        // pages are persisted encoded (see `svm_storage::codec`)
+        let data = $kv.borrow().get(&$key).unwrap().unwrap();
+        svm_storage::codec::decode_page(&data, svm_storage::page::PAGE_SIZE).unwrap()
+    }};
+}
+
+#[macro_export]
+macro_rules! assert_page_value {
+    ($kv: expr, $key: expr, $expected: expr) => {{
+        let actual = kv_page!($kv, $key);
         assert_eq!($expected, &actual[..]);
     }};
 }
@@ -27,7 +46,14 @@ macro_rules! assert_page_content {
 #[macro_export]
 macro_rules! kv_keys_vec {
     ($kv: ident) => {{
-        let keys: Vec<Vec<u8>> = $kv.borrow().keys().map(|key| key.clone()).collect();
+        // pages-hash and states are 32 bytes keys.
+        // The GC bookkeeping entries (see `svm_storage::gc`) are left out.
+        let keys: Vec<Vec<u8>> = $kv
+            .borrow()
+            .keys()
+            .filter(|key| key.len() == 32)
+            .map(|key| key.clone())
+            .collect();
         keys
     }};
 }
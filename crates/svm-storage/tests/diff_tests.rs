@@ -0,0 +1,164 @@
+extern crate svm_storage;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use svm_common::{Address, State};
+use svm_kv::memory::MemKVStore;
+
+use svm_storage::default::DefaultPageHasher;
+use svm_storage::diff::{self, ByteRangeDiff, PageDiff, StateDiff};
+use svm_storage::page::{zero_page, PageIndex, PAGE_SIZE};
+use svm_storage::testing::{app_pages_init, app_pages_open, default_page_hash};
+use svm_storage::traits::{PagesStorage, StateAwarePagesStorage};
+
+fn diff(
+    addr: &Address,
+    old_state: &State,
+    new_state: &State,
+    kv: &Rc<RefCell<MemKVStore>>,
+) -> StateDiff {
+    let kv = kv.borrow();
+
+    diff::diff_states::<_, DefaultPageHasher>(addr, old_state, new_state, 3, PAGE_SIZE, &*kv, &*kv)
+        .unwrap()
+}
+
+#[test]
+fn diff_states_changed_pages() {
+    let page_count = 3;
+    let (addr, kv, mut pages) = app_pages_init("my-app", page_count);
+
+    let mut page0 = zero_page(PAGE_SIZE);
+    page0[10..13].copy_from_slice(&[10, 20, 30]);
+    page0[100] = 40;
+
+    let mut page1 = zero_page(PAGE_SIZE);
+    page1[0] = 50;
+
+    pages.write_page(PageIndex(0), &page0);
+    pages.write_page(PageIndex(1), &page1);
+    pages.commit().unwrap();
+    let old_state = pages.get_state();
+
+    // page `0` is modified, page `1` stays the same, page `2` is a zero page (then, modified)
+    let mut pages = app_pages_open(&addr, &old_state, &kv, page_count);
+
+    let mut new_page0 = page0.clone();
+    new_page0[11] = 21;
+    new_page0[100] = 0;
+
+    let mut page2 = zero_page(PAGE_SIZE);
+    page2[4095] = 60;
+
+    pages.write_page(PageIndex(0), &new_page0);
+    pages.write_page(PageIndex(2), &page2);
+    pages.commit().unwrap();
+    let new_state = pages.get_state();
+
+    let actual = diff(&addr, &old_state, &new_state, &kv);
+
+    let expected = StateDiff {
+        addr: addr.clone(),
+        old_state: old_state.clone(),
+        new_state: new_state.clone(),
+        pages: vec![
+            PageDiff {
+                page_idx: PageIndex(0),
+                old_hash: default_page_hash(&addr, 0, &page0),
+                new_hash: default_page_hash(&addr, 0, &new_page0),
+                ranges: vec![
+                    ByteRangeDiff {
+                        offset: 11,
+                        old: vec![20],
+                        new: vec![21],
+                    },
+                    ByteRangeDiff {
+                        offset: 100,
+                        old: vec![40],
+                        new: vec![0],
+                    },
+                ],
+            },
+            PageDiff {
+                page_idx: PageIndex(2),
+                old_hash: default_page_hash(&addr, 2, &zero_page(PAGE_SIZE)),
+                new_hash: default_page_hash(&addr, 2, &page2),
+                ranges: vec![ByteRangeDiff {
+                    offset: 4095,
+                    old: vec![0],
+                    new: vec![60],
+                }],
+            },
+        ],
+    };
+
+    assert_eq!(expected, actual);
+
+    // the reverse diff
+    let reverse = diff(&addr, &new_state, &old_state, &kv);
+    assert_eq!(2, reverse.pages.len());
+    assert_eq!(vec![60], reverse.pages[1].ranges[0].old);
+    assert_eq!(vec![0], reverse.pages[1].ranges[0].new);
+}
+
+#[test]
+fn diff_states_from_initial_state() {
+    let (addr, kv, mut pages) = app_pages_init("my-app", 3);
+
+    pages.write_page(PageIndex(1), &[10, 20, 30]);
+    pages.commit().unwrap();
+    let state = pages.get_state();
+
+    let actual = diff(&addr, &State::empty(), &state, &kv);
+
+    assert_eq!(1, actual.pages.len());
+    assert_eq!(PageIndex(1), actual.pages[0].page_idx);
+    assert_eq!(
+        vec![ByteRangeDiff {
+            offset: 0,
+            old: vec![0, 0, 0],
+            new: vec![10, 20, 30],
+        }],
+        actual.pages[0].ranges
+    );
+
+    // no changes
+    assert!(diff(&addr, &state, &state, &kv).is_empty());
+}
+
+#[test]
+fn diff_states_missing_state() {
+    let (addr, kv, _pages) = app_pages_init("my-app", 3);
+
+    let kv = kv.borrow();
+    let missing = State::from(&[1; 32][..]);
+
+    let res = diff::diff_states::<_, DefaultPageHasher>(
+        &addr,
+        &State::empty(),
+        &missing,
+        3,
+        PAGE_SIZE,
+        &*kv,
+        &*kv,
+    );
+
+    assert!(res.is_err());
+}
+
+#[test]
+fn diff_states_serialization() {
+    let (addr, kv, mut pages) = app_pages_init("my-app", 3);
+
+    pages.write_page(PageIndex(0), &[10, 20, 30]);
+    pages.commit().unwrap();
+    let state = pages.get_state();
+
+    let diff = diff(&addr, &State::empty(), &state, &kv);
+
+    let json = serde_json::to_string(&diff).unwrap();
+    let decoded: StateDiff = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(diff, decoded);
+}
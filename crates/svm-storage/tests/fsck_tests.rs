@@ -0,0 +1,188 @@
+extern crate svm_storage;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use svm_common::{Address, State};
+use svm_kv::{memory::MemKVStore, traits::KVStore};
+
+use svm_storage::codec::PageCodec;
+use svm_storage::default::{DefaultPageHasher, DefaultStateHasher};
+use svm_storage::fsck::{self, FsckIssue, FsckReport};
+use svm_storage::page::{PageIndex, PAGE_SIZE};
+use svm_storage::testing::{
+    app_pages_init, app_pages_open, concat_pages_hash_with_zero_tail, default_page_hash,
+};
+use svm_storage::traits::{PagesStorage, StateAwarePagesStorage};
+
+fn check(
+    addr: &Address,
+    state: &State,
+    page_count: u16,
+    kv: &Rc<RefCell<MemKVStore>>,
+) -> FsckReport {
+    let kv = kv.borrow();
+
+    fsck::check_app_state::<_, DefaultPageHasher, DefaultStateHasher>(
+        addr, state, page_count, PAGE_SIZE, &*kv, &*kv,
+    )
+    .unwrap()
+}
+
+/// Commits a state whose pages `0` and `1` are non-zero pages (page `2` is a zero page).
+fn commit_state(page_count: u16) -> (Address, Rc<RefCell<MemKVStore>>, State) {
+    let (addr, kv, mut pages) = app_pages_init("my-app", page_count);
+
+    pages.write_page(PageIndex(0), &[10, 20, 30]);
+    pages.write_page(PageIndex(1), &[40, 50, 60]);
+    pages.commit().unwrap();
+
+    let state = pages.get_state();
+
+    (addr, kv, state)
+}
+
+#[test]
+fn fsck_intact_state() {
+    let (addr, kv, state) = commit_state(3);
+
+    let report = check(&addr, &state, 3, &kv);
+
+    assert!(report.is_ok());
+    assert_eq!(2, report.pages_checked);
+
+    // the same check through the pages-storage
+    let pages = app_pages_open(&addr, &state, &kv, 3);
+    assert_eq!(report, pages.check_integrity().unwrap());
+}
+
+#[test]
+fn fsck_empty_state() {
+    let (addr, kv, _pages) = app_pages_init("my-app", 3);
+
+    let report = check(&addr, &State::empty(), 3, &kv);
+
+    assert!(report.is_ok());
+    assert_eq!(0, report.pages_checked);
+}
+
+#[test]
+fn fsck_missing_state() {
+    let (addr, kv, _state) = commit_state(3);
+
+    let report = check(&addr, &State::from(&[1; 32][..]), 3, &kv);
+
+    assert_eq!(vec![FsckIssue::MissingState], report.issues);
+}
+
+#[test]
+fn fsck_missing_page() {
+    let (addr, kv, state) = commit_state(3);
+
+    let ph1 = default_page_hash(&addr, 1, &[40, 50, 60]);
+    kv.borrow_mut().delete(&[&ph1.0]).unwrap();
+
+    let report = check(&addr, &state, 3, &kv);
+
+    let expected = FsckIssue::MissingPage {
+        page_idx: PageIndex(1),
+        ph: ph1,
+    };
+    assert_eq!(vec![expected], report.issues);
+    assert_eq!(2, report.pages_checked);
+}
+
+#[test]
+fn fsck_corrupt_pages() {
+    let (addr, kv, state) = commit_state(3);
+
+    let ph0 = default_page_hash(&addr, 0, &[10, 20, 30]);
+    let ph1 = default_page_hash(&addr, 1, &[40, 50, 60]);
+
+    // page `0` content is altered while page `1` can't be decoded
+    let altered = PageCodec::NoOp.encode(&[10, 20, 31], PAGE_SIZE);
+    let garbage = [255, 1, 2];
+    kv.borrow_mut()
+        .store(&[(&ph0.0, &altered), (&ph1.0, &garbage)])
+        .unwrap();
+
+    let report = check(&addr, &state, 3, &kv);
+    assert_eq!(2, report.issues.len());
+
+    match &report.issues[0] {
+        FsckIssue::CorruptPage { page_idx, ph, .. } => {
+            assert_eq!(PageIndex(0), *page_idx);
+            assert_eq!(ph0, *ph);
+        }
+        issue => panic!("unexpected issue: {}", issue),
+    }
+
+    match &report.issues[1] {
+        FsckIssue::CorruptPage { page_idx, ph, .. } => {
+            assert_eq!(PageIndex(1), *page_idx);
+            assert_eq!(ph1, *ph);
+        }
+        issue => panic!("unexpected issue: {}", issue),
+    }
+}
+
+#[test]
+fn fsck_state_entry_mismatch() {
+    let (addr, kv, state) = commit_state(3);
+
+    // the state entry lists page `1` as a zero page
+    let ph0 = default_page_hash(&addr, 0, &[10, 20, 30]);
+    let entry = concat_pages_hash_with_zero_tail(&[ph0]);
+    kv.borrow_mut()
+        .store(&[(state.as_slice(), &entry)])
+        .unwrap();
+
+    let report = check(&addr, &state, 3, &kv);
+
+    match &report.issues[..] {
+        [FsckIssue::StateMismatch { actual }] => assert_ne!(state, *actual),
+        issues => panic!("unexpected issues: {:?}", issues),
+    }
+}
+
+#[test]
+fn fsck_invalid_state_entry() {
+    let (addr, kv, state) = commit_state(3);
+
+    // the state entry lists more pages than the app has
+    let report = check(&addr, &state, 1, &kv);
+
+    match &report.issues[..] {
+        [FsckIssue::InvalidStateEntry(..)] => (),
+        issues => panic!("unexpected issues: {:?}", issues),
+    }
+
+    // a malformed state entry
+    kv.borrow_mut()
+        .store(&[(state.as_slice(), &[1, 2, 3])])
+        .unwrap();
+
+    let report = check(&addr, &state, 3, &kv);
+
+    match &report.issues[..] {
+        [FsckIssue::InvalidStateEntry(..)] => (),
+        issues => panic!("unexpected issues: {:?}", issues),
+    }
+}
+
+#[test]
+fn fsck_opening_a_missing_state_defers_the_error() {
+    let (addr, kv, _state) = commit_state(3);
+    let missing = State::from(&[1; 32][..]);
+
+    let mut pages = app_pages_open(&addr, &missing, &kv, 3);
+
+    assert_eq!(
+        vec![FsckIssue::MissingState],
+        pages.check_integrity().unwrap().issues
+    );
+
+    // the pages are read as zero pages, but nothing can be committed
+    assert_eq!(None, pages.read_page(PageIndex(0)));
+    assert!(pages.commit().is_err());
+}
@@ -0,0 +1,251 @@
+extern crate svm_storage;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use svm_common::{Address, State};
+use svm_kv::{
+    memory::MemKVStore,
+    traits::{KVStore, Overlay},
+    unit_of_work::UnitOfWork,
+};
+
+use svm_storage::gc::{self, PruneStats, Retain};
+use svm_storage::overlay::OverlayAppPages;
+use svm_storage::page::{PageIndex, PAGE_SIZE};
+use svm_storage::testing::{app_pages_init, app_pages_open, default_page_hash};
+use svm_storage::traits::{PagesStorage, StateAwarePagesStorage};
+
+mod asserts;
+
+/// Commits `count` states. The `ith` state modifies page `0` to `[i]` while page `1` stays the same.
+fn commit_states(
+    addr: &Address,
+    kv: &Rc<RefCell<MemKVStore>>,
+    page_count: u16,
+    count: u8,
+) -> Vec<State> {
+    let mut state = State::empty();
+    let mut states = Vec::new();
+
+    for i in 0..count {
+        let mut pages = app_pages_open(addr, &state, kv, page_count);
+
+        pages.write_page(PageIndex(0), &[i]);
+
+        if i == 0 {
+            pages.write_page(PageIndex(1), &[100]);
+        }
+
+        pages.commit().unwrap();
+
+        state = pages.get_state();
+        states.push(state.clone());
+    }
+
+    states
+}
+
+#[test]
+fn gc_commit_tracks_app_states() {
+    let (addr, kv, _pages) = app_pages_init("my-app", 3);
+
+    let states = commit_states(&addr, &kv, 3, 3);
+
+    assert_eq!(states, gc::app_states(&*kv.borrow(), &addr).unwrap());
+}
+
+#[test]
+fn gc_prune_keep_last_states() {
+    let page_count = 3;
+    let (addr, kv, _pages) = app_pages_init("my-app", page_count);
+
+    let states = commit_states(&addr, &kv, page_count, 4);
+
+    let stats = gc::prune_app(&addr, &kv, &kv, &Retain::Last(2)).unwrap();
+
+    // states #0 and #1 are deleted, so are their versions of page `0`
+    let expected = PruneStats {
+        states_deleted: 2,
+        pages_deleted: 2,
+    };
+    assert_eq!(expected, stats);
+    assert_eq!(
+        states[2..].to_vec(),
+        gc::app_states(&*kv.borrow(), &addr).unwrap()
+    );
+
+    assert_no_key!(kv, states[0].bytes());
+    assert_no_key!(kv, states[1].bytes());
+    assert_no_key!(kv, default_page_hash(&addr, 0, &[0]).0);
+    assert_no_key!(kv, default_page_hash(&addr, 0, &[1]).0);
+
+    // page `1` is still referenced by the retained states
+    assert_page_value!(kv, default_page_hash(&addr, 1, &[100]).0, [100]);
+
+    // the retained states are intact
+    for (i, state) in states[2..].iter().enumerate() {
+        let mut pages = app_pages_open(&addr, state, &kv, page_count);
+
+        assert_eq!(Some(vec![i as u8 + 2]), pages.read_page(PageIndex(0)));
+        assert_eq!(Some(vec![100]), pages.read_page(PageIndex(1)));
+    }
+}
+
+#[test]
+fn gc_prune_keep_live_states() {
+    let page_count = 2;
+    let (addr, kv, _pages) = app_pages_init("my-app", page_count);
+
+    let states = commit_states(&addr, &kv, page_count, 3);
+
+    let live = vec![states[0].clone(), states[2].clone()];
+    let stats = gc::prune_app(&addr, &kv, &kv, &Retain::States(live.clone())).unwrap();
+
+    let expected = PruneStats {
+        states_deleted: 1,
+        pages_deleted: 1,
+    };
+    assert_eq!(expected, stats);
+    assert_eq!(live, gc::app_states(&*kv.borrow(), &addr).unwrap());
+
+    assert_no_key!(kv, states[1].bytes());
+    assert_no_key!(kv, default_page_hash(&addr, 0, &[1]).0);
+
+    let mut pages = app_pages_open(&addr, &states[0], &kv, page_count);
+    assert_eq!(Some(vec![0]), pages.read_page(PageIndex(0)));
+}
+
+#[test]
+fn gc_prune_live_states_always_keep_the_current_state() {
+    let page_count = 2;
+    let (addr, kv, _pages) = app_pages_init("my-app", page_count);
+
+    let states = commit_states(&addr, &kv, page_count, 3);
+
+    let live = vec![states[0].clone()];
+    let stats = gc::prune_app(&addr, &kv, &kv, &Retain::States(live)).unwrap();
+
+    let expected = PruneStats {
+        states_deleted: 1,
+        pages_deleted: 1,
+    };
+    assert_eq!(expected, stats);
+
+    let kept = vec![states[0].clone(), states[2].clone()];
+    assert_eq!(kept, gc::app_states(&*kv.borrow(), &addr).unwrap());
+
+    let mut pages = app_pages_open(&addr, &states[2], &kv, page_count);
+    assert_eq!(Some(vec![2]), pages.read_page(PageIndex(0)));
+    assert_eq!(Some(vec![100]), pages.read_page(PageIndex(1)));
+}
+
+#[test]
+fn gc_prune_nothing_to_delete() {
+    let page_count = 2;
+    let (addr, kv, _pages) = app_pages_init("my-app", page_count);
+
+    let states = commit_states(&addr, &kv, page_count, 2);
+
+    let stats = gc::prune_app(&addr, &kv, &kv, &Retain::Last(5)).unwrap();
+
+    assert_eq!(PruneStats::default(), stats);
+    assert_eq!(states, gc::app_states(&*kv.borrow(), &addr).unwrap());
+}
+
+#[test]
+fn gc_recommitting_an_existing_state_makes_it_the_most_recent() {
+    let page_count = 2;
+    let (addr, kv, _pages) = app_pages_init("my-app", page_count);
+
+    let states = commit_states(&addr, &kv, page_count, 2);
+
+    // reverting page `0` back to its content under the first state
+    let mut pages = app_pages_open(&addr, &states[1], &kv, page_count);
+    pages.write_page(PageIndex(0), &[0]);
+    pages.commit().unwrap();
+    assert_eq!(states[0], pages.get_state());
+
+    let expected = vec![states[1].clone(), states[0].clone()];
+    assert_eq!(expected, gc::app_states(&*kv.borrow(), &addr).unwrap());
+
+    gc::prune_app(&addr, &kv, &kv, &Retain::Last(1)).unwrap();
+    assert_eq!(
+        vec![states[0].clone()],
+        gc::app_states(&*kv.borrow(), &addr).unwrap()
+    );
+
+    let mut pages = app_pages_open(&addr, &states[0], &kv, page_count);
+    assert_eq!(Some(vec![0]), pages.read_page(PageIndex(0)));
+    assert_eq!(Some(vec![100]), pages.read_page(PageIndex(1)));
+}
+
+#[test]
+fn gc_prune_deletes_superseded_history_entries() {
+    let page_count = 2;
+    let (addr, kv, _pages) = app_pages_init("my-app", page_count);
+
+    let states = commit_states(&addr, &kv, page_count, 2);
+
+    // re-committing the first state (so its first history entry is superseded)
+    let mut pages = app_pages_open(&addr, &states[1], &kv, page_count);
+    pages.write_page(PageIndex(0), &[0]);
+    pages.commit().unwrap();
+
+    // each commit has its own history entry
+    assert_eq!(3, kv.borrow().iter_prefix(b"app-states:").unwrap().len());
+
+    gc::prune_app(&addr, &kv, &kv, &Retain::Last(2)).unwrap();
+
+    // nothing is deleted but the superseded entry
+    let expected = vec![states[1].clone(), states[0].clone()];
+    assert_eq!(expected, gc::app_states(&*kv.borrow(), &addr).unwrap());
+    assert_eq!(2, kv.borrow().iter_prefix(b"app-states:").unwrap().len());
+}
+
+#[test]
+fn gc_prune_with_unit_of_work_stores() {
+    let page_count = 2;
+    let addr = Address::of("my-app");
+
+    let backend = Rc::new(RefCell::new(MemKVStore::new()));
+    let mut uow = UnitOfWork::new(Rc::clone(&backend));
+
+    let mut state = State::empty();
+    let mut states = Vec::new();
+
+    for i in 0..3 {
+        let mut pages = OverlayAppPages::new_with_unit_of_work(
+            addr.clone(),
+            &mut uow,
+            "pages",
+            "app-states",
+            state.clone(),
+            page_count,
+            PAGE_SIZE,
+        )
+        .unwrap();
+
+        pages.write_page(PageIndex(0), &[i]);
+        pages.commit().unwrap();
+        uow.commit().unwrap();
+
+        state = pages.get_state();
+        states.push(state.clone());
+    }
+
+    let kv = uow.open_store("pages");
+    let states_kv = uow.open_store("app-states");
+
+    let stats = gc::prune_app(&addr, &kv, &states_kv, &Retain::Last(1)).unwrap();
+    uow.commit().unwrap();
+
+    let expected = PruneStats {
+        states_deleted: 2,
+        pages_deleted: 2,
+    };
+    assert_eq!(expected, stats);
+
+    let expected_keys = vec![states[2].bytes(), default_page_hash(&addr, 0, &[2]).0];
+    assert_same_keys!(expected_keys, kv_keys_vec!(backend));
+}
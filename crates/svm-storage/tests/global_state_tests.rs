@@ -0,0 +1,238 @@
+extern crate svm_storage;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use svm_common::{Address, DefaultKeyHasher, State};
+use svm_kv::{
+    faulty::{Fault, FaultyKV},
+    memory::MemKVStore,
+    overlay::OverlayKV,
+    traits::Overlay,
+};
+
+use svm_storage::memory::MemGlobalState;
+use svm_storage::overlay::OverlayGlobalState;
+use svm_storage::proof::ProofError;
+use svm_storage::traits::GlobalStateStorage;
+use svm_storage::GlobalState;
+
+fn global_state_init() -> (Rc<RefCell<MemKVStore>>, MemGlobalState) {
+    let kv = Rc::new(RefCell::new(MemKVStore::new()));
+    let gs = MemGlobalState::new(Rc::clone(&kv), State::empty());
+
+    (kv, gs)
+}
+
+#[test]
+fn global_state_empty() {
+    let (_kv, gs) = global_state_init();
+
+    let addr = Address::of("my-app");
+
+    assert_eq!(None, gs.get_app_state(&addr).unwrap());
+
+    let proof = gs.prove_app_state(&addr).unwrap();
+    assert_eq!(None, proof.state);
+    assert!(proof.siblings.iter().all(|s| s.is_none()));
+
+    assert!(proof.verify::<DefaultKeyHasher>(&gs.get_root()));
+    assert!(proof.verify::<DefaultKeyHasher>(&State::empty()));
+}
+
+#[test]
+fn global_state_set_app_state_and_commit() {
+    let (_kv, mut gs) = global_state_init();
+
+    let app1 = Address::of("app-1");
+    let app2 = Address::of("app-2");
+    let empty_root = gs.get_root();
+
+    gs.set_app_state(&app1, &State::from(0x10_20_30_40));
+
+    // uncommitted changes are visible, but don't change the root
+    assert_eq!(
+        Some(State::from(0x10_20_30_40)),
+        gs.get_app_state(&app1).unwrap()
+    );
+    assert_eq!(empty_root, gs.get_root());
+
+    let root1 = gs.commit().unwrap();
+    assert_ne!(empty_root, root1);
+    assert_eq!(root1, gs.get_root());
+
+    gs.set_app_state(&app2, &State::from(0x50_60_70_80));
+    let root2 = gs.commit().unwrap();
+    assert_ne!(root1, root2);
+
+    assert_eq!(
+        Some(State::from(0x10_20_30_40)),
+        gs.get_app_state(&app1).unwrap()
+    );
+    assert_eq!(
+        Some(State::from(0x50_60_70_80)),
+        gs.get_app_state(&app2).unwrap()
+    );
+}
+
+#[test]
+fn global_state_root_is_independent_of_updates_order() {
+    let (_kv1, mut gs1) = global_state_init();
+    let (_kv2, mut gs2) = global_state_init();
+
+    let apps: Vec<(Address, State)> = (0..10)
+        .map(|i| (Address::from(i * 1000), State::from(i + 1)))
+        .collect();
+
+    // a single commit
+    for (addr, state) in apps.iter() {
+        gs1.set_app_state(addr, state);
+    }
+    gs1.commit().unwrap();
+
+    // a commit per app (in reverse order)
+    for (addr, state) in apps.iter().rev() {
+        gs2.set_app_state(addr, state);
+        gs2.commit().unwrap();
+    }
+
+    assert_eq!(gs1.get_root(), gs2.get_root());
+}
+
+#[test]
+fn global_state_reopen() {
+    let (kv, mut gs) = global_state_init();
+
+    let app = Address::of("my-app");
+
+    gs.set_app_state(&app, &State::from(0x10_20_30_40));
+    let old_root = gs.commit().unwrap();
+
+    gs.set_app_state(&app, &State::from(0x50_60_70_80));
+    let new_root = gs.commit().unwrap();
+
+    let gs = MemGlobalState::new(Rc::clone(&kv), old_root.clone());
+    assert_eq!(
+        Some(State::from(0x10_20_30_40)),
+        gs.get_app_state(&app).unwrap()
+    );
+
+    let gs = MemGlobalState::open_latest(Rc::clone(&kv)).unwrap();
+    assert_eq!(new_root, gs.get_root());
+    assert_eq!(
+        Some(State::from(0x50_60_70_80)),
+        gs.get_app_state(&app).unwrap()
+    );
+}
+
+#[test]
+fn global_state_reload_after_overlay_discard() {
+    let kv = Rc::new(RefCell::new(MemKVStore::new()));
+    let overlay = Rc::new(RefCell::new(OverlayKV::new(Rc::clone(&kv))));
+    let mut gs = OverlayGlobalState::open_latest(Rc::clone(&overlay)).unwrap();
+
+    let app = Address::of("my-app");
+
+    gs.set_app_state(&app, &State::from(0x10_20_30_40));
+    let root = gs.commit().unwrap();
+    overlay.borrow_mut().commit().unwrap();
+
+    // speculative changes (buffered by the overlay only)
+    gs.set_app_state(&app, &State::from(0x50_60_70_80));
+    let speculative_root = gs.commit().unwrap();
+
+    assert_ne!(root, speculative_root);
+    assert_eq!(
+        root,
+        MemGlobalState::open_latest(Rc::clone(&kv))
+            .unwrap()
+            .get_root()
+    );
+
+    overlay.borrow_mut().discard();
+    gs.reload().unwrap();
+
+    assert_eq!(root, gs.get_root());
+    assert_eq!(
+        Some(State::from(0x10_20_30_40)),
+        gs.get_app_state(&app).unwrap()
+    );
+}
+
+#[test]
+fn global_state_app_state_proofs() {
+    let (_kv, mut gs) = global_state_init();
+
+    let app1 = Address::of("app-1");
+    let app2 = Address::of("app-2");
+    let app3 = Address::of("app-3");
+
+    gs.set_app_state(&app1, &State::from(0x10_20_30_40));
+    gs.set_app_state(&app2, &State::from(0x50_60_70_80));
+    let root = gs.commit().unwrap();
+
+    let proof = gs.prove_app_state(&app1).unwrap();
+    assert_eq!(Some(State::from(0x10_20_30_40)), proof.state);
+    assert!(proof.verify::<DefaultKeyHasher>(&root));
+
+    let proof = gs.prove_app_state(&app2).unwrap();
+    assert!(proof.verify::<DefaultKeyHasher>(&root));
+
+    // non-inclusion
+    let proof = gs.prove_app_state(&app3).unwrap();
+    assert_eq!(None, proof.state);
+    assert!(proof.verify::<DefaultKeyHasher>(&root));
+
+    // tampered state
+    let mut proof = gs.prove_app_state(&app1).unwrap();
+    proof.state = Some(State::from(0x50_60_70_80));
+    assert!(!proof.verify::<DefaultKeyHasher>(&root));
+
+    // stale root
+    gs.set_app_state(&app1, &State::from(0x11_22_33_44));
+    let new_root = gs.commit().unwrap();
+
+    let stale_proof = {
+        let mut proof = gs.prove_app_state(&app1).unwrap();
+        proof.state = Some(State::from(0x10_20_30_40));
+        proof
+    };
+    assert!(!stale_proof.verify::<DefaultKeyHasher>(&new_root));
+}
+
+#[test]
+fn global_state_prove_requires_committed_changes() {
+    let (_kv, mut gs) = global_state_init();
+
+    let app = Address::of("my-app");
+    gs.set_app_state(&app, &State::from(0x10_20_30_40));
+
+    assert_eq!(Err(ProofError::PendingChanges), gs.prove_app_state(&app));
+
+    gs.commit().unwrap();
+    assert!(gs.prove_app_state(&app).is_ok());
+}
+
+#[test]
+fn global_state_failed_commit_drops_pending_app_states() {
+    let kv = Rc::new(RefCell::new(FaultyKV::new(MemKVStore::new())));
+    let mut gs: GlobalState<_, DefaultKeyHasher> = GlobalState::new(Rc::clone(&kv), State::empty());
+
+    let app1 = Address::of("app-1");
+    let app2 = Address::of("app-2");
+
+    kv.borrow_mut().inject(0, Fault::Fail);
+
+    gs.set_app_state(&app1, &State::from(0x10_20_30_40));
+    assert!(gs.commit().is_err());
+    assert_eq!(None, gs.get_app_state(&app1).unwrap());
+
+    gs.set_app_state(&app2, &State::from(0x50_60_70_80));
+    gs.commit().unwrap();
+
+    assert_eq!(None, gs.get_app_state(&app1).unwrap());
+    assert_eq!(
+        Some(State::from(0x50_60_70_80)),
+        gs.get_app_state(&app2).unwrap()
+    );
+}
@@ -0,0 +1,149 @@
+use svm_storage::default::DefaultPageCache;
+use svm_storage::map::{MapError, MapRegion, MAP_MAX_VALUE_SIZE};
+use svm_storage::page::PageIndex;
+use svm_storage::testing::{app_pages_init_with_page_size, app_pages_open, app_storage_init};
+use svm_storage::AppStorage;
+
+fn region(first_page: u16, page_count: u16) -> MapRegion {
+    MapRegion {
+        first_page: PageIndex(first_page),
+        page_count,
+    }
+}
+
+#[test]
+fn map_set_get_and_remove() {
+    let (_addr, _kv, storage) = app_storage_init("my-app", 3);
+    let mut storage = storage.with_map_region(region(1, 2));
+    let mut map = storage.map().unwrap();
+
+    assert_eq!(None, map.get(b"alice"));
+
+    map.set(b"alice", &[10, 20]).unwrap();
+    map.set(b"bob", &[30]).unwrap();
+
+    assert_eq!(Some(vec![10, 20]), map.get(b"alice"));
+    assert_eq!(Some(vec![30]), map.get(b"bob"));
+
+    // overriding a value
+    map.set(b"alice", &[40, 50, 60]).unwrap();
+    assert_eq!(Some(vec![40, 50, 60]), map.get(b"alice"));
+
+    assert!(map.remove(b"alice"));
+    assert!(!map.remove(b"alice"));
+
+    assert_eq!(None, map.get(b"alice"));
+    assert_eq!(Some(vec![30]), map.get(b"bob"));
+}
+
+#[test]
+fn map_invalid_values() {
+    let (_addr, _kv, storage) = app_storage_init("my-app", 1);
+    let mut storage = storage.with_map_region(region(0, 1));
+    let mut map = storage.map().unwrap();
+
+    assert_eq!(Err(MapError::EmptyValue), map.set(b"alice", &[]));
+
+    let value = vec![0xFF; MAP_MAX_VALUE_SIZE + 1];
+    assert_eq!(
+        Err(MapError::ValueTooLarge(MAP_MAX_VALUE_SIZE + 1)),
+        map.set(b"alice", &value)
+    );
+
+    let value = vec![0xFF; MAP_MAX_VALUE_SIZE];
+    map.set(b"alice", &value).unwrap();
+    assert_eq!(Some(value), map.get(b"alice"));
+}
+
+#[test]
+fn map_collisions_are_resolved() {
+    let (_addr, _kv, pages) = app_pages_init_with_page_size("my-app", 1, 256);
+
+    // a page of `256` bytes holds `4` slots, so each additional key collides
+    let storage = AppStorage::new(Box::new(DefaultPageCache::new(pages, 1)));
+    let mut storage = storage.with_map_region(region(0, 1));
+    let mut map = storage.map().unwrap();
+
+    assert_eq!(4, map.slot_count());
+
+    for i in 0..4u8 {
+        map.set(&[i], &[i + 100]).unwrap();
+    }
+
+    assert_eq!(Err(MapError::MapFull), map.set(&[4], &[104]));
+
+    for i in 0..4u8 {
+        assert_eq!(Some(vec![i + 100]), map.get(&[i]));
+    }
+
+    // removing a key leaves the other keys reachable and its slot vacant
+    assert!(map.remove(&[1]));
+
+    for i in &[0u8, 2, 3] {
+        assert_eq!(Some(vec![i + 100]), map.get(&[*i]));
+    }
+
+    map.set(&[4], &[104]).unwrap();
+    assert_eq!(Some(vec![104]), map.get(&[4]));
+    assert_eq!(None, map.get(&[1]));
+}
+
+#[test]
+fn map_is_part_of_the_app_state() {
+    let (addr, kv, storage) = app_storage_init("my-app", 3);
+    let mut storage = storage.with_map_region(region(1, 2));
+
+    let empty_state = storage.commit().unwrap();
+
+    storage.map().unwrap().set(b"alice", &[10, 20]).unwrap();
+    let state = storage.commit().unwrap();
+
+    assert_ne!(empty_state, state);
+
+    let pages = app_pages_open(&addr, &state, &kv, 3);
+    let storage = AppStorage::new(Box::new(DefaultPageCache::new(pages, 3)));
+    let mut storage = storage.with_map_region(region(1, 2));
+
+    assert_eq!(Some(vec![10, 20]), storage.map().unwrap().get(b"alice"));
+
+    // removing the only key doesn't restore the empty state (a tombstone is left behind)
+    assert!(storage.map().unwrap().remove(b"alice"));
+    assert_eq!(None, storage.map().unwrap().get(b"alice"));
+}
+
+#[test]
+fn map_changes_are_undone_by_rollback() {
+    let (_addr, _kv, storage) = app_storage_init("my-app", 1);
+    let mut storage = storage.with_map_region(region(0, 1));
+
+    storage.map().unwrap().set(b"alice", &[10]).unwrap();
+
+    let checkpoint = storage.checkpoint();
+
+    storage.map().unwrap().set(b"alice", &[20]).unwrap();
+    storage.map().unwrap().set(b"bob", &[30]).unwrap();
+
+    storage.rollback_to(checkpoint);
+
+    assert_eq!(Some(vec![10]), storage.map().unwrap().get(b"alice"));
+    assert_eq!(None, storage.map().unwrap().get(b"bob"));
+}
+
+#[test]
+fn map_of_app_storage_without_map_region() {
+    let (_addr, _kv, mut storage) = app_storage_init("my-app", 1);
+
+    assert!(!storage.is_map_page(PageIndex(0)));
+    assert_eq!(Some(MapError::NoMapRegion), storage.map().err());
+}
+
+#[test]
+fn map_region_pages() {
+    let (_addr, _kv, storage) = app_storage_init("my-app", 4);
+    let storage = storage.with_map_region(region(1, 2));
+
+    assert!(!storage.is_map_page(PageIndex(0)));
+    assert!(storage.is_map_page(PageIndex(1)));
+    assert!(storage.is_map_page(PageIndex(2)));
+    assert!(!storage.is_map_page(PageIndex(3)));
+}
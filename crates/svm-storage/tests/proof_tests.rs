@@ -0,0 +1,170 @@
+extern crate svm_storage;
+
+use svm_storage::default::{DefaultPageHasher, DefaultStateHasher};
+use svm_storage::page::{PageIndex, PageOffset, PageSliceLayout, PAGE_SIZE};
+use svm_storage::proof::ProofError;
+use svm_storage::testing::{app_pages_init, app_pages_init_with_page_size, app_pages_open};
+use svm_storage::traits::{PagesStorage, StateAwarePagesStorage};
+
+fn page_with(bytes: &[u8]) -> Vec<u8> {
+    let mut page = svm_storage::page::zero_page(PAGE_SIZE);
+    page[..bytes.len()].copy_from_slice(bytes);
+
+    page
+}
+
+#[test]
+fn page_proof_of_modified_and_zero_pages() {
+    let page_count = 5;
+    let (addr, kv, mut pages) = app_pages_init("my-app", page_count);
+
+    pages.write_page(PageIndex(1), &page_with(&[10, 20, 30]));
+    pages.write_page(PageIndex(4), &page_with(&[40, 50, 60]));
+    pages.commit().unwrap();
+
+    let state = pages.get_state();
+
+    for page_idx in 0..page_count {
+        let proof = pages.prove_page(PageIndex(page_idx)).unwrap();
+
+        assert_eq!(PageIndex(page_idx), proof.page_idx);
+        assert!(proof.siblings.len() <= 3);
+        assert!(proof.verify::<DefaultPageHasher, DefaultStateHasher>(&addr, &state));
+    }
+
+    let proof = pages.prove_page(PageIndex(1)).unwrap();
+    assert_eq!(page_with(&[10, 20, 30]), proof.page);
+
+    let proof = pages.prove_page(PageIndex(2)).unwrap();
+    assert_eq!(svm_storage::page::zero_page(PAGE_SIZE), proof.page);
+
+    // reopening the app pages at the same state
+    let pages = app_pages_open(&addr, &state, &kv, page_count);
+    let proof = pages.prove_page(PageIndex(4)).unwrap();
+
+    assert_eq!(page_with(&[40, 50, 60]), proof.page);
+    assert!(proof.verify::<DefaultPageHasher, DefaultStateHasher>(&addr, &state));
+}
+
+#[test]
+fn page_proof_rejects_tampered_content() {
+    let (addr, _kv, mut pages) = app_pages_init("my-app", 3);
+
+    pages.write_page(PageIndex(0), &page_with(&[10, 20, 30]));
+    pages.commit().unwrap();
+
+    let state = pages.get_state();
+    let mut proof = pages.prove_page(PageIndex(0)).unwrap();
+
+    proof.page[0] = 11;
+    assert!(!proof.verify::<DefaultPageHasher, DefaultStateHasher>(&addr, &state));
+}
+
+#[test]
+fn page_proof_rejects_other_state_or_app() {
+    let (addr, kv, mut pages) = app_pages_init("my-app", 3);
+
+    pages.write_page(PageIndex(0), &page_with(&[10, 20, 30]));
+    pages.commit().unwrap();
+    let old_state = pages.get_state();
+
+    let mut pages = app_pages_open(&addr, &old_state, &kv, 3);
+    pages.write_page(PageIndex(0), &page_with(&[40, 50, 60]));
+    pages.commit().unwrap();
+    let new_state = pages.get_state();
+
+    let proof = pages.prove_page(PageIndex(0)).unwrap();
+
+    assert!(proof.verify::<DefaultPageHasher, DefaultStateHasher>(&addr, &new_state));
+    assert!(!proof.verify::<DefaultPageHasher, DefaultStateHasher>(&addr, &old_state));
+
+    let other_addr = svm_common::Address::of("other-app");
+    assert!(!proof.verify::<DefaultPageHasher, DefaultStateHasher>(&other_addr, &new_state));
+}
+
+#[test]
+fn page_proof_of_page_slice() {
+    let (addr, _kv, mut pages) = app_pages_init("my-app", 4);
+
+    pages.write_page(PageIndex(2), &page_with(&[0, 0, 10, 20, 30]));
+    pages.commit().unwrap();
+
+    let state = pages.get_state();
+    let proof = pages.prove_page(PageIndex(2)).unwrap();
+
+    let layout = PageSliceLayout::new(PageIndex(2), PageOffset(2), 3);
+    assert!(proof.verify_slice::<DefaultPageHasher, DefaultStateHasher>(
+        &addr,
+        &state,
+        &layout,
+        &[10, 20, 30]
+    ));
+
+    assert!(
+        !proof.verify_slice::<DefaultPageHasher, DefaultStateHasher>(
+            &addr,
+            &state,
+            &layout,
+            &[10, 20, 31]
+        )
+    );
+
+    let other_layout = PageSliceLayout::new(PageIndex(1), PageOffset(2), 3);
+    assert!(
+        !proof.verify_slice::<DefaultPageHasher, DefaultStateHasher>(
+            &addr,
+            &state,
+            &other_layout,
+            &[10, 20, 30]
+        )
+    );
+}
+
+#[test]
+fn page_proof_of_out_of_range_page() {
+    let (_addr, _kv, mut pages) = app_pages_init("my-app", 3);
+    pages.commit().unwrap();
+
+    let expected = ProofError::PageOutOfRange {
+        page_idx: PageIndex(3),
+        page_count: 3,
+    };
+
+    assert_eq!(Err(expected), pages.prove_page(PageIndex(3)));
+}
+
+#[test]
+fn page_proof_requires_no_dirty_pages() {
+    let (addr, _kv, mut pages) = app_pages_init("my-app", 3);
+
+    pages.write_page(PageIndex(0), &page_with(&[10, 20, 30]));
+
+    assert_eq!(
+        Err(ProofError::PendingChanges),
+        pages.prove_page(PageIndex(0))
+    );
+
+    // committing the dirty pages makes the page provable
+    pages.commit().unwrap();
+
+    let proof = pages.prove_page(PageIndex(0)).unwrap();
+    assert!(proof.verify::<DefaultPageHasher, DefaultStateHasher>(&addr, &pages.get_state()));
+}
+
+#[test]
+fn page_proof_of_non_default_page_size() {
+    let (addr, _kv, mut pages) = app_pages_init_with_page_size("my-app", 3, 512);
+
+    pages.write_page(PageIndex(1), &[10, 20, 30]);
+    pages.commit().unwrap();
+
+    let state = pages.get_state();
+    let mut proof = pages.prove_page(PageIndex(1)).unwrap();
+
+    assert_eq!(512, proof.page_size);
+    assert!(proof.verify::<DefaultPageHasher, DefaultStateHasher>(&addr, &state));
+
+    // the page size is part of the state
+    proof.page_size = PAGE_SIZE;
+    assert!(!proof.verify::<DefaultPageHasher, DefaultStateHasher>(&addr, &state));
+}
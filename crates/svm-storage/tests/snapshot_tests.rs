@@ -0,0 +1,226 @@
+extern crate svm_storage;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use svm_common::{Address, State};
+use svm_kv::memory::MemKVStore;
+
+use svm_storage::codec::PageCodec;
+use svm_storage::default::{DefaultPageHasher, DefaultStateHasher, FlatStateHasher};
+use svm_storage::page::{zero_page, PageIndex, PAGE_SIZE};
+use svm_storage::snapshot::{self, SnapshotError, SnapshotHeader};
+use svm_storage::testing::{
+    app_pages_init, app_pages_init_with_page_size, app_pages_open, app_pages_open_with_page_size,
+};
+use svm_storage::traits::{PagesStorage, StateAwarePagesStorage};
+
+/// The offset of page `0` content, under a snapshot of an app having 3 pages (whose page `0` is non-zero)
+const PAGE0_OFFSET: usize = 65 + 3 * 32 + 2 + 6;
+
+/// Commits a state whose pages `0` and `2` are non-zero pages (page `1` is a zero page).
+fn commit_state() -> (Address, Rc<RefCell<MemKVStore>>, State) {
+    let (addr, kv, mut pages) = app_pages_init("my-app", 3);
+
+    let mut page2 = zero_page(PAGE_SIZE);
+    page2[100] = 70;
+
+    pages.write_page(PageIndex(0), &[10, 20, 30]);
+    pages.write_page(PageIndex(2), &page2);
+    pages.commit().unwrap();
+
+    let state = pages.get_state();
+
+    (addr, kv, state)
+}
+
+fn export(addr: &Address, state: &State, kv: &Rc<RefCell<MemKVStore>>) -> Vec<u8> {
+    let mut pages = app_pages_open(addr, state, kv, 3);
+    let mut out = Vec::new();
+
+    snapshot::export_snapshot::<DefaultPageHasher, DefaultStateHasher, _, _>(
+        addr, 3, &mut pages, &mut out,
+    )
+    .unwrap();
+
+    out
+}
+
+fn import(snapshot: &[u8], kv: &Rc<RefCell<MemKVStore>>) -> Result<SnapshotHeader, SnapshotError> {
+    let mut input = snapshot;
+
+    snapshot::import_snapshot::<_, DefaultPageHasher, DefaultStateHasher, _>(
+        &mut input,
+        kv,
+        PageCodec::ZeroRun,
+    )
+}
+
+#[test]
+fn snapshot_export_then_import() {
+    let (addr, kv, state) = commit_state();
+
+    let snapshot = export(&addr, &state, &kv);
+
+    let other_kv = Rc::new(RefCell::new(MemKVStore::new()));
+    let header = import(&snapshot, &other_kv).unwrap();
+
+    let expected = SnapshotHeader {
+        addr: addr.clone(),
+        state: state.clone(),
+        page_count: 3,
+        page_size: PAGE_SIZE,
+        page_hasher_id: 1,
+        state_hasher_id: 2,
+    };
+    assert_eq!(expected, header);
+
+    let mut pages = app_pages_open(&addr, &state, &other_kv, 3);
+    assert_eq!(state, pages.get_state());
+    assert!(pages.check_integrity().unwrap().is_ok());
+
+    let mut page2 = zero_page(PAGE_SIZE);
+    page2[100] = 70;
+
+    assert_eq!(Some(vec![10, 20, 30]), pages.read_page(PageIndex(0)));
+    assert_eq!(None, pages.read_page(PageIndex(1)));
+    assert_eq!(Some(page2), pages.read_page(PageIndex(2)));
+
+    // zero pages aren't part of the snapshot
+    assert_eq!(PAGE0_OFFSET + 3 + 6 + 4096, snapshot.len());
+
+    // exporting the imported state yields the same snapshot
+    assert_eq!(snapshot, export(&addr, &state, &other_kv));
+}
+
+#[test]
+fn snapshot_of_initial_state() {
+    let (addr, kv, _pages) = app_pages_init("my-app", 3);
+
+    let snapshot = export(&addr, &State::empty(), &kv);
+
+    let other_kv = Rc::new(RefCell::new(MemKVStore::new()));
+    let header = import(&snapshot, &other_kv).unwrap();
+
+    assert_eq!(State::empty(), header.state);
+    assert_eq!(0, other_kv.borrow().keys().count());
+}
+
+#[test]
+fn snapshot_import_rejects_tampered_page() {
+    let (addr, kv, state) = commit_state();
+
+    let mut snapshot = export(&addr, &state, &kv);
+    snapshot[PAGE0_OFFSET + 1] = 21;
+
+    let other_kv = Rc::new(RefCell::new(MemKVStore::new()));
+
+    assert_eq!(
+        Err(SnapshotError::PageMismatch(PageIndex(0))),
+        import(&snapshot, &other_kv)
+    );
+
+    // nothing has been committed
+    assert_eq!(0, other_kv.borrow().keys().count());
+}
+
+#[test]
+fn snapshot_import_rejects_state_mismatch() {
+    let (addr, kv, state) = commit_state();
+
+    let mut snapshot = export(&addr, &state, &kv);
+
+    // the state resides right after the magic, version and address
+    snapshot[25] ^= 1;
+
+    let other_kv = Rc::new(RefCell::new(MemKVStore::new()));
+
+    match import(&snapshot, &other_kv) {
+        Err(SnapshotError::StateMismatch { actual, .. }) => assert_eq!(state, actual),
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    assert_eq!(0, other_kv.borrow().keys().count());
+}
+
+#[test]
+fn snapshot_import_rejects_other_hashers() {
+    let (addr, kv, state) = commit_state();
+
+    let snapshot = export(&addr, &state, &kv);
+
+    let other_kv = Rc::new(RefCell::new(MemKVStore::new()));
+    let mut input = &snapshot[..];
+
+    let res = snapshot::import_snapshot::<_, DefaultPageHasher, FlatStateHasher, _>(
+        &mut input,
+        &other_kv,
+        PageCodec::NoOp,
+    );
+
+    let expected = SnapshotError::HasherMismatch {
+        snapshot: (1, 2),
+        expected: (1, 1),
+    };
+    assert_eq!(Err(expected), res);
+}
+
+#[test]
+fn snapshot_import_rejects_malformed_input() {
+    let (addr, kv, state) = commit_state();
+
+    let snapshot = export(&addr, &state, &kv);
+    let other_kv = Rc::new(RefCell::new(MemKVStore::new()));
+
+    // truncated
+    match import(&snapshot[..snapshot.len() - 1], &other_kv) {
+        Err(SnapshotError::Io(..)) => (),
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    // bad magic
+    let mut bad = snapshot.clone();
+    bad[0] = b'X';
+
+    match import(&bad, &other_kv) {
+        Err(SnapshotError::InvalidFormat(..)) => (),
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    // page index out of range
+    let mut bad = snapshot.clone();
+    bad[PAGE0_OFFSET - 5] = 3;
+
+    match import(&bad, &other_kv) {
+        Err(SnapshotError::InvalidFormat(..)) => (),
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    assert_eq!(0, other_kv.borrow().keys().count());
+}
+
+#[test]
+fn snapshot_of_non_default_page_size() {
+    let (addr, _kv, mut pages) = app_pages_init_with_page_size("my-app", 3, 256);
+
+    pages.write_page(PageIndex(1), &[10, 20, 30]);
+    pages.commit().unwrap();
+
+    let state = pages.get_state();
+    let mut out = Vec::new();
+
+    snapshot::export_snapshot::<DefaultPageHasher, DefaultStateHasher, _, _>(
+        &addr, 3, &mut pages, &mut out,
+    )
+    .unwrap();
+
+    let other_kv = Rc::new(RefCell::new(MemKVStore::new()));
+    let header = import(&out, &other_kv).unwrap();
+
+    assert_eq!(256, header.page_size);
+
+    let mut pages = app_pages_open_with_page_size(&addr, &state, &other_kv, 3, 256);
+
+    assert!(pages.check_integrity().unwrap().is_ok());
+    assert_eq!(Some(vec![10, 20, 30]), pages.read_page(PageIndex(1)));
+}